@@ -1,8 +1,5 @@
-#![cfg_attr(feature = "simd", feature(portable_simd))]
-
-use gf::GF;
-
-use rand::Rng;
+use gf::{shamir, GF};
+use rand::RngCore;
 
 const _LOGTABLE: [usize; 256] = [
     512, 255, 1, 25, 2, 50, 26, 198, 3, 223, 51, 238, 27, 104, 199, 75, 4, 100, 224, 14, 52, 141,
@@ -64,93 +61,17 @@ const _ALOGTABLE: [u8; 1025] = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
 ];
 
-// FIXME: decreases performance by 10x
-#[cfg(feature = "simd")]
-#[inline]
-fn poly(input: &[u8], x: u8) -> u8 {
-    use core::simd::Simd;
-
-    const SELECT: Simd<usize, 32> = Simd::from_array([
-        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
-        25, 26, 27, 28, 29, 30, 31,
-    ]);
-
-    // max input len is 32 bytes
-
-    let vals = Simd::gather_or_default(input, SELECT);
-
-    (GF::splat(x).pow(SELECT) * GF(vals)).sum_lanes()
-}
-
-#[cfg(not(feature = "simd"))]
-#[inline]
-fn poly(input: &[u8], x: u8) -> u8 {
-    // x^n * inp[n] + x^n-1 * inp[n-1] + ... + x^0 * inp[0]
-    input
-        .iter()
-        .enumerate()
-        .map(|(i, b)| GF(x).pow(i) * GF(*b))
-        .sum::<GF<u8>>()
-        .into()
-}
-
-pub fn share(
-    data: impl AsRef<[u8]>,
-    threshold: u8,
-    mut rng: impl rand::RngCore + rand::CryptoRng,
-) -> impl Iterator<Item = (u8, Box<[u8]>)> {
-    let mut buf = vec![0u8; data.as_ref().len() * threshold as usize].into_boxed_slice();
-
-    rng.fill_bytes(&mut buf);
-
-    buf.chunks_mut(threshold as usize)
-        .zip(data.as_ref())
-        .for_each(|(chunk, byte)| {
-            chunk[0] = *byte;
-        });
-
-    (1..).map(move |x| {
-        (
-            x,
-            buf.chunks(threshold as usize)
-                .map(|chunk| poly(chunk, x))
-                .collect(),
-        )
-    })
-}
-
-/// the formula:
-/// ${\displaystyle f(0)=\sum _{j=0}^{k-1}y_{j}\prod _{\begin{smallmatrix}m\,=\,0\\m\,\neq \,j\end{smallmatrix}}^{k-1}{\frac {x_{m}}{x_{m}-x_{j}}}}$
-fn interpolate(shares: impl Iterator<Item = (u8, u8)> + Clone) -> u8 {
-    shares
-        .clone()
-        .enumerate()
-        .map(|(i, (xi, share))| {
-            GF(share)
-                * shares
-                    .clone()
-                    .enumerate()
-                    .filter(|(j, _)| *j != i)
-                    .map(|(_, (xj, _))| GF(xj) / (GF(xi) - GF(xj)))
-                    .product::<GF<u8>>()
-        })
-        .sum::<GF<u8>>()
-        .into()
-}
-
-pub fn reconstruct(shares: impl Iterator<Item = (u8, impl AsRef<[u8]>)> + Clone) -> Box<[u8]> {
-    let first_len = shares.clone().next().unwrap().1.as_ref().len();
-    (0..first_len)
-        .map(|i| interpolate(shares.clone().map(|(x, bytes)| (x, bytes.as_ref()[i]))))
-        .collect()
-}
-
 fn main() {
-    let data = "hello world";
-    let shares = share(data, 4, rand::thread_rng())
-        .take(5)
-        .collect::<Vec<_>>();
-
-    let reconstructed = reconstruct(shares.iter().map(|(c, x)| (*c, x)));
-    assert_eq!(&*reconstructed, data.as_bytes());
+    let data = GF::from_slice(b"hello world");
+    let mut rng = rand::thread_rng();
+    let mut shares = shamir::split(data, 4, 7, |buf| rng.fill_bytes(buf));
+
+    let reconstructed = shamir::combine(&shares[..4]).unwrap();
+    assert_eq!(reconstructed, data);
+
+    // one cheating shareholder is outvoted and named
+    shares[2].data[0] += GF(0xFF);
+    let (reconstructed, faulty) = shamir::combine_robust(&shares, 4).unwrap();
+    assert_eq!(reconstructed, data);
+    assert_eq!(faulty, [GF(3)]);
 }
@@ -0,0 +1,425 @@
+//! # Additive FFT
+//!
+//! The Lin–Chung–Han transform over binary fields. A polynomial given in the
+//! *novel polynomial basis*
+//!
+//! `X_i(x) = prod_{bit j of i set} W_j(x)`
+//!
+//! with `W_j` the subspace polynomial of `span(v_0, .., v_{j-1})`, normalized to
+//! `W_j(v_j) = 1`, is evaluated at all the points `w_i + shift` in
+//! `O(n log n)`, where `w_i` is the field element with bit pattern `i`.
+//!
+//! ```
+//! use gf::{additive_fft::AdditiveFft, Poly, GF};
+//!
+//! let fft = AdditiveFft::<GF<u8>>::new(3);
+//!
+//! let mut data = [1u8, 2, 3, 4, 0, 0, 0, 0].map(GF);
+//! let poly = Poly::new(data.to_vec());
+//!
+//! fft.to_novel(&mut data);
+//! fft.fft(&mut data, GF(0));
+//!
+//! for (i, y) in data.iter().enumerate() {
+//!     assert_eq!(*y, poly.eval(GF(i as u8)));
+//! }
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::BinaryField;
+
+/// Precomputed subspace polynomials for transforms of length `2^log_n`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AdditiveFft<F> {
+    log_n: u32,
+    /// `images[j][t]` is `W_j(v_t)`, enough to evaluate the linear map `W_j`
+    /// anywhere.
+    images: Vec<Vec<F>>,
+    /// `subspace[j][t]` is the coefficient of `x^(2^t)` in `W_j`.
+    subspace: Vec<Vec<F>>,
+}
+
+impl<F: BinaryField> AdditiveFft<F> {
+    /// # Panics
+    ///
+    /// Panics if `2^log_n` is larger than the field.
+    pub fn new(log_n: u32) -> Self {
+        assert!(log_n <= F::DEGREE, "transform larger than the field");
+
+        // unnormalized W_0(x) = x and W_{j+1}(x) = W_j(x)^2 + W_j(v_j) W_j(x)
+        let mut basis_images: Vec<F> = (0..F::DEGREE).map(|t| F::from_bits(1 << t)).collect();
+        let mut coeffs = vec![F::ONE];
+
+        let mut images = Vec::with_capacity(log_n as usize);
+        let mut subspace = Vec::with_capacity(log_n as usize);
+        for j in 0..log_n as usize {
+            let w_vj = basis_images[j];
+            let norm = w_vj.inv();
+            images.push(basis_images.iter().map(|&w| w * norm).collect());
+            subspace.push(coeffs.iter().map(|&c| c * norm).collect());
+
+            for w in &mut basis_images {
+                *w = *w * *w + w_vj * *w;
+            }
+            let mut next = vec![F::ZERO; coeffs.len() + 1];
+            for (t, &c) in coeffs.iter().enumerate() {
+                next[t + 1] += c * c;
+                next[t] += w_vj * c;
+            }
+            coeffs = next;
+        }
+
+        Self {
+            log_n,
+            images,
+            subspace,
+        }
+    }
+
+    #[inline]
+    pub fn log_len(&self) -> u32 {
+        self.log_n
+    }
+
+    /// Transform length `2^log_n`.
+    #[inline]
+    pub fn size(&self) -> usize {
+        1 << self.log_n
+    }
+
+    /// `W_j` at the element with the given bits.
+    fn subspace_eval(&self, j: usize, mut bits: u32) -> F {
+        let mut acc = F::ZERO;
+        let mut t = 0;
+        while bits != 0 {
+            if bits & 1 == 1 {
+                acc += self.images[j][t];
+            }
+            bits >>= 1;
+            t += 1;
+        }
+        acc
+    }
+
+    /// Evaluates the novel basis coefficients in `data` at `w_i + shift`,
+    /// in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is not of the transform length.
+    pub fn fft(&self, data: &mut [F], shift: F) {
+        assert_eq!(data.len(), self.size(), "wrong transform length");
+
+        for j in (0..self.log_n as usize).rev() {
+            let half = 1 << j;
+            for (b, block) in data.chunks_mut(2 * half).enumerate() {
+                let skew = self.subspace_eval(j, (b * 2 * half) as u32 ^ shift.to_bits());
+                let (lo, hi) = block.split_at_mut(half);
+                F::mul_add_slice(lo, hi, skew);
+                for (h, &l) in hi.iter_mut().zip(lo.iter()) {
+                    *h += l;
+                }
+            }
+        }
+    }
+
+    /// Inverse of [`fft`](Self::fft).
+    pub fn ifft(&self, data: &mut [F], shift: F) {
+        assert_eq!(data.len(), self.size(), "wrong transform length");
+
+        for j in 0..self.log_n as usize {
+            let half = 1 << j;
+            for (b, block) in data.chunks_mut(2 * half).enumerate() {
+                let skew = self.subspace_eval(j, (b * 2 * half) as u32 ^ shift.to_bits());
+                let (lo, hi) = block.split_at_mut(half);
+                for (h, &l) in hi.iter_mut().zip(lo.iter()) {
+                    *h -= l;
+                }
+                F::mul_add_slice(lo, hi, -skew);
+            }
+        }
+    }
+
+    /// [`fft`](Self::fft) where every point is a whole vector of elements,
+    /// such as an erasure code shard.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is not of the transform length or the vectors differ
+    /// in length.
+    pub fn fft_slices<S: AsMut<[F]>>(&self, data: &mut [S], shift: F) {
+        assert_eq!(data.len(), self.size(), "wrong transform length");
+
+        for j in (0..self.log_n as usize).rev() {
+            let half = 1 << j;
+            for (b, block) in data.chunks_mut(2 * half).enumerate() {
+                let skew = self.subspace_eval(j, (b * 2 * half) as u32 ^ shift.to_bits());
+                let (lo, hi) = block.split_at_mut(half);
+                for (l, h) in lo.iter_mut().zip(hi) {
+                    let (l, h) = (l.as_mut(), h.as_mut());
+                    F::mul_add_slice(l, h, skew);
+                    add_slice(h, l);
+                }
+            }
+        }
+    }
+
+    /// [`ifft`](Self::ifft) where every point is a whole vector of elements.
+    pub fn ifft_slices<S: AsMut<[F]>>(&self, data: &mut [S], shift: F) {
+        assert_eq!(data.len(), self.size(), "wrong transform length");
+
+        for j in 0..self.log_n as usize {
+            let half = 1 << j;
+            for (b, block) in data.chunks_mut(2 * half).enumerate() {
+                let skew = self.subspace_eval(j, (b * 2 * half) as u32 ^ shift.to_bits());
+                let (lo, hi) = block.split_at_mut(half);
+                for (l, h) in lo.iter_mut().zip(hi) {
+                    let (l, h) = (l.as_mut(), h.as_mut());
+                    add_slice(h, l);
+                    F::mul_add_slice(l, h, -skew);
+                }
+            }
+        }
+    }
+
+    /// Formal derivative of a polynomial in the novel basis, in place.
+    ///
+    /// `W_j` is linearized, so its derivative is the constant coefficient
+    /// `c_j` of `x`, and `X_i' = sum_{bit j of i set} c_j X_{i - 2^j}`.
+    pub fn derivative(&self, coeffs: &mut [F]) {
+        assert_eq!(coeffs.len(), self.size(), "wrong transform length");
+
+        // every index only adds to lower ones, so ascending order reads each
+        // coefficient before anything is added to it
+        coeffs[0] = F::ZERO;
+        for i in 1..coeffs.len() {
+            let d = core::mem::take(&mut coeffs[i]);
+            for j in (0..self.log_n as usize).filter(|j| i >> j & 1 == 1) {
+                coeffs[i - (1 << j)] += self.subspace[j][0] * d;
+            }
+        }
+    }
+
+    /// [`derivative`](Self::derivative) where every coefficient is a whole
+    /// vector of elements.
+    pub fn derivative_slices<S: AsMut<[F]>>(&self, coeffs: &mut [S]) {
+        assert_eq!(coeffs.len(), self.size(), "wrong transform length");
+
+        coeffs[0].as_mut().fill(F::ZERO);
+        for i in 1..coeffs.len() {
+            let (lower, rest) = coeffs.split_at_mut(i);
+            let d = rest[0].as_mut();
+            for j in (0..self.log_n as usize).filter(|j| i >> j & 1 == 1) {
+                F::mul_add_slice(lower[i - (1 << j)].as_mut(), d, self.subspace[j][0]);
+            }
+            d.fill(F::ZERO);
+        }
+    }
+
+    /// Converts monomial coefficients to the novel basis, in place, with
+    /// `O(n log^2 n)` operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `coeffs` is not of the transform length.
+    pub fn to_novel(&self, coeffs: &mut [F]) {
+        assert_eq!(coeffs.len(), self.size(), "wrong transform length");
+
+        // f = f_lo + W_j f_hi on every block, by division through the sparse W_j
+        for j in (0..self.log_n as usize).rev() {
+            let half = 1 << j;
+            let w = &self.subspace[j];
+            let lead_inv = w[j].inv();
+            for block in coeffs.chunks_mut(2 * half) {
+                for i in (half..2 * half).rev() {
+                    let q = block[i] * lead_inv;
+                    block[i] = q;
+                    for (t, &c) in w[..j].iter().enumerate() {
+                        block[i - half + (1 << t)] -= q * c;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Inverse of [`to_novel`](Self::to_novel).
+    pub fn from_novel(&self, coeffs: &mut [F]) {
+        assert_eq!(coeffs.len(), self.size(), "wrong transform length");
+
+        for j in 0..self.log_n as usize {
+            let half = 1 << j;
+            let w = &self.subspace[j];
+            for block in coeffs.chunks_mut(2 * half) {
+                for i in half..2 * half {
+                    let q = block[i];
+                    for (t, &c) in w[..j].iter().enumerate() {
+                        block[i - half + (1 << t)] += q * c;
+                    }
+                    block[i] = q * w[j];
+                }
+            }
+        }
+    }
+}
+
+fn add_slice<F: BinaryField>(dst: &mut [F], src: &[F]) {
+    for (d, &s) in dst.iter_mut().zip(src) {
+        *d += s;
+    }
+}
+
+/// Polynomial product by evaluation on a subspace, `None` if the product does
+/// not fit into the field.
+pub(crate) fn transform_mul<F: BinaryField>(a: &[F], b: &[F]) -> Option<Vec<F>> {
+    let len = a.len() + b.len() - 1;
+    let log_n = len.next_power_of_two().trailing_zeros();
+    if log_n > F::DEGREE {
+        return None;
+    }
+
+    let fft = AdditiveFft::new(log_n);
+    let mut fa = vec![F::ZERO; fft.size()];
+    let mut fb = vec![F::ZERO; fft.size()];
+    fa[..a.len()].copy_from_slice(a);
+    fb[..b.len()].copy_from_slice(b);
+
+    for f in [&mut fa, &mut fb] {
+        fft.to_novel(f);
+        fft.fft(f, F::ZERO);
+    }
+    for (x, &y) in fa.iter_mut().zip(&fb) {
+        *x *= y;
+    }
+    fft.ifft(&mut fa, F::ZERO);
+    fft.from_novel(&mut fa);
+
+    fa.truncate(len);
+    Some(fa)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::{transform_mul, AdditiveFft};
+    use crate::{BinaryField, Field, GF2m, Poly, GF};
+
+    type GF65536 = GF2m<0x1002D>;
+
+    fn check_fft<F: BinaryField>(log_n: u32, shift: F) {
+        let fft = AdditiveFft::<F>::new(log_n);
+        let coeffs: Vec<F> = (0..fft.size() as u32)
+            .map(|i| F::from_bits((i * 0x9E37 + 11) & ((1 << F::DEGREE) - 1)))
+            .collect();
+        let poly = Poly::new(coeffs.clone());
+
+        let mut data = coeffs.clone();
+        fft.to_novel(&mut data);
+        let novel = data.clone();
+        fft.fft(&mut data, shift);
+
+        for (i, &y) in data.iter().enumerate() {
+            assert_eq!(y, poly.eval(F::from_bits(i as u32) + shift));
+        }
+
+        fft.ifft(&mut data, shift);
+        assert_eq!(data, novel);
+        fft.from_novel(&mut data);
+        assert_eq!(data, coeffs);
+    }
+
+    #[test]
+    fn fft_gf256() {
+        check_fft(8, GF(0u8));
+        check_fft(5, GF(0xA0u8));
+    }
+
+    #[test]
+    fn fft_gf65536() {
+        check_fft(10, GF65536::ZERO);
+        check_fft(6, GF65536::new(0x1240));
+    }
+
+    #[test]
+    fn fft_slices() {
+        let fft = AdditiveFft::<GF<u8>>::new(4);
+        let mut shards: Vec<Vec<GF<u8>>> = (0..16u8)
+            .map(|i| vec![GF(i), GF(i.wrapping_mul(37)), GF(255 - i)])
+            .collect();
+        let original = shards.clone();
+
+        fft.fft_slices(&mut shards, GF(0x30));
+        for col in 0..3 {
+            let mut data: Vec<_> = original.iter().map(|s| s[col]).collect();
+            fft.fft(&mut data, GF(0x30));
+            assert!(shards.iter().zip(&data).all(|(s, &d)| s[col] == d));
+        }
+
+        fft.ifft_slices(&mut shards, GF(0x30));
+        assert_eq!(shards, original);
+    }
+
+    #[test]
+    fn derivative() {
+        let fft = AdditiveFft::<GF65536>::new(7);
+        let coeffs: Vec<_> = (0..128u16).map(|i| GF65536::new(i * 411 + 5)).collect();
+        let expected = Poly::new(coeffs.clone()).derivative();
+
+        let mut data = coeffs.clone();
+        fft.to_novel(&mut data);
+        fft.derivative(&mut data);
+        fft.from_novel(&mut data);
+        assert_eq!(Poly::new(data), expected);
+
+        let mut slices: Vec<_> = coeffs.iter().map(|&c| vec![c, c + GF65536::ONE]).collect();
+        let mut novel: Vec<_> = coeffs.clone();
+        fft.to_novel(&mut novel);
+        for (s, &n) in slices.iter_mut().zip(&novel) {
+            s[0] = n;
+        }
+        fft.derivative(&mut novel);
+        fft.derivative_slices(&mut slices);
+        assert!(slices.iter().zip(&novel).all(|(s, &n)| s[0] == n));
+    }
+
+    fn schoolbook<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+        let mut out = vec![F::ZERO; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                out[i + j] += x * y;
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn mul() {
+        let a: Vec<_> = (0..300u32)
+            .map(|i| GF65536::new((i * i + 3) as u16))
+            .collect();
+        let b: Vec<_> = (0..200u32)
+            .map(|i| GF65536::new((7 * i + 1) as u16))
+            .collect();
+        assert_eq!(transform_mul(&a, &b).unwrap(), schoolbook(&a, &b));
+
+        let a: Vec<_> = (0..100u8).map(|i| GF(i.wrapping_mul(3))).collect();
+        let b: Vec<_> = (0..120u8).map(|i| GF(i ^ 0x5A)).collect();
+        assert_eq!(transform_mul(&a, &b).unwrap(), schoolbook(&a, &b));
+        assert!(transform_mul(&[GF(1u8); 200], &[GF(1u8); 100]).is_none());
+    }
+
+    #[test]
+    fn poly_mul() {
+        let a: Vec<_> = (0..1000u32)
+            .map(|i| GF65536::new((i * 5 + 9) as u16))
+            .collect();
+        let b: Vec<_> = (0..700u32)
+            .map(|i| GF65536::new((i ^ 0xBEEF) as u16))
+            .collect();
+        assert_eq!(
+            Poly::new(a.clone()) * Poly::new(b.clone()),
+            Poly::new(schoolbook(&a, &b))
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+
+use crate::Field;
+
+/// Inverts every element of `elems` in place.
+///
+/// Uses Montgomery's trick: a single call to [`Field::inv`] plus `3(n - 1)`
+/// multiplications, which pays off whenever `inv` is much more expensive than
+/// `mul`.
+///
+/// ```
+/// use gf::{batch_inv, GF};
+///
+/// let mut bytes = [1u8, 2, 3, 200];
+/// batch_inv(GF::from_slice_mut(&mut bytes));
+///
+/// assert_eq!(GF(bytes[3]), GF(200u8).inv());
+/// ```
+///
+/// # Panics
+///
+/// Panics if any element is zero, see [`batch_inv_or_zero`].
+pub fn batch_inv<F: Field>(elems: &mut [F]) {
+    assert!(
+        elems.iter().all(|e| !e.is_zero()),
+        "attempt to divide by zero"
+    );
+    batch_inv_or_zero(elems)
+}
+
+/// Inverts every nonzero element of `elems` in place, zeros are left as they are.
+///
+/// ```
+/// use gf::{batch_inv_or_zero, GF};
+///
+/// let mut elems = [GF(5u8), GF(0), GF(9)];
+/// batch_inv_or_zero(&mut elems);
+///
+/// assert_eq!(elems, [GF(5).inv(), GF(0), GF(9).inv()]);
+/// ```
+pub fn batch_inv_or_zero<F: Field>(elems: &mut [F]) {
+    // prefix[i] is the product of all nonzero elements in elems[..=i]
+    let mut prefix = Vec::with_capacity(elems.len());
+    let mut acc = None;
+    for e in elems.iter() {
+        if !e.is_zero() {
+            acc = Some(acc.map_or(*e, |acc| acc * *e));
+        }
+        prefix.push(acc.unwrap_or(F::ONE));
+    }
+
+    let Some(acc) = acc else {
+        return;
+    };
+    let first = elems.iter().position(|e| !e.is_zero()).unwrap_or(0);
+
+    let mut inv = acc.inv();
+    for i in (first + 1..elems.len()).rev() {
+        let e = elems[i];
+        if !e.is_zero() {
+            elems[i] = inv * prefix[i - 1];
+            inv *= e;
+        }
+    }
+    elems[first] = inv;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{batch_inv, batch_inv_or_zero};
+    use crate::GF;
+
+    #[test]
+    fn inv_all() {
+        let mut elems: [u8; 255] = core::array::from_fn(|i| i as u8 + 1);
+        batch_inv(GF::from_slice_mut(&mut elems));
+
+        for (i, e) in elems.iter().enumerate() {
+            assert_eq!(GF(*e) * GF(i as u8 + 1), GF(1));
+        }
+    }
+
+    #[test]
+    fn inv_empty() {
+        batch_inv::<GF<u8>>(&mut []);
+    }
+
+    #[test]
+    fn inv_or_zero() {
+        let mut elems = [GF(0u8), GF(3), GF(0), GF(0), GF(77), GF(0)];
+        batch_inv_or_zero(&mut elems);
+
+        assert_eq!(
+            elems,
+            [GF(0), GF(3).inv(), GF(0), GF(0), GF(77).inv(), GF(0)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn inv_zero() {
+        batch_inv(&mut [GF(1u8), GF(0)]);
+    }
+}
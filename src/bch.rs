@@ -0,0 +1,279 @@
+//! # Binary BCH Codes
+//!
+//! Narrow-sense primitive BCH codes of length `n = 2^m - 1` over bits, built
+//! over the field `F` of degree `m`. The generator is the least common
+//! multiple of the minimal polynomials of `alpha, ..., alpha^(2t)`, so every
+//! codeword corrects up to `t` bit errors. Shorter messages give shortened
+//! codes with the same parity.
+//!
+//! Codewords are bit slices with the highest power first, the message followed
+//! by the parity bits. Some primitive polynomials for `m` from 5 to 16:
+//!
+//! | m | `POLY`   | m  | `POLY`    |
+//! |---|----------|----|-----------|
+//! | 5 | `0x25`   | 11 | `0x805`   |
+//! | 6 | `0x43`   | 12 | `0x1053`  |
+//! | 7 | `0x89`   | 13 | `0x201B`  |
+//! | 8 | `0x11D`  | 14 | `0x4443`  |
+//! | 9 | `0x211`  | 15 | `0x8003`  |
+//! | 10| `0x409`  | 16 | `0x1002D` |
+//!
+//! ```
+//! use gf::{bch::Bch, GF2m};
+//!
+//! // BCH(1023, 923) correcting 10 errors
+//! let code = Bch::<GF2m<0x409>>::new(10);
+//! assert_eq!((code.n(), code.k()), (1023, 923));
+//!
+//! let message: Vec<bool> = (0..500).map(|i| i % 3 == 0).collect();
+//! let mut codeword = message.clone();
+//! codeword.extend(code.encode(&message));
+//!
+//! for i in [0, 17, 123, 499, 550] {
+//!     codeword[i] = !codeword[i];
+//! }
+//! assert_eq!(code.decode(&mut codeword), Ok(5));
+//! assert_eq!(codeword[..500], message[..]);
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{
+    reed_solomon::{berlekamp_massey, Error},
+    BinaryField, Poly,
+};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Bch<F> {
+    t: usize,
+    /// the generator polynomial, highest power first, without the leading one
+    generator: Vec<bool>,
+    field: PhantomData<F>,
+}
+
+impl<F: BinaryField> Bch<F> {
+    /// # Panics
+    ///
+    /// Panics if `t` is zero or the generator would fill the whole code.
+    pub fn new(t: usize) -> Self {
+        let n = (1usize << F::DEGREE) - 1;
+        assert!(t >= 1 && 2 * t < n, "t out of range");
+
+        // one minimal polynomial per cyclotomic coset, the product of
+        // (x - alpha^j) over the coset of i
+        let mut covered = vec![false; n];
+        let mut generator = Poly::one();
+        for i in 1..=2 * t {
+            if covered[i] {
+                continue;
+            }
+            let mut j = i;
+            loop {
+                covered[j] = true;
+                generator *= Poly::new([F::exp(j), F::ONE]);
+                j = 2 * j % n;
+                if j == i {
+                    break;
+                }
+            }
+        }
+
+        let parity = generator.degree().unwrap();
+        assert!(parity < n, "t out of range");
+        let generator = generator.coeffs()[..parity]
+            .iter()
+            .rev()
+            .map(|c| *c == F::ONE)
+            .collect();
+
+        Self {
+            t,
+            generator,
+            field: PhantomData,
+        }
+    }
+
+    /// Number of correctable bit errors.
+    #[inline]
+    pub fn t(&self) -> usize {
+        self.t
+    }
+
+    /// Length of the full code, `2^m - 1`.
+    #[inline]
+    pub fn n(&self) -> usize {
+        (1 << F::DEGREE) - 1
+    }
+
+    /// Maximum message length.
+    #[inline]
+    pub fn k(&self) -> usize {
+        self.n() - self.parity_len()
+    }
+
+    #[inline]
+    pub fn parity_len(&self) -> usize {
+        self.generator.len()
+    }
+
+    /// Coefficients of the generator polynomial, highest first.
+    pub fn generator(&self) -> Vec<bool> {
+        let mut g = vec![true];
+        g.extend(&self.generator);
+        g
+    }
+
+    /// The parity bits for `message`, the remainder of `message * x^parity`
+    /// divided by the generator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `message` is longer than [`k`](Self::k).
+    pub fn encode(&self, message: &[bool]) -> Vec<bool> {
+        assert!(message.len() <= self.k(), "message too long");
+
+        let mut rem = vec![false; self.parity_len()];
+        for &m in message {
+            let feedback = m ^ rem[0];
+            rem.rotate_left(1);
+            *rem.last_mut().unwrap() = false;
+            if feedback {
+                for (r, &g) in rem.iter_mut().zip(&self.generator) {
+                    *r ^= g;
+                }
+            }
+        }
+        rem
+    }
+
+    /// `codeword(alpha^i)` for `i` in `1..=2t`.
+    pub fn syndromes(&self, codeword: &[bool]) -> Vec<F> {
+        let n = codeword.len();
+        let mut syndromes = vec![F::ZERO; 2 * self.t];
+        for (j, _) in codeword.iter().enumerate().filter(|(_, &b)| b) {
+            let power = n - 1 - j;
+            for (i, s) in syndromes.iter_mut().enumerate() {
+                *s += F::exp(power * (i + 1));
+            }
+        }
+        syndromes
+    }
+
+    /// Corrects `codeword` in place and returns the number of flipped bits.
+    /// On failure `codeword` is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `codeword` is longer than [`n`](Self::n).
+    pub fn decode(&self, codeword: &mut [bool]) -> Result<usize, Error> {
+        let n = codeword.len();
+        assert!(n <= self.n(), "codeword too long");
+
+        let syndromes = self.syndromes(codeword);
+        if syndromes.iter().all(F::is_zero) {
+            return Ok(0);
+        }
+
+        let locator = berlekamp_massey(&syndromes, Poly::one(), 0);
+        let degree = locator.degree().unwrap_or(0);
+        if degree > self.t {
+            return Err(Error::TooManyErrors);
+        }
+
+        // Chien search, bit j is wrong if alpha^-(n - 1 - j) is a root
+        let order = self.n();
+        let positions: Vec<usize> = (0..n)
+            .filter(|&j| locator.eval(F::exp(order - (n - 1 - j))).is_zero())
+            .collect();
+        if positions.len() != degree {
+            return Err(Error::TooManyErrors);
+        }
+
+        for &j in &positions {
+            codeword[j] = !codeword[j];
+        }
+        Ok(positions.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::Bch;
+    use crate::{reed_solomon::Error, GF2m};
+
+    fn bits(s: &str) -> Vec<bool> {
+        s.bytes().map(|b| b == b'1').collect()
+    }
+
+    #[test]
+    fn known_generators() {
+        // BCH(15, 7) and BCH(15, 5) over x^4 + x + 1
+        assert_eq!(Bch::<GF2m<0b10011>>::new(2).generator(), bits("111010001"));
+        assert_eq!(
+            Bch::<GF2m<0b10011>>::new(3).generator(),
+            bits("10100110111")
+        );
+        // BCH(31, 21) over x^5 + x^2 + 1
+        let code = Bch::<GF2m<0b100101>>::new(2);
+        assert_eq!(code.generator(), bits("11101101001"));
+        assert_eq!(code.k(), 21);
+    }
+
+    #[test]
+    fn table_polynomials_are_primitive() {
+        macro_rules! check {
+            ($($poly:literal),*) => {$(
+                assert_eq!(GF2m::<$poly>::GENERATOR, GF2m::new(2));
+            )*}
+        }
+        check!(
+            0x25, 0x43, 0x89, 0x11D, 0x211, 0x409, 0x805, 0x1053, 0x201B, 0x4443, 0x8003, 0x1002D
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        let code = Bch::<GF2m<0x1002D>>::new(8);
+        assert_eq!((code.n(), code.k()), (65535, 65535 - 16 * 8));
+        let code = Bch::<GF2m<0b100101>>::new(7);
+        assert_eq!(code.k(), 6);
+    }
+
+    #[test]
+    fn correct_up_to_t() {
+        let code = Bch::<GF2m<0x1002D>>::new(8);
+        let message: Vec<bool> = (0..4096).map(|i| (i * 7 + i / 5) % 3 == 0).collect();
+        let mut original = message.clone();
+        original.extend(code.encode(&message));
+        assert!(code.syndromes(&original).iter().all(|s| s.0 == 0));
+
+        let mut codeword = original.clone();
+        let errors = [0, 1, 100, 1000, 2000, 3000, 4095, 4096 + 127];
+        for &i in &errors {
+            codeword[i] = !codeword[i];
+        }
+        assert_eq!(code.decode(&mut codeword), Ok(8));
+        assert_eq!(codeword, original);
+    }
+
+    #[test]
+    fn too_many_errors() {
+        let code = Bch::<GF2m<0b100101>>::new(3);
+        let message = bits("1011001110110101");
+        let mut codeword = message.clone();
+        codeword.extend(code.encode(&message));
+
+        for i in [0, 3, 7, 20] {
+            codeword[i] = !codeword[i];
+        }
+        let damaged = codeword.clone();
+        // four errors either fail or land on a different codeword
+        match code.decode(&mut codeword) {
+            Err(Error::TooManyErrors) => assert_eq!(codeword, damaged),
+            Ok(_) => assert_ne!(codeword[..16], message[..]),
+        }
+    }
+}
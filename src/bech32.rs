@@ -0,0 +1,528 @@
+//! # Bech32 and Bech32m
+//!
+//! The checksummed base32 format of BIP-173 and BIP-350, used for segwit
+//! addresses and, with other checksums, by [codex32](crate::codex32). A
+//! string is a human-readable part, the separator `1`, and a data part of
+//! 5-bit values in the alphabet [`CHARSET`] ending in six checksum
+//! characters.
+//!
+//! The checksum is a BCH code over GF(32), `x^5 + x^3 + 1`, of degree 6,
+//! which detects any four errors in strings of up to 90 characters. Its
+//! generator has the roots `b^997, b^998, b^999` for a primitive `b` in
+//! GF(1024), so [`locate_errors`] finds up to two substitutions from three
+//! syndromes there, by the method of Bitcoin Core. That is all the code
+//! allows: its minimum distance is five, so three errors can be two
+//! substitutions away from another valid string and be read as two errors
+//! elsewhere. Three or four errors are detected but not located, and
+//! nothing is corrected: a user should retype the characters named, not
+//! trust a guess.
+//!
+//! ```
+//! use gf::bech32::{self, Variant};
+//!
+//! let address = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+//! let (hrp, data, variant) = bech32::decode(address).unwrap();
+//! assert_eq!((hrp.as_str(), data.len(), variant), ("bc", 33, Variant::Bech32));
+//! assert_eq!(bech32::encode(&hrp, &data, variant).unwrap(), address);
+//!
+//! let typo = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5ndq";
+//! assert!(bech32::decode(typo).is_err());
+//! assert_eq!(bech32::locate_errors(typo, Variant::Bech32), Ok(vec![39]));
+//! ```
+
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt;
+
+use crate::{Field, GF2m, Poly};
+
+/// GF(32) modulo `x^5 + x^3 + 1`, with characters as in [`CHARSET`].
+pub(crate) type GF32 = GF2m<0x29>;
+pub(crate) type GF1024 = GF2m<0x409>;
+
+/// The bech32 alphabet, with the character for `v` at index `v`.
+pub const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Longest string of BIP-173.
+const MAX_LEN: usize = 90;
+
+/// The image in [`GF1024`] of the root `x` of `x^5 + x^3 + 1`, which embeds
+/// GF(32) as `a_0 + a_1 x + ... + a_4 x^4 -> a_0 + a_1 y + ... + a_4 y^4`.
+const EMBEDDING: u16 = 90;
+
+/// `b` as a power of [`GF1024::GENERATOR`], and the inverse of that power
+/// modulo 1023 to take logarithms to the base `b`.
+const BETA: usize = 446;
+const BETA_INV: usize = 695;
+const FIRST_ROOT: usize = 997;
+
+pub(crate) fn embed(a: GF32) -> GF1024 {
+    let y = GF1024::new(EMBEDDING);
+    (0..5)
+        .filter(|i| a.0 >> i & 1 == 1)
+        .map(|i| y.pow(i))
+        .fold(GF1024::ZERO, |acc, p| acc + p)
+}
+
+/// The element of GF(32) embedded as `b`, if any.
+pub(crate) fn unembed(b: GF1024) -> Option<GF32> {
+    (0..32).map(GF32::new).find(|&a| embed(a) == b)
+}
+
+/// The value of a character in either case.
+pub(crate) fn from_char(c: u8) -> Option<GF32> {
+    CHARSET
+        .iter()
+        .position(|&x| x == c.to_ascii_lowercase())
+        .map(|v| GF32::new(v as u16))
+}
+
+pub(crate) fn to_char(a: GF32) -> char {
+    CHARSET[a.0 as usize] as char
+}
+
+/// A checksum given as in the `polymod` functions of the reference code,
+/// with 5 bits per symbol and the highest power first: the generator below
+/// its leading term, the starting residue, and the residue of valid data.
+pub(crate) struct Checksum {
+    pub(crate) len: usize,
+    pub(crate) generator: u128,
+    pub(crate) init: u128,
+    pub(crate) target: u128,
+}
+
+impl Checksum {
+    fn symbols(&self, packed: u128) -> impl DoubleEndedIterator<Item = GF32> + '_ {
+        (0..self.len)
+            .rev()
+            .map(move |i| GF32::new((packed >> (5 * i) & 31) as u16))
+    }
+
+    pub(crate) fn generator(&self) -> Poly<GF32> {
+        let mut coeffs: Vec<GF32> = self.symbols(self.generator).collect();
+        coeffs.reverse();
+        coeffs.push(GF32::ONE);
+        Poly::new(coeffs)
+    }
+
+    /// `init x^n + data` modulo the generator, for `n` data symbols.
+    pub(crate) fn residue(&self, data: &[GF32]) -> Poly<GF32> {
+        let coeffs: Vec<GF32> = self
+            .symbols(self.init)
+            .chain(data.iter().copied())
+            .rev()
+            .collect();
+        Poly::new(coeffs).div_rem(&self.generator()).1
+    }
+
+    pub(crate) fn target(&self) -> Poly<GF32> {
+        let mut coeffs: Vec<GF32> = self.symbols(self.target).collect();
+        coeffs.reverse();
+        Poly::new(coeffs)
+    }
+
+    /// The checksum symbols to append to `data`.
+    pub(crate) fn create(&self, data: &[GF32]) -> Vec<GF32> {
+        let mut shifted = data.to_vec();
+        shifted.resize(data.len() + self.len, GF32::ZERO);
+        let residue = &self.residue(&shifted) + &self.target();
+        (0..self.len).rev().map(|i| residue.coeff(i)).collect()
+    }
+
+    pub(crate) fn verify(&self, data: &[GF32]) -> bool {
+        self.residue(data) == self.target()
+    }
+}
+
+const BECH32: Checksum = Checksum {
+    len: 6,
+    generator: 0x3b6a57b2,
+    init: 1,
+    target: 1,
+};
+
+const BECH32M: Checksum = Checksum {
+    target: 0x2bc830a3,
+    ..BECH32
+};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Variant {
+    /// BIP-173, for segwit version 0.
+    Bech32,
+    /// BIP-350, for segwit version 1 and later.
+    Bech32m,
+}
+
+impl Variant {
+    fn checksum(self) -> &'static Checksum {
+        match self {
+            Variant::Bech32 => &BECH32,
+            Variant::Bech32m => &BECH32M,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    /// More than 90 characters, an empty human-readable part, or a data
+    /// part shorter than the checksum.
+    InvalidLength,
+    /// Upper and lower case letters in the same string.
+    MixedCase,
+    MissingSeparator,
+    /// A character outside `!` to `~` in the human-readable part, or
+    /// outside [`CHARSET`] in the data part, at this position.
+    InvalidCharacter {
+        position: usize,
+        character: char,
+    },
+    InvalidChecksum,
+    /// A checksum with more errors than can be located.
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength => "invalid length".fmt(f),
+            Error::MixedCase => "mixed upper and lower case".fmt(f),
+            Error::MissingSeparator => "missing separator".fmt(f),
+            Error::InvalidCharacter {
+                position,
+                character,
+            } => write!(f, "invalid character {character:?} at position {position}"),
+            Error::InvalidChecksum => "invalid checksum".fmt(f),
+            Error::TooManyErrors => "too many errors to locate".fmt(f),
+        }
+    }
+}
+
+/// The human-readable part expanded to the symbols the checksum covers,
+/// followed by the data part.
+fn checksummed(hrp: &str, data: &[GF32]) -> Vec<GF32> {
+    let hrp = hrp.as_bytes();
+    let mut values: Vec<GF32> = hrp
+        .iter()
+        .map(|&c| c.to_ascii_lowercase() >> 5)
+        .chain([0])
+        .chain(hrp.iter().map(|&c| c.to_ascii_lowercase() & 31))
+        .map(|v| GF32::new(v as u16))
+        .collect();
+    values.extend_from_slice(data);
+    values
+}
+
+fn check_hrp(hrp: &str) -> Result<(), Error> {
+    if hrp.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    match hrp.char_indices().find(|(_, c)| !('!'..='~').contains(c)) {
+        Some((position, character)) => Err(Error::InvalidCharacter {
+            position,
+            character,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// The human-readable part and the data part of `s`, checksum included.
+fn split(s: &str) -> Result<(&str, Vec<GF32>), Error> {
+    if s.len() > MAX_LEN {
+        return Err(Error::InvalidLength);
+    }
+    if s.bytes().any(|b| b.is_ascii_lowercase()) && s.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(Error::MixedCase);
+    }
+    let separator = s.rfind('1').ok_or(Error::MissingSeparator)?;
+    let hrp = &s[..separator];
+    check_hrp(hrp)?;
+
+    let data = s[separator + 1..]
+        .char_indices()
+        .map(|(i, character)| {
+            character
+                .is_ascii()
+                .then(|| from_char(character as u8))
+                .flatten()
+                .ok_or(Error::InvalidCharacter {
+                    position: separator + 1 + i,
+                    character,
+                })
+        })
+        .collect::<Result<Vec<GF32>, Error>>()?;
+    if data.len() < BECH32.len {
+        return Err(Error::InvalidLength);
+    }
+    Ok((hrp, data))
+}
+
+/// Encodes 5-bit values under `hrp`, in lower case.
+///
+/// # Panics
+///
+/// Panics if a value is not below 32.
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Error> {
+    check_hrp(hrp)?;
+    if hrp.len() + 1 + data.len() + BECH32.len > MAX_LEN {
+        return Err(Error::InvalidLength);
+    }
+    let mut values: Vec<GF32> = data.iter().map(|&v| GF32::new(v as u16)).collect();
+    let checksum = variant.checksum().create(&checksummed(hrp, &values));
+    values.extend(checksum);
+
+    let mut out = hrp.to_ascii_lowercase();
+    out.push('1');
+    out.extend(values.into_iter().map(to_char));
+    Ok(out)
+}
+
+/// The human-readable part in lower case, the 5-bit values without the
+/// checksum, and the variant whose checksum matches.
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), Error> {
+    let (hrp, data) = split(s)?;
+    let values = checksummed(hrp, &data);
+    let variant = [Variant::Bech32, Variant::Bech32m]
+        .into_iter()
+        .find(|v| v.checksum().verify(&values))
+        .ok_or(Error::InvalidChecksum)?;
+    let payload = data[..data.len() - BECH32.len]
+        .iter()
+        .map(|a| a.0 as u8)
+        .collect();
+    Ok((hrp.to_ascii_lowercase(), payload, variant))
+}
+
+/// The positions in `s` of the characters of the data part that are wrong,
+/// if at most two substitutions there explain the checksum of `variant`.
+/// Empty for a valid string.
+///
+/// Bitcoin Core's method, followed here, locates at most two substitutions.
+/// Three or four are only detected: the result is then
+/// [`TooManyErrors`](Error::TooManyErrors) or one or two positions that may
+/// be wrong, never an empty list.
+pub fn locate_errors(s: &str, variant: Variant) -> Result<Vec<usize>, Error> {
+    let (hrp, data) = split(s)?;
+    let checksum = variant.checksum();
+    let residue = &checksum.residue(&checksummed(hrp, &data)) - &checksum.target();
+    if residue.is_zero() {
+        return Ok(vec![]);
+    }
+
+    // the error polynomial E has the same value as the residue at each
+    // root, s_i = sum e_j X_j^(997 + i) with X_j = b^p_j for the error at
+    // power p_j, the position counted from the end
+    let beta = GF1024::exp(BETA);
+    let [s0, s1, s2] = [0, 1, 2].map(|i| {
+        let root = beta.pow(FIRST_ROOT + i);
+        residue
+            .coeffs()
+            .iter()
+            .rev()
+            .fold(GF1024::ZERO, |acc, &c| acc * root + embed(c))
+    });
+    let n = data.len();
+    let power = |x: GF1024| x.log().map(|l| l * BETA_INV % 1023);
+    let is_error = |e: GF1024| !e.is_zero() && unembed(e).is_some();
+    let position = |p: usize| s.len() - 1 - p;
+
+    // a single error: X = s1 / s0 = s2 / s1
+    if !s0.is_zero() {
+        let x = s1 / s0;
+        if s2 == s1 * x {
+            if let Some(p) = power(x).filter(|&p| p < n) {
+                if is_error(s0 / x.pow(FIRST_ROOT)) {
+                    return Ok(vec![position(p)]);
+                }
+            }
+        }
+    }
+
+    // two errors: for each X_1, s2 + s1 X_1 and s1 + s0 X_1 are
+    // e_2 X_2^998 (X_2 + X_1) and e_2 X_2^997 (X_2 + X_1), whose ratio is X_2
+    let mut found = None;
+    let mut x1 = GF1024::ONE;
+    for p1 in 0..n {
+        let a = s2 + s1 * x1;
+        let b = s1 + s0 * x1;
+        if !b.is_zero() {
+            let x2 = a / b;
+            if let Some(p2) = power(x2).filter(|&p2| p1 < p2 && p2 < n) {
+                let x2_root = x2.pow(FIRST_ROOT);
+                let e2 = b / (x2_root * (x2 + x1));
+                let e1 = (s0 + e2 * x2_root) / x1.pow(FIRST_ROOT);
+                if is_error(e1) && is_error(e2) {
+                    if found.is_some() {
+                        return Err(Error::TooManyErrors);
+                    }
+                    found = Some(vec![position(p2), position(p1)]);
+                }
+            }
+        }
+        x1 *= beta;
+    }
+    found.ok_or(Error::TooManyErrors)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn embedding_and_roots() {
+        let y = embed(GF32::new(2));
+        assert_eq!(y.pow(5) + y.pow(3), GF1024::ONE);
+        for a in 0..32 {
+            assert_eq!(unembed(embed(GF32::new(a))), Some(GF32::new(a)));
+            for b in 0..32 {
+                let (a, b) = (GF32::new(a), GF32::new(b));
+                assert_eq!(embed(a * b), embed(a) * embed(b));
+                assert_eq!(embed(a + b), embed(a) + embed(b));
+            }
+        }
+
+        let generator = BECH32.generator();
+        let beta = GF1024::exp(BETA);
+        for i in 0..3 {
+            let root = beta.pow(FIRST_ROOT + i);
+            let value = generator
+                .coeffs()
+                .iter()
+                .rev()
+                .fold(GF1024::ZERO, |acc, &c| acc * root + embed(c));
+            assert_eq!(value, GF1024::ZERO);
+        }
+        assert_eq!(BETA * BETA_INV % 1023, 1);
+    }
+
+    #[test]
+    fn vectors() {
+        // valid strings of BIP-173 and BIP-350
+        let bech32 = [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ];
+        let bech32m = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+        for (strings, variant) in [(bech32, Variant::Bech32), (bech32m, Variant::Bech32m)] {
+            for s in strings {
+                let (hrp, data, v) = decode(s).unwrap();
+                assert_eq!(v, variant);
+                assert_eq!(encode(&hrp, &data, variant).unwrap(), s.to_lowercase());
+                assert_eq!(locate_errors(s, variant), Ok(vec![]));
+            }
+        }
+        let (_, data, _) = decode("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw").unwrap();
+        assert_eq!(data, (0..32).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(decode("pzry9x0s0muk"), Err(Error::MissingSeparator));
+        assert_eq!(decode("1pzry9x0s0muk"), Err(Error::InvalidLength));
+        assert_eq!(decode("li1dgmt3"), Err(Error::InvalidLength));
+        assert_eq!(decode("A1G7SGD8"), Err(Error::InvalidChecksum));
+        assert_eq!(decode("A12uEL5L"), Err(Error::MixedCase));
+        assert_eq!(
+            decode("x1b4n0q5v"),
+            Err(Error::InvalidCharacter {
+                position: 2,
+                character: 'b'
+            })
+        );
+        assert_eq!(
+            decode("\u{7f}1axkwrx"),
+            Err(Error::InvalidCharacter {
+                position: 0,
+                character: '\u{7f}'
+            })
+        );
+        let long = "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx";
+        assert_eq!(decode(long), Err(Error::InvalidLength));
+        assert_eq!(encode("", &[], Variant::Bech32), Err(Error::InvalidLength));
+        assert_eq!(
+            encode("a", &[0; 83], Variant::Bech32m),
+            Err(Error::InvalidLength)
+        );
+        assert!(encode("a", &[0; 82], Variant::Bech32m).is_ok());
+    }
+
+    #[test]
+    fn locate() {
+        let mut rng = StdRng::seed_from_u64(0x9e37_79b9);
+        let mut random = || rng.next_u32() as usize;
+
+        for variant in [Variant::Bech32, Variant::Bech32m] {
+            for len in [0, 1, 20, 32, 53, 81] {
+                let data: Vec<u8> = (0..len).map(|_| (random() % 32) as u8).collect();
+                let s = encode("tb", &data, variant).unwrap();
+                for errors in 1..=2 {
+                    let mut chars: Vec<u8> = s.clone().into_bytes();
+                    let mut positions = Vec::new();
+                    while positions.len() < errors {
+                        let p = 3 + random() % (s.len() - 3);
+                        if !positions.contains(&p) {
+                            positions.push(p);
+                            let old = from_char(chars[p]).unwrap().0 as usize;
+                            chars[p] = CHARSET[(old + 1 + random() % 31) % 32];
+                        }
+                    }
+                    positions.sort_unstable();
+                    let wrong = String::from_utf8(chars).unwrap();
+                    assert_eq!(decode(&wrong), Err(Error::InvalidChecksum));
+                    let mut located = locate_errors(&wrong, variant).unwrap();
+                    located.sort_unstable();
+                    assert_eq!(located, positions);
+                }
+            }
+        }
+
+        // three or four errors are always detected, and may be mistaken for
+        // one or two elsewhere
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        for errors in [3, 4] {
+            for _ in 0..100 {
+                let mut chars: Vec<u8> = s.into();
+                let mut positions = Vec::new();
+                while positions.len() < errors {
+                    let p = 3 + random() % (s.len() - 3);
+                    if !positions.contains(&p) {
+                        positions.push(p);
+                        let old = from_char(chars[p]).unwrap().0 as usize;
+                        chars[p] = CHARSET[(old + 1 + random() % 31) % 32];
+                    }
+                }
+                let wrong = String::from_utf8(chars).unwrap();
+                assert_eq!(decode(&wrong), Err(Error::InvalidChecksum));
+                assert_ne!(locate_errors(&wrong, Variant::Bech32), Ok(vec![]));
+            }
+        }
+    }
+
+    #[test]
+    fn beyond_two() {
+        // the two strings differ in the five characters at 9, 11, 31, 36 and
+        // 38, so three errors toward the second are read as two at 36 and 38
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        let t = "bc1qar0srjrqxfkvy5l643lydnw9re56gtzzff4mdq";
+        assert!(decode(s).is_ok() && decode(t).is_ok());
+        let mut wrong: Vec<u8> = s.into();
+        for p in [9, 11, 31] {
+            wrong[p] = t.as_bytes()[p];
+        }
+        let wrong = String::from_utf8(wrong).unwrap();
+        assert_eq!(decode(&wrong), Err(Error::InvalidChecksum));
+        assert_eq!(locate_errors(&wrong, Variant::Bech32), Ok(vec![36, 38]));
+    }
+}
@@ -0,0 +1,369 @@
+//! # CCSDS Reed–Solomon
+//!
+//! The telemetry code of CCSDS 131.0-B: RS(255,223) over `GF(2^8)` with the
+//! polynomial `0x187`, the generator roots `alpha^(11 j)` for
+//! `j = 112..=143`, symbols on the wire in Berlekamp's dual basis, interleaving
+//! depths from 1 to 8 and shortening by virtual fill.
+//!
+//! A codeblock of depth `I` interleaves `I` codewords symbol by symbol, so the
+//! data comes through unchanged and is followed by the interleaved parity.
+//! With a virtual fill of `V`, the first `V` data symbols of every codeword are
+//! zero and left out of the codeblock.
+//!
+//! ```
+//! use gf::ccsds::Ccsds;
+//!
+//! let code = Ccsds::new(2, 0);
+//! let data: Vec<u8> = (0..code.data_len()).map(|i| i as u8).collect();
+//!
+//! let mut block = code.encode(&data);
+//! assert_eq!(block.len(), 510);
+//! assert_eq!(&block[..446], &data[..]);
+//!
+//! block[7] ^= 0x10;
+//! block[500] = 0;
+//! assert_eq!(code.decode(&mut block), Ok(2));
+//! assert_eq!(&block[..446], &data[..]);
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{reed_solomon::Error, reed_solomon::ReedSolomon, GF2m};
+
+type G = GF2m<0x187>;
+
+/// Rows of the conversion from the conventional to the dual basis.
+const TAL: [u8; 8] = [0x8d, 0xef, 0xec, 0x86, 0xfa, 0x99, 0xaf, 0x7b];
+
+const DUAL_TABLES: ([u8; 256], [u8; 256]) = {
+    let mut to_dual = [0; 256];
+    let mut from_dual = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut dual = 0;
+        let mut bit = 0;
+        while bit < 8 {
+            if i & (1 << bit) != 0 {
+                dual ^= TAL[7 - bit];
+            }
+            bit += 1;
+        }
+        to_dual[i] = dual;
+        from_dual[dual as usize] = i as u8;
+        i += 1;
+    }
+    (to_dual, from_dual)
+};
+
+/// Converts a symbol from the conventional basis `alpha^7, ..., alpha^0` to
+/// the dual basis `l_0, ..., l_7`.
+#[inline]
+pub fn to_dual_basis(conventional: u8) -> u8 {
+    DUAL_TABLES.0[conventional as usize]
+}
+
+/// Converts a symbol from the dual basis to the conventional basis.
+#[inline]
+pub fn from_dual_basis(dual: u8) -> u8 {
+    DUAL_TABLES.1[dual as usize]
+}
+
+/// Codeword length.
+pub const N: usize = 255;
+/// Data symbols per codeword.
+pub const K: usize = 223;
+
+/// Encoder and decoder for one interleaving depth and virtual fill.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Ccsds {
+    depth: usize,
+    virtual_fill: usize,
+    code: ReedSolomon<G>,
+}
+
+impl Ccsds {
+    /// # Panics
+    ///
+    /// Panics if `depth` is not in `1..=8` or `virtual_fill` leaves no data.
+    pub fn new(depth: usize, virtual_fill: usize) -> Self {
+        assert!((1..=8).contains(&depth), "interleaving depth must be 1..=8");
+        assert!(virtual_fill < K, "virtual fill too large");
+
+        Self {
+            depth,
+            virtual_fill,
+            code: ReedSolomon::new(N - K, G::GENERATOR.pow(11), 112),
+        }
+    }
+
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[inline]
+    pub fn virtual_fill(&self) -> usize {
+        self.virtual_fill
+    }
+
+    /// Data bytes per codeblock.
+    #[inline]
+    pub fn data_len(&self) -> usize {
+        self.depth * (K - self.virtual_fill)
+    }
+
+    /// Bytes per codeblock, data and parity.
+    #[inline]
+    pub fn block_len(&self) -> usize {
+        self.depth * (N - self.virtual_fill)
+    }
+
+    /// The codeword `i` of a codeblock in the conventional basis, with the
+    /// virtual fill in front.
+    fn codeword(&self, block: &[u8], i: usize) -> Vec<G> {
+        let mut codeword = vec![G::ZERO; self.virtual_fill];
+        codeword.extend(
+            block[i..]
+                .iter()
+                .step_by(self.depth)
+                .map(|&b| G::new(from_dual_basis(b) as u16)),
+        );
+        codeword
+    }
+
+    /// The codeblock for `data`, given in the dual basis like the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` does not have exactly [`data_len`](Self::data_len) bytes.
+    pub fn encode(&self, data: &[u8]) -> Vec<u8> {
+        assert_eq!(data.len(), self.data_len(), "wrong data length");
+
+        let mut block = data.to_vec();
+        block.resize(self.block_len(), 0);
+        let parity_start = self.data_len();
+        for i in 0..self.depth {
+            let message = self.codeword(data, i);
+            for (j, p) in self.code.encode(&message).into_iter().enumerate() {
+                block[parity_start + j * self.depth + i] = to_dual_basis(p.0 as u8);
+            }
+        }
+        block
+    }
+
+    /// Corrects a codeblock in place and returns the number of symbols that
+    /// changed. Every interleaved codeword corrects up to 16 errors; if any of
+    /// them fails, `block` is left untouched. The virtual fill is known to be
+    /// zero, so a correction that lands in it is a misdecode and fails too.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block` does not have exactly [`block_len`](Self::block_len) bytes.
+    pub fn decode(&self, block: &mut [u8]) -> Result<usize, Error> {
+        assert_eq!(block.len(), self.block_len(), "wrong block length");
+
+        let mut codewords = Vec::with_capacity(self.depth);
+        let mut corrected = 0;
+        for i in 0..self.depth {
+            let mut codeword = self.codeword(block, i);
+            corrected += self.code.decode(&mut codeword, &[])?;
+            if codeword[..self.virtual_fill].iter().any(|&s| s != G::ZERO) {
+                return Err(Error::TooManyErrors);
+            }
+            codewords.push(codeword);
+        }
+
+        for (i, codeword) in codewords.iter().enumerate() {
+            for (j, s) in codeword[self.virtual_fill..].iter().enumerate() {
+                block[j * self.depth + i] = to_dual_basis(s.0 as u8);
+            }
+        }
+        Ok(corrected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{from_dual_basis, to_dual_basis, Ccsds, G};
+    use crate::reed_solomon::Error;
+
+    #[test]
+    fn dual_basis() {
+        let table: Vec<u8> = (0..16).map(to_dual_basis).collect();
+        assert_eq!(
+            table,
+            [
+                0x00, 0x7b, 0xaf, 0xd4, 0x99, 0xe2, 0x36, 0x4d, 0xfa, 0x81, 0x55, 0x2e, 0x63, 0x18,
+                0xcc, 0xb7
+            ]
+        );
+        for x in 0..=255 {
+            assert_eq!(from_dual_basis(to_dual_basis(x)), x);
+        }
+    }
+
+    #[test]
+    fn generator() {
+        // the generator coefficients as powers of alpha, highest first
+        let expected = [
+            0, 249, 59, 66, 4, 43, 126, 251, 97, 30, 3, 213, 50, 66, 170, 5, 24, 5, 170, 66, 50,
+            213, 3, 30, 97, 251, 126, 43, 4, 66, 59, 249, 0,
+        ];
+        assert_eq!(G::GENERATOR, G::new(2));
+
+        let g = Ccsds::new(1, 0).code.generator();
+        let logs: Vec<usize> = g.coeffs().iter().rev().map(|c| c.log().unwrap()).collect();
+        assert_eq!(logs, expected);
+    }
+
+    /// A transcription of `init_rs`, `encode_rs_ccsds` and `gen_ccsds_tal` of
+    /// Phil Karn's libfec, sharing nothing with the code above but the Tal
+    /// matrix of CCSDS 131.0-B: the parity of one codeword, in the dual
+    /// basis, for data in the dual basis.
+    #[allow(clippy::needless_range_loop)]
+    fn karn_encode(data: &[u8], pad: usize) -> [u8; 32] {
+        const TAL: [u8; 8] = [0x8d, 0xef, 0xec, 0x86, 0xfa, 0x99, 0xaf, 0x7b];
+        const A0: usize = 255;
+        let (nroots, fcr, prim) = (32, 112, 11);
+
+        let mut alpha_to = [0u8; 256];
+        let mut index_of = [A0; 256];
+        let mut sr = 1usize;
+        for i in 0..255 {
+            index_of[sr] = i;
+            alpha_to[i] = sr as u8;
+            sr <<= 1;
+            if sr & 0x100 != 0 {
+                sr ^= 0x187;
+            }
+        }
+        let modnn = |x: usize| x % 255;
+
+        let mut genpoly = [0u8; 33];
+        genpoly[0] = 1;
+        let mut root = fcr * prim;
+        for i in 0..nroots {
+            genpoly[i + 1] = 1;
+            for j in (1..=i).rev() {
+                genpoly[j] = if genpoly[j] != 0 {
+                    genpoly[j - 1] ^ alpha_to[modnn(index_of[genpoly[j] as usize] + root)]
+                } else {
+                    genpoly[j - 1]
+                };
+            }
+            genpoly[0] = alpha_to[modnn(index_of[genpoly[0] as usize] + root)];
+            root += prim;
+        }
+        let genpoly = genpoly.map(|g| index_of[g as usize]);
+
+        let mut taltab = [0u8; 256];
+        let mut tal1tab = [0u8; 256];
+        for i in 0..256 {
+            for j in 0..8 {
+                for k in 0..8 {
+                    if i & (1 << k) != 0 {
+                        taltab[i] ^= TAL[7 - k] & (1 << j);
+                    }
+                }
+            }
+            tal1tab[taltab[i] as usize] = i as u8;
+        }
+
+        let mut parity = [0u8; 32];
+        for &d in &data[..255 - nroots - pad] {
+            let feedback = index_of[(tal1tab[d as usize] ^ parity[0]) as usize];
+            if feedback != A0 {
+                for j in 1..nroots {
+                    parity[j] ^= alpha_to[modnn(feedback + genpoly[nroots - j])];
+                }
+            }
+            parity.copy_within(1.., 0);
+            parity[nroots - 1] = if feedback != A0 {
+                alpha_to[modnn(feedback + genpoly[0])]
+            } else {
+                0
+            };
+        }
+        parity.map(|p| taltab[p as usize])
+    }
+
+    #[test]
+    fn same_as_karn() {
+        for (depth, fill) in [(1, 0), (1, 33), (5, 0), (5, 100)] {
+            let code = Ccsds::new(depth, fill);
+            let data: Vec<u8> = (0..code.data_len())
+                .map(|i| (i * 167 + i / 7) as u8)
+                .collect();
+            let block = code.encode(&data);
+
+            let mut expected = data.clone();
+            expected.resize(code.block_len(), 0);
+            for i in 0..depth {
+                let column: Vec<u8> = data[i..].iter().step_by(depth).copied().collect();
+                for (j, p) in karn_encode(&column, fill).into_iter().enumerate() {
+                    expected[code.data_len() + j * depth + i] = p;
+                }
+            }
+            assert_eq!(block, expected);
+        }
+    }
+
+    #[test]
+    fn interleaved_and_shortened() {
+        for depth in 1..=8 {
+            for fill in [0, 1, 100, 222] {
+                let code = Ccsds::new(depth, fill);
+                let data: Vec<u8> = (0..code.data_len()).map(|i| (i * 31) as u8).collect();
+                let original = code.encode(&data);
+                assert_eq!(&original[..data.len()], &data[..]);
+
+                // a burst of 16 symbols in every codeword
+                let mut block = original.clone();
+                for b in &mut block[..16 * depth] {
+                    *b ^= 0xFF;
+                }
+                assert_eq!(code.decode(&mut block), Ok(16 * depth));
+                assert_eq!(block, original);
+
+                for b in &mut block[..17 * depth] {
+                    *b = !*b;
+                }
+                let damaged = block.clone();
+                assert_eq!(code.decode(&mut block), Err(Error::TooManyErrors));
+                assert_eq!(block, damaged);
+            }
+        }
+    }
+
+    #[test]
+    fn correction_in_virtual_fill() {
+        // the generator, shifted to start in the virtual fill, is a codeword
+        // one symbol away from the shortened block plus its other 32 symbols
+        let code = Ccsds::new(1, 1);
+        let data: Vec<u8> = (0..code.data_len()).map(|i| (i * 7) as u8).collect();
+        let original = code.encode(&data);
+        let g: Vec<u8> = code
+            .code
+            .generator()
+            .coeffs()
+            .iter()
+            .rev()
+            .map(|c| c.0 as u8)
+            .collect();
+
+        let mut block = original.clone();
+        for (b, &c) in block.iter_mut().zip(&g[1..]) {
+            *b = to_dual_basis(from_dual_basis(*b) ^ c);
+        }
+        let damaged = block.clone();
+        assert_eq!(code.decode(&mut block), Err(Error::TooManyErrors));
+        assert_eq!(block, damaged);
+
+        // with that one fill symbol set it is a codeword
+        let unshortened = Ccsds::new(1, 0);
+        let mut block = [&[to_dual_basis(g[0])][..], &damaged].concat();
+        assert_eq!(unshortened.decode(&mut block), Ok(0));
+    }
+}
@@ -0,0 +1,410 @@
+//! MD5, SHA-256, HMAC, PBKDF2 and CRC-32, as required by file formats built on
+//! the codes in this crate. Only the SHA-256 based ones are meant for anything
+//! security related.
+
+/// Incremental MD5 (RFC 1321).
+#[derive(Clone, Debug)]
+pub(crate) struct Md5 {
+    state: [u32; 4],
+    buffer: [u8; 64],
+    len: u64,
+}
+
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+    14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15,
+    21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const SINES: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+impl Md5 {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            buffer: [0; 64],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn digest(data: &[u8]) -> [u8; 16] {
+        let mut md5 = Self::new();
+        md5.update(data);
+        md5.finish()
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        let filled = (self.len % 64) as usize;
+        self.len += data.len() as u64;
+
+        if filled != 0 {
+            let take = data.len().min(64 - filled);
+            self.buffer[filled..filled + take].copy_from_slice(&data[..take]);
+            data = &data[take..];
+            if filled + take < 64 {
+                return;
+            }
+            let block = self.buffer;
+            self.compress(&block);
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block.try_into().unwrap());
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+    }
+
+    pub(crate) fn finish(mut self) -> [u8; 16] {
+        let bits = self.len.wrapping_mul(8);
+        let padding = 1 + (119 - self.len % 64) % 64;
+        let mut tail = [0; 72];
+        tail[0] = 0x80;
+        tail[padding as usize..padding as usize + 8].copy_from_slice(&bits.to_le_bytes());
+        self.update(&tail[..padding as usize + 8]);
+
+        let mut out = [0; 16];
+        for (o, s) in out.chunks_exact_mut(4).zip(self.state) {
+            o.copy_from_slice(&s.to_le_bytes());
+        }
+        out
+    }
+
+    fn compress(&mut self, block: &[u8; 64]) {
+        let mut m = [0u32; 16];
+        for (m, b) in m.iter_mut().zip(block.chunks_exact(4)) {
+            *m = u32::from_le_bytes(b.try_into().unwrap());
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let f = f.wrapping_add(a).wrapping_add(SINES[i]).wrapping_add(m[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(SHIFTS[i]));
+        }
+
+        for (s, v) in self.state.iter_mut().zip([a, b, c, d]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+}
+
+/// Incremental SHA-256 (FIPS 180-4).
+#[derive(Clone, Debug)]
+pub(crate) struct Sha256 {
+    state: [u32; 8],
+    buffer: [u8; 64],
+    len: u64,
+}
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+impl Sha256 {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: [
+                0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+                0x5be0cd19,
+            ],
+            buffer: [0; 64],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn digest(data: &[u8]) -> [u8; 32] {
+        let mut sha = Self::new();
+        sha.update(data);
+        sha.finish()
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        let filled = (self.len % 64) as usize;
+        self.len += data.len() as u64;
+
+        if filled != 0 {
+            let take = data.len().min(64 - filled);
+            self.buffer[filled..filled + take].copy_from_slice(&data[..take]);
+            data = &data[take..];
+            if filled + take < 64 {
+                return;
+            }
+            let block = self.buffer;
+            self.compress(&block);
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block.try_into().unwrap());
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+    }
+
+    pub(crate) fn finish(mut self) -> [u8; 32] {
+        let bits = self.len.wrapping_mul(8);
+        let padding = 1 + (119 - self.len % 64) % 64;
+        let mut tail = [0; 72];
+        tail[0] = 0x80;
+        tail[padding as usize..padding as usize + 8].copy_from_slice(&bits.to_be_bytes());
+        self.update(&tail[..padding as usize + 8]);
+
+        let mut out = [0; 32];
+        for (o, s) in out.chunks_exact_mut(4).zip(self.state) {
+            o.copy_from_slice(&s.to_be_bytes());
+        }
+        out
+    }
+
+    fn compress(&mut self, block: &[u8; 64]) {
+        let mut w = [0u32; 64];
+        for (w, b) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_be_bytes(b.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(ROUND_CONSTANTS[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (s, v) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+}
+
+/// HMAC-SHA256 (RFC 2104). Cloning a keyed instance skips hashing the key
+/// again, which PBKDF2 relies on.
+#[derive(Clone, Debug)]
+pub(crate) struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl HmacSha256 {
+    pub(crate) fn new(key: &[u8]) -> Self {
+        let mut block = [0; 64];
+        if key.len() > 64 {
+            block[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let mut inner = Sha256::new();
+        inner.update(&block.map(|b| b ^ 0x36));
+        let mut outer = Sha256::new();
+        outer.update(&block.map(|b| b ^ 0x5c));
+        Self { inner, outer }
+    }
+
+    pub(crate) fn mac(key: &[u8], data: &[u8]) -> [u8; 32] {
+        let mut hmac = Self::new(key);
+        hmac.update(data);
+        hmac.finish()
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub(crate) fn finish(self) -> [u8; 32] {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finish());
+        outer.finish()
+    }
+}
+
+/// PBKDF2 (RFC 8018) with HMAC-SHA256, filling `out` with the derived key.
+pub(crate) fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, out: &mut [u8]) {
+    let prf = HmacSha256::new(password);
+    for (i, block) in out.chunks_mut(32).enumerate() {
+        let mut hmac = prf.clone();
+        hmac.update(salt);
+        hmac.update(&(i as u32 + 1).to_be_bytes());
+        let mut u = hmac.finish();
+        let mut t = u;
+        for _ in 1..iterations {
+            let mut hmac = prf.clone();
+            hmac.update(&u);
+            u = hmac.finish();
+            for (t, u) in t.iter_mut().zip(u) {
+                *t ^= u;
+            }
+        }
+        block.copy_from_slice(&t[..block.len()]);
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB88320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 as used by zip, PNG and Ethernet.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &b| {
+        CRC32_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc32, pbkdf2_sha256, HmacSha256, Md5, Sha256};
+    use alloc::{format, string::String};
+
+    #[test]
+    fn md5() {
+        let hex = |h: [u8; 16]| u128::from_be_bytes(h);
+        assert_eq!(hex(Md5::digest(b"")), 0xd41d8cd98f00b204e9800998ecf8427e);
+        assert_eq!(hex(Md5::digest(b"abc")), 0x900150983cd24fb0d6963f7d28e17f72);
+
+        let long = [b'a'; 1000];
+        let mut md5 = Md5::new();
+        for chunk in long.chunks(37) {
+            md5.update(chunk);
+        }
+        assert_eq!(md5.finish(), Md5::digest(&long));
+        assert_eq!(hex(Md5::digest(&long)), 0xcabe45dcc9ae5b66ba86600cca6b8ba8);
+    }
+
+    #[test]
+    fn sha256() {
+        let hex = |h: [u8; 32]| {
+            (
+                u128::from_be_bytes(h[..16].try_into().unwrap()),
+                u128::from_be_bytes(h[16..].try_into().unwrap()),
+            )
+        };
+        assert_eq!(
+            hex(Sha256::digest(b"")),
+            (
+                0xe3b0c44298fc1c149afbf4c8996fb924,
+                0x27ae41e4649b934ca495991b7852b855
+            )
+        );
+        assert_eq!(
+            hex(Sha256::digest(b"abc")),
+            (
+                0xba7816bf8f01cfea414140de5dae2223,
+                0xb00361a396177a9cb410ff61f20015ad
+            )
+        );
+
+        let long = [b'a'; 1000];
+        let mut sha = Sha256::new();
+        for chunk in long.chunks(37) {
+            sha.update(chunk);
+        }
+        assert_eq!(sha.finish(), Sha256::digest(&long));
+        assert_eq!(
+            hex(Sha256::digest(&long)),
+            (
+                0x41edece42d63e8d9bf515a9ba6932e1c,
+                0x20cbc9f5a5d134645adb5db1b9737ea3
+            )
+        );
+    }
+
+    #[test]
+    fn hmac_pbkdf2() {
+        let hex = |h: &[u8]| h.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        assert_eq!(
+            hex(&HmacSha256::mac(&[0x0b; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        // keys longer than a block are hashed first
+        assert_eq!(
+            hex(&HmacSha256::mac(&[b'k'; 100], b"long key")),
+            "5903eedefe0bba3f15b658715e41d71f1848739bddf19e497e968df5723dcd56"
+        );
+
+        let mut key = [0; 32];
+        pbkdf2_sha256(b"password", b"salt", 2, &mut key);
+        assert_eq!(
+            hex(&key),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+        let mut key = [0; 40];
+        pbkdf2_sha256(
+            b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            &mut key,
+        );
+        assert_eq!(
+            hex(&key),
+            "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9"
+        );
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+        assert_eq!(crc32(b""), 0);
+    }
+}
@@ -0,0 +1,326 @@
+//! # Cross-Interleaved Reed–Solomon Coding
+//!
+//! The two-level code of the compact disc: every frame of 24 data bytes gets
+//! 4 parity bytes from the outer code C2, a shortened RS(28,24), the 28 bytes
+//! are spread over the following frames by a [`DelayInterleaver`], and every
+//! resulting frame gets 4 parity bytes from the inner code C1, a shortened
+//! RS(32,28). Both codes are over [`GF<u8>`] with the generator roots
+//! `2^0, ..., 2^3`.
+//!
+//! The decoder corrects single errors with C1 and marks every other damaged
+//! C1 frame as erased. After deinterleaving, each C2 frame sees the erasures of
+//! a burst spread over many frames and fills in up to 4 of them. With a unit
+//! delay of 4 frames, bursts of 16 whole frames, 512 bytes, are
+//! corrected completely.
+//!
+//! This follows the structure of the CD, but is not bit compatible with it:
+//! the parity stays at the end of every codeword and there is no scrambling or
+//! parity inversion.
+//!
+//! ```
+//! use gf::circ::{Decoder, Encoder};
+//!
+//! let mut encoder = Encoder::new(4);
+//! let mut decoder = Decoder::new(4);
+//!
+//! let mut received = Vec::new();
+//! for i in 0..200u8 {
+//!     let mut frame = encoder.encode(&[i; 24]);
+//!     if (50..60).contains(&i) {
+//!         frame = [0xAA; 32];
+//!     }
+//!     received.push(decoder.decode(&frame));
+//! }
+//!
+//! // the decoder lags by `delay()` frames
+//! let delay = decoder.delay();
+//! assert!(received[delay..].iter().all(|f| f.erased == [false; 24]));
+//! assert_eq!(received[delay + 55].data, [55; 24]);
+//! ```
+
+use alloc::{collections::VecDeque, vec::Vec};
+
+use crate::{reed_solomon::ReedSolomon, GF};
+
+/// Data bytes per frame.
+pub const DATA_LEN: usize = 24;
+/// Bytes per C2 codeword, the width of the interleaver.
+const C2_LEN: usize = 28;
+/// Bytes per frame on the medium.
+pub const FRAME_LEN: usize = 32;
+
+/// # Convolutional Interleaver
+///
+/// Delays lane `i` of every frame by `delays[i]` frames. An interleaver with
+/// the delays `i * d` is undone by one with the delays `(n - 1 - i) * d`,
+/// after a total lag of `(n - 1) * d` frames.
+///
+/// ```
+/// use gf::circ::DelayInterleaver;
+///
+/// let mut interleaver = DelayInterleaver::new(3, 1, 0);
+/// let mut deinterleaver = DelayInterleaver::deinterleaver(3, 1, 0);
+///
+/// let mut out = Vec::new();
+/// for frame in [[1, 2, 3], [4, 5, 6], [7, 8, 9], [0, 0, 0], [0, 0, 0]] {
+///     let mut frame = frame;
+///     interleaver.push(&mut frame);
+///     deinterleaver.push(&mut frame);
+///     out.push(frame);
+/// }
+/// assert_eq!(out[2..], [[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DelayInterleaver<T> {
+    lanes: Vec<VecDeque<T>>,
+}
+
+impl<T: Clone> DelayInterleaver<T> {
+    /// `width` lanes with the delays `0, unit_delay, 2 * unit_delay, ...`,
+    /// initially filled with `fill`.
+    pub fn new(width: usize, unit_delay: usize, fill: T) -> Self {
+        Self::with_delays((0..width).map(|i| i * unit_delay), fill)
+    }
+
+    /// The inverse of [`new`](Self::new), with the delays in reverse.
+    pub fn deinterleaver(width: usize, unit_delay: usize, fill: T) -> Self {
+        Self::with_delays((0..width).rev().map(|i| i * unit_delay), fill)
+    }
+
+    pub fn with_delays(delays: impl IntoIterator<Item = usize>, fill: T) -> Self {
+        Self {
+            lanes: delays
+                .into_iter()
+                .map(|d| core::iter::repeat_n(fill.clone(), d).collect())
+                .collect(),
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.lanes.len()
+    }
+
+    /// Replaces every element of `frame` by the one its lane received the
+    /// given number of frames ago.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame` does not have exactly [`width`](Self::width) elements.
+    pub fn push(&mut self, frame: &mut [T]) {
+        assert_eq!(frame.len(), self.width(), "wrong frame width");
+        for (lane, x) in self.lanes.iter_mut().zip(frame) {
+            if !lane.is_empty() {
+                lane.push_back(x.clone());
+                *x = lane.pop_front().unwrap();
+            }
+        }
+    }
+}
+
+fn copy_symbols(dst: &mut [u8], src: &[GF<u8>]) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = s.0;
+    }
+}
+
+fn c1() -> ReedSolomon<GF<u8>> {
+    ReedSolomon::new(FRAME_LEN - C2_LEN, GF(2), 0)
+}
+
+fn c2() -> ReedSolomon<GF<u8>> {
+    ReedSolomon::new(C2_LEN - DATA_LEN, GF(2), 0)
+}
+
+#[derive(Clone, Debug)]
+pub struct Encoder {
+    c1: ReedSolomon<GF<u8>>,
+    c2: ReedSolomon<GF<u8>>,
+    interleaver: DelayInterleaver<u8>,
+}
+
+impl Encoder {
+    /// An encoder whose interleaver delays C2 byte `i` by `i * unit_delay`
+    /// frames.
+    pub fn new(unit_delay: usize) -> Self {
+        Self {
+            c1: c1(),
+            c2: c2(),
+            interleaver: DelayInterleaver::new(C2_LEN, unit_delay, 0),
+        }
+    }
+
+    /// Encodes the next frame. The interleaver starts out with all zero frames,
+    /// which are valid C2 codewords.
+    pub fn encode(&mut self, data: &[u8; DATA_LEN]) -> [u8; FRAME_LEN] {
+        let mut frame = [0; FRAME_LEN];
+        frame[..DATA_LEN].copy_from_slice(data);
+
+        let c2 = self.c2.encode(GF::from_slice(data));
+        copy_symbols(&mut frame[DATA_LEN..C2_LEN], &c2);
+
+        self.interleaver.push(&mut frame[..C2_LEN]);
+
+        let c1 = self.c1.encode(GF::from_slice(&frame[..C2_LEN]));
+        copy_symbols(&mut frame[C2_LEN..], &c1);
+        frame
+    }
+}
+
+/// A decoded frame.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Frame {
+    pub data: [u8; DATA_LEN],
+    /// Bytes that could not be corrected and must be concealed.
+    pub erased: [bool; DATA_LEN],
+}
+
+#[derive(Clone, Debug)]
+pub struct Decoder {
+    c1: ReedSolomon<GF<u8>>,
+    c2: ReedSolomon<GF<u8>>,
+    deinterleaver: DelayInterleaver<(u8, bool)>,
+    delay: usize,
+}
+
+impl Decoder {
+    /// A decoder for frames from [`Encoder::new`] with the same `unit_delay`.
+    pub fn new(unit_delay: usize) -> Self {
+        Self {
+            c1: c1(),
+            c2: c2(),
+            deinterleaver: DelayInterleaver::deinterleaver(C2_LEN, unit_delay, (0, false)),
+            delay: (C2_LEN - 1) * unit_delay,
+        }
+    }
+
+    /// Number of frames between a frame going into the encoder and coming out
+    /// of the decoder.
+    #[inline]
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+
+    /// Decodes the next frame read from the medium, returning the frame from
+    /// [`delay`](Self::delay) frames earlier.
+    pub fn decode(&mut self, frame: &[u8; FRAME_LEN]) -> Frame {
+        let mut c1 = GF::from_slice(frame).to_vec();
+        let erased = !matches!(self.c1.decode(&mut c1, &[]), Ok(0 | 1));
+        if erased {
+            c1.copy_from_slice(GF::from_slice(frame));
+        }
+
+        let mut c2: Vec<(u8, bool)> = c1[..C2_LEN].iter().map(|b| (b.0, erased)).collect();
+        self.deinterleaver.push(&mut c2);
+
+        let erasures: Vec<usize> = (0..C2_LEN).filter(|&i| c2[i].1).collect();
+        let mut codeword: Vec<GF<u8>> = c2.iter().map(|&(b, _)| GF(b)).collect();
+
+        let mut out = Frame {
+            data: [0; DATA_LEN],
+            erased: [false; DATA_LEN],
+        };
+        if self.c2.decode(&mut codeword, &erasures).is_err() {
+            // without C1 flags there is no telling which bytes are wrong
+            for &i in erasures.iter().filter(|&&i| i < DATA_LEN) {
+                out.erased[i] = true;
+            }
+            if !out.erased.contains(&true) {
+                out.erased = [true; DATA_LEN];
+            }
+        }
+        copy_symbols(&mut out.data, &codeword[..DATA_LEN]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{Decoder, DelayInterleaver, Encoder, Frame, FRAME_LEN};
+
+    fn run(frames: usize, damage: impl Fn(usize, &mut [u8; FRAME_LEN])) -> (Vec<Frame>, usize) {
+        let mut encoder = Encoder::new(4);
+        let mut decoder = Decoder::new(4);
+        let out = (0..frames)
+            .map(|t| {
+                let data = core::array::from_fn(|i| (t * 24 + i) as u8);
+                let mut frame = encoder.encode(&data);
+                damage(t, &mut frame);
+                decoder.decode(&frame)
+            })
+            .collect();
+        (out, decoder.delay())
+    }
+
+    fn expected(t: usize) -> [u8; 24] {
+        core::array::from_fn(|i| (t * 24 + i) as u8)
+    }
+
+    #[test]
+    fn interleaver_roundtrip() {
+        let mut interleaver = DelayInterleaver::new(5, 2, 0u32);
+        let mut deinterleaver = DelayInterleaver::deinterleaver(5, 2, 0u32);
+        for t in 0..100 {
+            let mut frame: Vec<u32> = (0..5).map(|i| t * 5 + i + 1).collect();
+            interleaver.push(&mut frame);
+            deinterleaver.push(&mut frame);
+            if t >= 8 {
+                let earlier = t - 8;
+                assert_eq!(
+                    frame,
+                    (0..5).map(|i| earlier * 5 + i + 1).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn long_burst() {
+        // 16 frames are wiped out completely
+        let (out, delay) = run(300, |t, frame| {
+            if (100..116).contains(&t) {
+                *frame = [0xAA; FRAME_LEN];
+            }
+        });
+        for t in 0..300 - delay {
+            assert_eq!(out[t + delay].erased, [false; 24]);
+            assert_eq!(out[t + delay].data, expected(t));
+        }
+    }
+
+    #[test]
+    fn random_errors() {
+        let (out, delay) = run(200, |t, frame| {
+            frame[(t * 7) % FRAME_LEN] ^= 0x11;
+            // C1 gives up on these and leaves them to C2
+            if t == 10 || t == 90 {
+                frame[(t * 11 + 5) % FRAME_LEN] ^= 0x42;
+            }
+        });
+        for t in 0..200 - delay {
+            assert_eq!(out[t + delay].erased, [false; 24]);
+            assert_eq!(out[t + delay].data, expected(t));
+        }
+    }
+
+    #[test]
+    fn too_long_burst_is_flagged() {
+        let (out, delay) = run(300, |t, frame| {
+            if (100..140).contains(&t) {
+                *frame = [0xAA; FRAME_LEN];
+            }
+        });
+        let flagged = out[delay..]
+            .iter()
+            .filter(|f| f.erased.contains(&true))
+            .count();
+        assert!(flagged > 0);
+        for (t, frame) in out[delay..].iter().enumerate() {
+            if !frame.erased.contains(&true) {
+                assert_eq!(frame.data, expected(t));
+            }
+        }
+    }
+}
@@ -0,0 +1,630 @@
+//! # codex32
+//!
+//! The BIP-93 format for backing up and sharing master seeds by hand. A
+//! codex32 string is `ms1` followed by a data part in the bech32 alphabet:
+//!
+//! - the threshold `k`, `0` for an unshared secret or `2` to `9`,
+//! - a four character identifier common to all shares of a seed,
+//! - the share index, with `s` the secret itself,
+//! - the seed packed into 5-bit characters,
+//! - a checksum of 13 characters, or 15 for data parts above 93 characters.
+//!
+//! Sharing is Shamir's scheme over GF(32) applied to every character of the
+//! data part at once: the share with index `i` is the value at `x = i` of the
+//! polynomial through the shares given. Since the checksum is linear, any
+//! interpolated string is again valid.
+//!
+//! The checksums are BCH codes over GF(32) whose generators have eight
+//! consecutive roots in GF(1024), so with GF(32) embedded in GF(1024) every
+//! codex32 string is a codeword of a [Reed–Solomon](crate::reed_solomon)
+//! code there, which corrects 4 substitutions or 8 erasures.
+//!
+//! ```
+//! use gf::codex32::Codex32;
+//!
+//! // the second test vector of BIP-93
+//! let a = Codex32::parse("MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM").unwrap();
+//! let c = Codex32::parse("MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN").unwrap();
+//! assert_eq!((a.threshold(), a.identifier().as_str(), a.share_index()), (2, "name", 'a'));
+//!
+//! let secret = Codex32::interpolate(&[a, c], 's').unwrap();
+//! assert_eq!(
+//!     secret.to_string(),
+//!     "ms12names6xqguzttxkeqnjsjzv4jv3nz5k3kwgsphuh6evw"
+//! );
+//! assert_eq!(secret.payload()[..4], [0xd1, 0x80, 0x8e, 0x09]);
+//!
+//! let (fixed, positions) =
+//!     Codex32::correct("ms12names6xqguzttxkeqnjsjzv4jv3nz5k3kwgspxuh6e?w").unwrap();
+//! assert_eq!(fixed, secret);
+//! assert_eq!(positions, [41, 46]);
+//! ```
+
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt;
+
+use crate::{
+    bech32::{embed, from_char, to_char, unembed, Checksum, GF1024, GF32},
+    interpolate::Lagrange,
+    reed_solomon::ReedSolomon,
+};
+
+const PREFIX: &str = "ms1";
+/// Threshold, identifier and share index.
+const HEADER_LEN: usize = 6;
+const MIN_SEED_LEN: usize = 16;
+const MAX_SEED_LEN: usize = 64;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    /// Not starting with `ms1`.
+    InvalidPrefix,
+    /// Upper and lower case letters in the same string.
+    MixedCase,
+    /// A character outside [`CHARSET`](crate::bech32::CHARSET), at this position.
+    InvalidCharacter {
+        position: usize,
+        character: char,
+    },
+    /// A length that fits neither checksum, or leaves more than four bits of
+    /// padding, or a seed not of 16 to 64 bytes.
+    InvalidLength,
+    InvalidChecksum,
+    /// A threshold other than `0` and `2` to `9`.
+    InvalidThreshold,
+    /// An identifier not of four bech32 characters.
+    InvalidIdentifier,
+    /// A share index other than `s` for an unshared secret.
+    InvalidShareIndex,
+    /// Fewer shares than the threshold.
+    TooFewShares,
+    /// Shares that differ in threshold, identifier or length.
+    Mismatch,
+    /// Two shares with the same index.
+    DuplicateIndex,
+    /// More errors than the checksum corrects.
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidPrefix => "codex32 strings must start with ms1".fmt(f),
+            Error::MixedCase => "mixed upper and lower case".fmt(f),
+            Error::InvalidCharacter {
+                position,
+                character,
+            } => write!(f, "invalid character {character:?} at position {position}"),
+            Error::InvalidLength => "invalid length".fmt(f),
+            Error::InvalidChecksum => "invalid checksum".fmt(f),
+            Error::InvalidThreshold => "threshold must be 0 or 2 to 9".fmt(f),
+            Error::InvalidIdentifier => "identifier must be 4 bech32 characters".fmt(f),
+            Error::InvalidShareIndex => "share index of an unshared secret must be s".fmt(f),
+            Error::TooFewShares => "fewer shares than the threshold".fmt(f),
+            Error::Mismatch => "shares do not belong to the same seed".fmt(f),
+            Error::DuplicateIndex => "duplicate share index".fmt(f),
+            Error::TooManyErrors => "too many errors to correct".fmt(f),
+        }
+    }
+}
+
+/// One of the two BCH codes of BIP-93.
+struct Code {
+    /// Starting from the residue `0x23181b3` of the reference code, valid
+    /// data parts leave `secretshare32` or `secretshare32ex`.
+    checksum: Checksum,
+    /// `alpha` as a power of [`GF1024::GENERATOR`], and the power of it at
+    /// the first of the eight consecutive roots.
+    alpha: usize,
+    first_root: usize,
+}
+
+const SHORT: Code = Code {
+    checksum: Checksum {
+        len: 13,
+        generator: 0x19dc500ce73fde210,
+        init: 0x23181b3,
+        target: 0x10ce0795c2fd1e62a,
+    },
+    alpha: 187,
+    first_root: 9,
+};
+
+const LONG: Code = Code {
+    checksum: Checksum {
+        len: 15,
+        generator: 0x3d59d273535ea62d897,
+        init: 0x23181b3,
+        target: 0x43381e570bf4798ab26,
+    },
+    alpha: 251,
+    first_root: 1020,
+};
+
+impl Code {
+    /// The code for a data part of `len` characters. The short code has
+    /// length 93, the order of its `alpha`; long strings are limited to
+    /// 127 characters in all.
+    fn for_len(len: usize) -> Option<&'static Code> {
+        match len {
+            45..=93 => Some(&SHORT),
+            96..=124 => Some(&LONG),
+            _ => None,
+        }
+    }
+
+    /// Corrects `data` in place as a Reed–Solomon codeword over GF(1024).
+    fn correct(&self, data: &mut [GF32], erasures: &[usize]) -> Result<(), Error> {
+        // valid data parts are `offset` away from multiples of the generator,
+        // with the offset only in the checksum
+        let checksum = &self.checksum;
+        let n = data.len();
+        let offset = &checksum.residue(&vec![GF32::ZERO; n]) + &checksum.target();
+        let mut codeword: Vec<GF1024> = data.iter().map(|&a| embed(a)).collect();
+        for i in 0..checksum.len {
+            codeword[n - 1 - i] += embed(offset.coeff(i));
+        }
+
+        let rs = ReedSolomon::new(8, GF1024::exp(self.alpha), self.first_root);
+        rs.decode(&mut codeword, erasures)
+            .map_err(|_| Error::TooManyErrors)?;
+        for i in 0..checksum.len {
+            codeword[n - 1 - i] -= embed(offset.coeff(i));
+        }
+
+        // a correction outside GF(32), or not a codeword for the roots
+        // beyond the eight, is a failure
+        let corrected = codeword
+            .iter()
+            .map(|&b| unembed(b))
+            .collect::<Option<Vec<GF32>>>()
+            .filter(|c| checksum.verify(c))
+            .ok_or(Error::TooManyErrors)?;
+        data.copy_from_slice(&corrected);
+        Ok(())
+    }
+}
+
+/// The data part of `s`, and the positions in it of `?` if `erasures`.
+fn data_part(s: &str, erasures: bool) -> Result<(Vec<GF32>, Vec<usize>), Error> {
+    if s.bytes().any(|b| b.is_ascii_lowercase()) && s.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(Error::MixedCase);
+    }
+    if !s
+        .get(..PREFIX.len())
+        .is_some_and(|p| p.eq_ignore_ascii_case(PREFIX))
+    {
+        return Err(Error::InvalidPrefix);
+    }
+
+    let mut data = Vec::with_capacity(s.len() - PREFIX.len());
+    let mut erased = Vec::new();
+    for (position, character) in s.char_indices().skip(PREFIX.len()) {
+        let symbol = match character {
+            '?' if erasures => {
+                erased.push(data.len());
+                Some(GF32::ZERO)
+            }
+            c if c.is_ascii() => from_char(c as u8),
+            _ => None,
+        };
+        data.push(symbol.ok_or(Error::InvalidCharacter {
+            position,
+            character,
+        })?);
+    }
+    Ok((data, erased))
+}
+
+/// A valid codex32 string: a share, or the secret itself.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Codex32 {
+    /// The data part, checksum included.
+    data: Vec<GF32>,
+}
+
+impl Codex32 {
+    /// Encodes `seed` as the share `share_index` with threshold `threshold`,
+    /// with zero padding bits.
+    pub fn from_seed(
+        threshold: u8,
+        identifier: &str,
+        share_index: char,
+        seed: &[u8],
+    ) -> Result<Self, Error> {
+        let threshold_char = match threshold {
+            0 | 2..=9 => b'0' + threshold,
+            _ => return Err(Error::InvalidThreshold),
+        };
+        if identifier.len() != 4 || identifier.bytes().any(|c| from_char(c).is_none()) {
+            return Err(Error::InvalidIdentifier);
+        }
+        let index = share_index
+            .is_ascii()
+            .then(|| from_char(share_index as u8))
+            .flatten()
+            .ok_or(Error::InvalidShareIndex)?;
+        if threshold == 0 && !share_index.eq_ignore_ascii_case(&'s') {
+            return Err(Error::InvalidShareIndex);
+        }
+        if !(MIN_SEED_LEN..=MAX_SEED_LEN).contains(&seed.len()) {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut data: Vec<GF32> = [threshold_char]
+            .iter()
+            .chain(identifier.as_bytes())
+            .map(|&c| from_char(c).unwrap())
+            .collect();
+        data.push(index);
+        let (mut acc, mut bits) = (0u32, 0);
+        for &b in seed {
+            acc = acc << 8 | b as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                data.push(GF32::new((acc >> bits & 31) as u16));
+            }
+        }
+        if bits > 0 {
+            data.push(GF32::new((acc << (5 - bits) & 31) as u16));
+        }
+
+        let code = if data.len() + SHORT.checksum.len <= 93 {
+            &SHORT
+        } else {
+            &LONG
+        };
+        let checksum = code.checksum.create(&data);
+        data.extend(checksum);
+        Ok(Self { data })
+    }
+
+    /// Parses and validates a codex32 string in either case.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let (data, _) = data_part(s, false)?;
+        let code = Code::for_len(data.len()).ok_or(Error::InvalidLength)?;
+        if !code.checksum.verify(&data) {
+            return Err(Error::InvalidChecksum);
+        }
+        Self::from_data(data, code)
+    }
+
+    /// Checks everything but the checksum.
+    fn from_data(data: Vec<GF32>, code: &Code) -> Result<Self, Error> {
+        let share = Self { data };
+        let payload_bits = 5 * (share.data.len() - HEADER_LEN - code.checksum.len);
+        if payload_bits % 8 > 4 {
+            return Err(Error::InvalidLength);
+        }
+        match share.threshold() {
+            0 if share.share_index() != 's' => Err(Error::InvalidShareIndex),
+            0 | 2..=9 => Ok(share),
+            _ => Err(Error::InvalidThreshold),
+        }
+    }
+
+    /// Corrects up to 4 wrong characters, or up to 8 written as `?`, and
+    /// returns the valid string with the positions of the characters that
+    /// changed. Nothing outside the data part is corrected.
+    pub fn correct(s: &str) -> Result<(Self, Vec<usize>), Error> {
+        let (mut data, erasures) = data_part(s, true)?;
+        let code = Code::for_len(data.len()).ok_or(Error::InvalidLength)?;
+        let received = data.clone();
+        if !erasures.is_empty() || !code.checksum.verify(&data) {
+            code.correct(&mut data, &erasures)?;
+        }
+        let positions = (0..data.len())
+            .filter(|&j| data[j] != received[j] || erasures.contains(&j))
+            .map(|j| j + PREFIX.len())
+            .collect();
+        Ok((Self::from_data(data, code)?, positions))
+    }
+
+    /// The share at `share_index` of the polynomial through `shares`, the
+    /// secret for `s`. Any shares beyond the threshold are not used.
+    pub fn interpolate(shares: &[Codex32], share_index: char) -> Result<Self, Error> {
+        let first = shares.first().ok_or(Error::TooFewShares)?;
+        let threshold = first.threshold() as usize;
+        if threshold == 0 {
+            return Err(Error::InvalidThreshold);
+        }
+        let x = share_index
+            .is_ascii()
+            .then(|| from_char(share_index as u8))
+            .flatten()
+            .ok_or(Error::InvalidShareIndex)?;
+        if shares
+            .iter()
+            .any(|s| s.data.len() != first.data.len() || s.data[..5] != first.data[..5])
+        {
+            return Err(Error::Mismatch);
+        }
+        if shares.len() < threshold {
+            return Err(Error::TooFewShares);
+        }
+
+        let shares = &shares[..threshold];
+        let xs: Vec<GF32> = shares.iter().map(|s| s.data[5]).collect();
+        for (i, x) in xs.iter().enumerate() {
+            if xs[..i].contains(x) {
+                return Err(Error::DuplicateIndex);
+            }
+        }
+        let mut data = vec![GF32::ZERO; first.data.len()];
+        Lagrange::at(&xs, x).combine(shares.iter().map(|s| &s.data), &mut data);
+        Ok(Self { data })
+    }
+
+    /// `0` for an unshared secret, else the number of shares needed.
+    pub fn threshold(&self) -> u8 {
+        match to_char(self.data[0]) {
+            c @ '0'..='9' => c as u8 - b'0',
+            _ => u8::MAX,
+        }
+    }
+
+    pub fn identifier(&self) -> String {
+        self.data[1..5].iter().map(|&a| to_char(a)).collect()
+    }
+
+    pub fn share_index(&self) -> char {
+        to_char(self.data[5])
+    }
+
+    /// The seed, or share data, without the padding bits.
+    pub fn payload(&self) -> Vec<u8> {
+        let code = Code::for_len(self.data.len()).unwrap();
+        let payload = &self.data[HEADER_LEN..self.data.len() - code.checksum.len];
+        let mut out = Vec::with_capacity(5 * payload.len() / 8);
+        let (mut acc, mut bits) = (0u32, 0);
+        for &a in payload {
+            acc = acc << 5 | a.0 as u32;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                out.push((acc >> bits) as u8);
+            }
+        }
+        out
+    }
+}
+
+/// The lowercase string.
+impl fmt::Display for Codex32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(PREFIX)?;
+        for &a in &self.data {
+            fmt::Write::write_char(f, to_char(a))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn roots() {
+        for code in [&SHORT, &LONG] {
+            let generator = code.checksum.generator();
+            let alpha = GF1024::exp(code.alpha);
+            for i in 0..8 {
+                let root = alpha.pow(code.first_root + i);
+                let value = generator
+                    .coeffs()
+                    .iter()
+                    .rev()
+                    .fold(GF1024::ZERO, |acc, &c| acc * root + embed(c));
+                assert_eq!(value, GF1024::ZERO);
+            }
+        }
+        assert_eq!(GF1024::exp(SHORT.alpha).pow(93), GF1024::ONE);
+    }
+
+    #[test]
+    fn vectors() {
+        // BIP-93 test vectors 1, 2 and 5
+        let secret = Codex32::parse("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw").unwrap();
+        assert_eq!((secret.threshold(), secret.share_index()), (0, 's'));
+        assert_eq!(secret.payload(), hex("318c6318c6318c6318c6318c6318c631"));
+
+        let shares = [
+            "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM",
+            "MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN",
+        ]
+        .map(|s| Codex32::parse(s).unwrap());
+        let secret = Codex32::interpolate(&shares, 's').unwrap();
+        assert_eq!(secret.payload(), hex("d1808e096b35b209ca12132b264662a5"));
+        assert_eq!(
+            Codex32::interpolate(&shares, 'D').unwrap().to_string(),
+            "ms12namedll4f8jlh4e5vdvuldlfxu2jhdnlsm97xvenrxeg"
+        );
+        assert_eq!(Codex32::interpolate(&shares, 'a').unwrap(), shares[0]);
+
+        let long = "MS100C8VSM32ZXFGUHPCHTLUPZRY9X8GF2TVDW0S3JN54KHCE6MUA7LQPZYGSFJD6AN074RXVCEMLH8WU3TK925ACDEFGHJKLMNPQRSTUVWXY06FHPV80UNDVARHRAK";
+        let secret = Codex32::parse(long).unwrap();
+        assert_eq!(
+            secret.payload(),
+            hex("dc5423251cb87175ff8110c8531d0952d8d73e1194e95b5f19d6f9df7c01111104c9baecdfea8cccc677fb9ddc8aec5553b86e528bcadfdcc201c17c638c47e9")
+        );
+        // with padding bits of 001 where this crate writes zeros
+        let seed = secret.payload();
+        let ours = Codex32::from_seed(0, "0c8v", 's', &seed).unwrap();
+        assert_eq!(ours.payload(), seed);
+        assert_eq!(ours.to_string()[..111], long.to_lowercase()[..111]);
+    }
+
+    #[test]
+    fn split_and_recover() {
+        // k - 1 random shares and the secret fix the rest, for every length
+        for len in [16, 20, 32, 46, 47, 64] {
+            let seed: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(97) ^ 0x5a).collect();
+            let secret = Codex32::from_seed(3, "cash", 's', &seed).unwrap();
+            assert_eq!(secret.payload(), seed);
+            assert_eq!(Codex32::parse(&secret.to_string()), Ok(secret.clone()));
+
+            let a = Codex32::from_seed(3, "cash", 'a', &[0x11; 64][..len]).unwrap();
+            let c = Codex32::from_seed(3, "cash", 'c', &[0x22; 64][..len]).unwrap();
+            let base = [secret.clone(), a.clone(), c.clone()];
+            let shares: Vec<Codex32> = ['d', 'e', 'f']
+                .iter()
+                .map(|&i| Codex32::interpolate(&base, i).unwrap())
+                .collect();
+            for share in &shares {
+                assert_eq!(
+                    Codex32::parse(&share.to_string().to_uppercase()),
+                    Ok(share.clone())
+                );
+            }
+            let recovered = Codex32::interpolate(&[shares[2].clone(), a, shares[0].clone()], 's');
+            assert_eq!(recovered, Ok(secret));
+        }
+    }
+
+    #[test]
+    fn correction() {
+        let seed: Vec<u8> = (0..64).collect();
+        for seed in [&seed[..16], &seed[..]] {
+            let share = Codex32::from_seed(2, "fax0", 'x', seed).unwrap();
+            let s = share.to_string();
+            let n = s.len();
+
+            let mut wrong: Vec<u8> = s.clone().into_bytes();
+            for (i, c) in [(3, b'q'), (17, b'l'), (30, b'z'), (n - 1, b'p')] {
+                wrong[i] = if wrong[i] == c { b'7' } else { c };
+            }
+            let wrong = String::from_utf8(wrong).unwrap();
+            assert_eq!(Codex32::parse(&wrong), Err(Error::InvalidChecksum));
+            assert_eq!(
+                Codex32::correct(&wrong),
+                Ok((share.clone(), vec![3, 17, 30, n - 1]))
+            );
+
+            let mut erased = s.clone().into_bytes();
+            for i in [5, 6, 7, 20, 21, 40, n - 3, n - 2] {
+                erased[i] = b'?';
+            }
+            let (corrected, positions) =
+                Codex32::correct(core::str::from_utf8(&erased).unwrap()).unwrap();
+            assert_eq!(corrected, share);
+            assert_eq!(positions, [5, 6, 7, 20, 21, 40, n - 3, n - 2]);
+
+            assert_eq!(Codex32::correct(&s), Ok((share, vec![])));
+        }
+
+        // five errors are beyond the code
+        let s = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+        assert_eq!(
+            Codex32::correct("ms10tests0xxxxxxx0xxxxxxxx0xxxxxxxx0x4n0vca9cmczlw"),
+            Err(Error::TooManyErrors)
+        );
+        assert_eq!(Codex32::correct(s).unwrap().1, []);
+    }
+
+    #[test]
+    fn errors() {
+        let s = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+        assert_eq!(
+            Codex32::parse(&s.replace("ms1", "mx1")),
+            Err(Error::InvalidPrefix)
+        );
+        assert_eq!(
+            Codex32::parse(&s.replace("test", "Test")),
+            Err(Error::MixedCase)
+        );
+        assert_eq!(
+            Codex32::parse(&s.replace("test", "tbst")),
+            Err(Error::InvalidCharacter {
+                position: 5,
+                character: 'b'
+            })
+        );
+        assert_eq!(Codex32::parse(&s[..47]), Err(Error::InvalidLength));
+        assert_eq!(
+            Codex32::parse(&s.replace("4n", "n4")),
+            Err(Error::InvalidChecksum)
+        );
+
+        let data = |s: &str| data_part(s, false).unwrap().0;
+        let with_checksum = |header: &str| {
+            let mut d = data(&["ms1", header, &s[9..35]].concat());
+            d.truncate(32);
+            let c = SHORT.checksum.create(&d);
+            d.extend(c);
+            d.iter().map(|&a| to_char(a)).collect::<String>()
+        };
+        let parse = |d: &str| Codex32::parse(&["ms1", d].concat());
+        assert_eq!(
+            parse(&with_checksum("0testa")),
+            Err(Error::InvalidShareIndex)
+        );
+        assert!(parse(&with_checksum("2testa")).is_ok());
+        assert_eq!(
+            parse(&with_checksum("stests")),
+            Err(Error::InvalidThreshold)
+        );
+
+        assert_eq!(
+            Codex32::from_seed(1, "test", 's', &[0; 16]),
+            Err(Error::InvalidThreshold)
+        );
+        assert_eq!(
+            Codex32::from_seed(2, "tes", 's', &[0; 16]),
+            Err(Error::InvalidIdentifier)
+        );
+        assert_eq!(
+            Codex32::from_seed(2, "tesb", 's', &[0; 16]),
+            Err(Error::InvalidIdentifier)
+        );
+        assert_eq!(
+            Codex32::from_seed(0, "test", 'a', &[0; 16]),
+            Err(Error::InvalidShareIndex)
+        );
+        assert_eq!(
+            Codex32::from_seed(2, "test", 'b', &[0; 16]),
+            Err(Error::InvalidShareIndex)
+        );
+        assert_eq!(
+            Codex32::from_seed(2, "test", 'a', &[0; 15]),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(
+            Codex32::from_seed(2, "test", 'a', &[0; 65]),
+            Err(Error::InvalidLength)
+        );
+
+        let share = |id, i| Codex32::from_seed(2, id, i, &[0; 16]).unwrap();
+        let secret = Codex32::parse(s).unwrap();
+        assert_eq!(
+            Codex32::interpolate(&[secret], 'a'),
+            Err(Error::InvalidThreshold)
+        );
+        assert_eq!(Codex32::interpolate(&[], 'a'), Err(Error::TooFewShares));
+        assert_eq!(
+            Codex32::interpolate(&[share("test", 'a')], 's'),
+            Err(Error::TooFewShares)
+        );
+        assert_eq!(
+            Codex32::interpolate(&[share("test", 'a'), share("tess", 'c')], 's'),
+            Err(Error::Mismatch)
+        );
+        assert_eq!(
+            Codex32::interpolate(&[share("test", 'a'), share("test", 'a')], 's'),
+            Err(Error::DuplicateIndex)
+        );
+        assert_eq!(
+            Codex32::interpolate(&[share("test", 'a'), share("test", 'c')], 'b'),
+            Err(Error::InvalidShareIndex)
+        );
+    }
+}
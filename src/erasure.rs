@@ -0,0 +1,331 @@
+//! # FFT Erasure Code
+//!
+//! Systematic Reed–Solomon erasure coding in `O(n log n)` on top of the
+//! [additive FFT](crate::additive_fft), in the style of Leopard-RS. The data
+//! shards plus the parity shards rounded up to a power of two must fit into
+//! the field, 256 for `GF(2^8)` and 65536 for `GF(2^16)`.
+//!
+//! Shard `i` is the value of the codeword at the field element with bits `i`,
+//! with the parity shards in front of the data shards, rounded up to a power of
+//! two. A codeword is valid if its inverse transform vanishes on the last
+//! block, which makes encoding a single inverse transform per block of data
+//! and one forward transform.
+//!
+//! ```
+//! use gf::{erasure::ErasureCoder, GF};
+//!
+//! let coder = ErasureCoder::<GF<u8>>::new(3, 2).unwrap();
+//!
+//! let data = [b"abcd", b"efgh", b"ijkl"].map(|d| GF::from_slice(d).to_vec());
+//! let mut parity = vec![vec![GF(0); 4]; 2];
+//! coder.encode(&data, &mut parity).unwrap();
+//!
+//! let mut shards: Vec<_> = data.iter().chain(&parity).cloned().map(Some).collect();
+//! shards[0] = None;
+//! shards[3] = None;
+//! coder.reconstruct(&mut shards).unwrap();
+//!
+//! assert_eq!(shards[0].as_deref(), Some(GF::from_slice(b"abcd")));
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{additive_fft::AdditiveFft, BinaryField};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    /// No data or parity shards, or more shards than field elements.
+    InvalidShardCount,
+    /// Fewer shards present than there are data shards.
+    TooFewShards,
+    /// Shards of different lengths, or the wrong number of them.
+    ShardMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidShardCount => "invalid number of shards".fmt(f),
+            Error::TooFewShards => "too few shards to reconstruct".fmt(f),
+            Error::ShardMismatch => "shard count or length mismatch".fmt(f),
+        }
+    }
+}
+
+/// Encoder and decoder for a fixed number of data and parity shards.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ErasureCoder<F> {
+    data_shards: usize,
+    parity_shards: usize,
+    /// transform over one block of parity, `parity_shards` rounded up
+    block: AdditiveFft<F>,
+    /// transform over the whole codeword
+    full: AdditiveFft<F>,
+}
+
+impl<F: BinaryField> ErasureCoder<F> {
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        if data_shards == 0 || parity_shards == 0 {
+            return Err(Error::InvalidShardCount);
+        }
+
+        let block = parity_shards.next_power_of_two();
+        let total = (block + data_shards).next_power_of_two();
+        if total.trailing_zeros() > F::DEGREE {
+            return Err(Error::InvalidShardCount);
+        }
+
+        Ok(Self {
+            data_shards,
+            parity_shards,
+            block: AdditiveFft::new(block.trailing_zeros()),
+            full: AdditiveFft::new(total.trailing_zeros()),
+        })
+    }
+
+    #[inline]
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    #[inline]
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    #[inline]
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    /// Position of shard `i` (data first, then parity) in the codeword.
+    fn position(&self, i: usize) -> usize {
+        if i < self.data_shards {
+            self.block.size() + i
+        } else {
+            i - self.data_shards
+        }
+    }
+
+    /// Computes the parity shards from the data shards.
+    pub fn encode<D, P>(&self, data: &[D], parity: &mut [P]) -> Result<(), Error>
+    where
+        D: AsRef<[F]>,
+        P: AsMut<[F]>,
+    {
+        if data.len() != self.data_shards || parity.len() != self.parity_shards {
+            return Err(Error::ShardMismatch);
+        }
+        let len = data[0].as_ref().len();
+        if data.iter().any(|d| d.as_ref().len() != len)
+            || parity.iter_mut().any(|p| p.as_mut().len() != len)
+        {
+            return Err(Error::ShardMismatch);
+        }
+
+        let block = self.block.size();
+        let mut sum = vec![vec![F::ZERO; len]; block];
+        let mut work = vec![vec![F::ZERO; len]; block];
+        for (i, chunk) in data.chunks(block).enumerate() {
+            for (w, d) in work.iter_mut().zip(chunk) {
+                w.copy_from_slice(d.as_ref());
+            }
+            for w in &mut work[chunk.len()..] {
+                w.fill(F::ZERO);
+            }
+
+            self.block
+                .ifft_slices(&mut work, F::from_bits(((i + 1) * block) as u32));
+            for (s, w) in sum.iter_mut().zip(&work) {
+                for (s, &w) in s.iter_mut().zip(w) {
+                    *s += w;
+                }
+            }
+        }
+
+        self.block.fft_slices(&mut sum, F::ZERO);
+        for (p, s) in parity.iter_mut().zip(&sum) {
+            p.as_mut().copy_from_slice(s);
+        }
+        Ok(())
+    }
+
+    /// Checks that the parity shards match the data shards.
+    pub fn verify<S: AsRef<[F]>>(&self, shards: &[S]) -> Result<bool, Error> {
+        if shards.len() != self.total_shards() {
+            return Err(Error::ShardMismatch);
+        }
+        let (data, parity) = shards.split_at(self.data_shards);
+        let len = data[0].as_ref().len();
+
+        let mut expected = vec![vec![F::ZERO; len]; self.parity_shards];
+        self.encode(data, &mut expected)?;
+        Ok(expected
+            .iter()
+            .zip(parity)
+            .all(|(e, p)| e[..] == *p.as_ref()))
+    }
+
+    /// Fills in every missing shard, data first, then parity, as long as at
+    /// least as many shards as there are data shards are present.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<F>>]) -> Result<(), Error> {
+        if shards.len() != self.total_shards() {
+            return Err(Error::ShardMismatch);
+        }
+        let present = shards.iter().flatten().count();
+        if present < self.data_shards {
+            return Err(Error::TooFewShards);
+        }
+        if present == shards.len() {
+            return Ok(());
+        }
+
+        let len = shards.iter().flatten().next().unwrap().len();
+        if shards.iter().flatten().any(|s| s.len() != len) {
+            return Err(Error::ShardMismatch);
+        }
+
+        let n = self.full.size();
+        let mut erased = vec![false; n];
+        erased[self.parity_shards..self.block.size()].fill(true);
+        for (i, shard) in shards.iter().enumerate() {
+            erased[self.position(i)] = shard.is_none();
+        }
+        let locator = erasure_locator_logs::<F>(&erased);
+
+        // with E(x) = prod (x - w_e) over the erasures, the values of
+        // g(x) = f(x) E(x) are known everywhere, and at an erasure
+        // f(w_e) = g'(w_e) / E'(w_e)
+        let mut work = vec![vec![F::ZERO; len]; n];
+        for (i, shard) in shards.iter().enumerate() {
+            if let Some(shard) = shard {
+                let pos = self.position(i);
+                work[pos].copy_from_slice(shard);
+                F::mul_slice(&mut work[pos], F::exp(locator[pos]));
+            }
+        }
+
+        self.full.ifft_slices(&mut work, F::ZERO);
+        self.full.derivative_slices(&mut work);
+        self.full.fft_slices(&mut work, F::ZERO);
+
+        let order = (1 << F::DEGREE) - 1;
+        for (i, shard) in shards.iter_mut().enumerate() {
+            if shard.is_none() {
+                let pos = self.position(i);
+                let mut value = core::mem::take(&mut work[pos]);
+                F::mul_slice(&mut value, F::exp(order - locator[pos]));
+                *shard = Some(value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `log E(w_i)` for every position that is not erased and `log E'(w_i)` for
+/// every position that is, with `E(x) = prod (x - w_e)` over the erasures.
+///
+/// Both are `sum_e log(w_i + w_e) = sum_e log(w_(i ^ e))` with the term `e = i`
+/// left out, a XOR convolution of the erasures with the log table, which a
+/// Walsh–Hadamard transform modulo `2^m - 1` does in `O(n log n)`.
+fn erasure_locator_logs<F: BinaryField>(erased: &[bool]) -> Vec<usize> {
+    let order = (1u64 << F::DEGREE) - 1;
+
+    let mut indicator: Vec<u64> = erased.iter().map(|&e| e as u64).collect();
+    let mut logs: Vec<u64> = (0..erased.len() as u32)
+        .map(|i| F::from_bits(i).log().unwrap_or(0) as u64)
+        .collect();
+
+    walsh_hadamard(&mut indicator, order);
+    walsh_hadamard(&mut logs, order);
+    for (a, &b) in indicator.iter_mut().zip(&logs) {
+        *a = *a * b % order;
+    }
+    walsh_hadamard(&mut indicator, order);
+
+    // the transform is its own inverse up to a factor of n = 2^k, and
+    // 2^-k = 2^(m - k mod m) modulo 2^m - 1
+    let k = erased.len().trailing_zeros();
+    let n_inv = 1u64 << ((F::DEGREE - k % F::DEGREE) % F::DEGREE);
+    indicator
+        .iter()
+        .map(|&v| (v * n_inv % order) as usize)
+        .collect()
+}
+
+fn walsh_hadamard(data: &mut [u64], modulus: u64) {
+    let mut half = 1;
+    while half < data.len() {
+        for block in data.chunks_mut(2 * half) {
+            let (lo, hi) = block.split_at_mut(half);
+            for (a, b) in lo.iter_mut().zip(hi) {
+                let (x, y) = (*a, *b);
+                *a = (x + y) % modulus;
+                *b = (x + modulus - y) % modulus;
+            }
+        }
+        half <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::{ErasureCoder, Error};
+    use crate::{BinaryField, GF2m, GF};
+
+    fn roundtrip<F: BinaryField>(data_shards: usize, parity_shards: usize, lost: &[usize]) {
+        let coder = ErasureCoder::<F>::new(data_shards, parity_shards).unwrap();
+        let mask = (1 << F::DEGREE) - 1;
+        let data: Vec<Vec<F>> = (0..data_shards as u32)
+            .map(|i| {
+                (0..8u32)
+                    .map(|j| F::from_bits(i.wrapping_mul(2654435761).rotate_left(j) & mask))
+                    .collect()
+            })
+            .collect();
+        let mut parity = vec![vec![F::ZERO; 8]; parity_shards];
+        coder.encode(&data, &mut parity).unwrap();
+
+        let original: Vec<_> = data.iter().chain(&parity).cloned().map(Some).collect();
+        assert!(coder
+            .verify(&data.iter().chain(&parity).collect::<Vec<_>>())
+            .unwrap());
+
+        let mut shards = original.clone();
+        for &i in lost {
+            shards[i] = None;
+        }
+        coder.reconstruct(&mut shards).unwrap();
+        assert_eq!(shards, original);
+    }
+
+    #[test]
+    fn gf256() {
+        roundtrip::<GF<u8>>(3, 2, &[0, 4]);
+        roundtrip::<GF<u8>>(10, 5, &[1, 2, 3, 11, 14]);
+        roundtrip::<GF<u8>>(190, 64, &(0..64).map(|i| i * 4).collect::<Vec<_>>());
+        roundtrip::<GF<u8>>(1, 7, &[0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn gf65536() {
+        type F = GF2m<0x1002D>;
+        roundtrip::<F>(1000, 300, &(0..300).map(|i| i * 4 + 1).collect::<Vec<_>>());
+        roundtrip::<F>(17, 3, &[16, 17, 18]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            ErasureCoder::<GF<u8>>::new(200, 100),
+            Err(Error::InvalidShardCount)
+        );
+
+        let coder = ErasureCoder::<GF<u8>>::new(2, 1).unwrap();
+        let mut shards = vec![Some(vec![GF(1)]), None, None];
+        assert_eq!(coder.reconstruct(&mut shards), Err(Error::TooFewShards));
+    }
+}
@@ -0,0 +1,291 @@
+//! # Reed–Solomon Codes in Evaluation Form
+//!
+//! The message `f_0, ..., f_{k-1}` is sent as `f(x_0), ..., f(x_{n-1})` at
+//! distinct points, the form of non-systematic Reed–Solomon codes and of
+//! Shamir shares. Up to `(n - k) / 2` wrong symbols are corrected straight
+//! from the received values, by two classic decoders:
+//!
+//! - [Berlekamp–Welch] solves the linear system `Q(x_i) = y_i E(x_i)` for an
+//!   error locator `E` and `Q = f E`, in `O(n^3)`.
+//! - [Gao] interpolates the received word to `g_1`, runs the extended
+//!   Euclidean algorithm on `prod (x - x_i)` and `g_1` up to half way, and
+//!   divides the remainder by its cofactor, in quasi-linear time with fast
+//!   [`Poly`] arithmetic.
+//!
+//! Both return the same message whenever there is one within the radius,
+//! which makes them a cross-check for each other and for the syndrome-based
+//! [`ReedSolomon`](crate::reed_solomon::ReedSolomon) decoder.
+//!
+//! [Berlekamp–Welch]: EvaluationCode::decode_berlekamp_welch
+//! [Gao]: EvaluationCode::decode_gao
+//!
+//! ```
+//! use gf::{evaluation::EvaluationCode, GF};
+//!
+//! let points: Vec<GF<u8>> = (1..=10).map(GF).collect();
+//! let code = EvaluationCode::new(&points, 4);
+//!
+//! let message = GF::from_slice(b"gaos").to_vec();
+//! let mut received = code.encode(&message);
+//! received[2] = GF(0);
+//! received[7] += GF(1);
+//! received[9] = GF(42);
+//!
+//! assert_eq!(code.decode_berlekamp_welch(&received), Ok(message.clone()));
+//! assert_eq!(code.decode_gao(&received), Ok(message));
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    linear_code::row_reduce, multipoint::SubproductTree, reed_solomon::Error, Field, Poly,
+};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EvaluationCode<F> {
+    tree: SubproductTree<F>,
+    k: usize,
+}
+
+impl<F: Field> EvaluationCode<F> {
+    /// The code of messages with `k` symbols evaluated at `points`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero or above the number of points, or the points
+    /// contain duplicates.
+    pub fn new(points: &[F], k: usize) -> Self {
+        let n = points.len();
+        assert!(0 < k && k <= n, "message length out of range");
+
+        let mut sorted: Vec<u128> = points.iter().map(|x| x.to_index()).collect();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), n, "duplicate points");
+
+        Self {
+            tree: SubproductTree::new(points),
+            k,
+        }
+    }
+
+    #[inline]
+    pub fn points(&self) -> &[F] {
+        self.tree.points()
+    }
+
+    /// Codeword length.
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.tree.points().len()
+    }
+
+    /// Message length.
+    #[inline]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Number of errors both decoders correct, `(n - k) / 2`.
+    #[inline]
+    pub fn radius(&self) -> usize {
+        (self.n() - self.k) / 2
+    }
+
+    /// `f(x_i)` for the polynomial `f` with the coefficients `message`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `message` does not have exactly [`k`](Self::k) symbols.
+    pub fn encode(&self, message: &[F]) -> Vec<F> {
+        assert_eq!(message.len(), self.k, "wrong message length");
+        self.tree.evaluate(&Poly::new(message.to_vec()))
+    }
+
+    /// Decodes by solving for the error locator `E`, monic of degree
+    /// [`radius`](Self::radius), and `Q` of degree below `radius + k` with
+    /// `Q(x_i) = y_i E(x_i)` at every point. Any solution has `Q = f E`
+    /// when there are few enough errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `received` does not have one symbol per point.
+    pub fn decode_berlekamp_welch(&self, received: &[F]) -> Result<Vec<F>, Error> {
+        assert_eq!(received.len(), self.n(), "wrong word length");
+
+        // unknowns q_0, ..., q_{e+k-1}, then e_0, ..., e_{e-1}, then the
+        // right-hand side y_i x_i^e from the leading coefficient of E
+        let e = self.radius();
+        let unknowns = 2 * e + self.k;
+        let mut rows: Vec<Vec<F>> = self
+            .points()
+            .iter()
+            .zip(received)
+            .map(|(&x, &y)| {
+                let mut row = Vec::with_capacity(unknowns + 1);
+                let mut power = F::ONE;
+                for _ in 0..e + self.k {
+                    row.push(power);
+                    power *= x;
+                }
+                let mut power = y;
+                for _ in 0..e {
+                    row.push(-power);
+                    power *= x;
+                }
+                row.push(power);
+                row
+            })
+            .collect();
+
+        let pivots = row_reduce(&mut rows, 0..unknowns);
+        if rows[pivots.len()..]
+            .iter()
+            .any(|row| !row[unknowns].is_zero())
+        {
+            return Err(Error::TooManyErrors);
+        }
+        let mut solution = vec![F::ZERO; unknowns];
+        for (row, &col) in rows.iter().zip(&pivots) {
+            solution[col] = row[unknowns];
+        }
+
+        let mut locator = solution.split_off(e + self.k);
+        locator.push(F::ONE);
+        let (f, rem) = Poly::new(solution).div_rem(&Poly::new(locator));
+        if !rem.is_zero() {
+            return Err(Error::TooManyErrors);
+        }
+        self.check(f, received)
+    }
+
+    /// Decodes by Gao's algorithm: with `g_0 = prod (x - x_i)` and `g_1` the
+    /// interpolation of `received`, the extended Euclidean algorithm stopped
+    /// at the first remainder `g` of degree below `(n + k) / 2` gives
+    /// `g = u g_0 + v g_1` with `f = g / v`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `received` does not have one symbol per point.
+    pub fn decode_gao(&self, received: &[F]) -> Result<Vec<F>, Error> {
+        assert_eq!(received.len(), self.n(), "wrong word length");
+
+        let g1 = self.tree.interpolate(received);
+        let (g, v) = self
+            .tree
+            .root()
+            .partial_gcd(&g1, (self.n() + self.k).div_ceil(2));
+        let (f, rem) = g.div_rem(&v);
+        if !rem.is_zero() {
+            return Err(Error::TooManyErrors);
+        }
+        self.check(f, received)
+    }
+
+    /// The coefficients of `f` as a message, if it has degree below `k` and
+    /// its codeword is within the radius of `received`.
+    fn check(&self, f: Poly<F>, received: &[F]) -> Result<Vec<F>, Error> {
+        if f.degree().is_some_and(|d| d >= self.k) {
+            return Err(Error::TooManyErrors);
+        }
+        let errors = self
+            .tree
+            .evaluate(&f)
+            .iter()
+            .zip(received)
+            .filter(|(a, b)| a != b)
+            .count();
+        if errors > self.radius() {
+            return Err(Error::TooManyErrors);
+        }
+
+        let mut message = f.into_coeffs();
+        message.resize(self.k, F::ZERO);
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{reed_solomon::ReedSolomon, GFp, GF};
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    /// Adds nonzero errors at `count` distinct positions.
+    fn corrupt<F: Field>(word: &mut [F], count: usize, rng: &mut impl RngCore) {
+        let mut positions: Vec<usize> = (0..word.len()).collect();
+        for i in 0..count {
+            let j = i + rng.next_u32() as usize % (positions.len() - i);
+            positions.swap(i, j);
+            let e = 1 + rng.next_u32() as u128 % F::ORDER;
+            word[positions[i]] += F::from_index(e);
+        }
+    }
+
+    #[test]
+    fn up_to_radius() {
+        let mut rng = StdRng::seed_from_u64(0x1234_5678);
+        let points: Vec<GF<u8>> = (0..40).map(|i| GF(i * 3 + 1)).collect();
+        for k in [1, 7, 16, 39, 40] {
+            let code = EvaluationCode::new(&points, k);
+            for errors in 0..=code.radius() {
+                let message: Vec<GF<u8>> = (0..k).map(|_| GF(rng.next_u32() as u8)).collect();
+                let mut received = code.encode(&message);
+                corrupt(&mut received, errors, &mut rng);
+
+                assert_eq!(code.decode_berlekamp_welch(&received), Ok(message.clone()));
+                assert_eq!(code.decode_gao(&received), Ok(message));
+            }
+        }
+    }
+
+    #[test]
+    fn beyond_radius() {
+        // both decoders agree, on a failure or on a codeword within the radius
+        let mut rng = StdRng::seed_from_u64(0xdead_beef);
+        let points: Vec<GFp<17>> = (0..16).map(GFp::new).collect();
+        let code = EvaluationCode::new(&points, 6);
+        for _ in 0..200 {
+            let message: Vec<GFp<17>> = (0..6).map(|_| GFp::new(rng.next_u32() as u64)).collect();
+            let mut received = code.encode(&message);
+            corrupt(&mut received, 6 + rng.next_u32() as usize % 6, &mut rng);
+
+            let bw = code.decode_berlekamp_welch(&received);
+            assert_eq!(bw, code.decode_gao(&received));
+            if let Ok(decoded) = bw {
+                assert_ne!(decoded, message);
+                let distance = code
+                    .encode(&decoded)
+                    .iter()
+                    .zip(&received)
+                    .filter(|(a, b)| a != b)
+                    .count();
+                assert!(distance <= code.radius());
+            }
+        }
+    }
+
+    #[test]
+    fn matches_syndrome_decoder() {
+        // evaluation at alpha^0, ..., alpha^254 is the narrow-sense cyclic
+        // code with zeros alpha^1, ..., alpha^(n-k)
+        let mut rng = StdRng::seed_from_u64(0x0bad_cafe);
+        let alpha = GF(2u8);
+        let points: Vec<GF<u8>> = (0..255).map(|i| alpha.pow(i)).collect();
+        let code = EvaluationCode::new(&points, 223);
+        let rs = ReedSolomon::new(32, alpha, 1);
+
+        let message: Vec<GF<u8>> = (0..223).map(|_| GF(rng.next_u32() as u8)).collect();
+        let mut received = code.encode(&message);
+        corrupt(&mut received, 16, &mut rng);
+
+        let mut codeword: Vec<GF<u8>> = received.iter().rev().copied().collect();
+        assert_eq!(rs.decode(&mut codeword, &[]), Ok(16));
+        codeword.reverse();
+
+        let decoded = code.decode_gao(&received).unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(code.encode(&decoded), codeword);
+        assert_eq!(code.decode_berlekamp_welch(&received), Ok(message));
+    }
+}
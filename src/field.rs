@@ -0,0 +1,112 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{
+    fmt::Debug,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// # Finite Field
+///
+/// Common interface of the field element types in this crate, so that
+/// algorithms can be written once and used with any of them.
+///
+/// ```
+/// use gf::{Field, GF};
+///
+/// fn square<F: Field>(x: F) -> F {
+///     x * x
+/// }
+///
+/// assert_eq!(square(GF(7u8)), GF(7u8).pow(2));
+/// ```
+pub trait Field:
+    Copy
+    + Eq
+    + Debug
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Product
+{
+    /// The additive identity.
+    const ZERO: Self;
+    /// The multiplicative identity.
+    const ONE: Self;
+    /// Number of nonzero elements, one less than the size, which does not fit
+    /// in a `u128` for `GF(2^128)`.
+    const ORDER: u128;
+
+    /// Multiplicative inverse, the result for zero is unspecified.
+    fn inv(self) -> Self;
+
+    fn pow(self, exp: usize) -> Self;
+
+    /// The element numbered `i`, for `i <= ORDER`. Zero and one are numbered 0
+    /// and 1.
+    fn from_index(i: u128) -> Self;
+
+    /// The number of `self` in [`from_index`](Self::from_index).
+    fn to_index(self) -> u128;
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    /// `dst[i] += c * src[i]` for every element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices differ in length.
+    fn mul_add_slice(dst: &mut [Self], src: &[Self], c: Self) {
+        assert_eq!(dst.len(), src.len(), "slice lengths differ");
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d += c * s;
+        }
+    }
+
+    /// `dst[i] *= c` for every element.
+    fn mul_slice(dst: &mut [Self], c: Self) {
+        for d in dst {
+            *d *= c;
+        }
+    }
+
+    /// Product of two nonempty coefficient vectors with a fast transform, or
+    /// `None` if the field has none of the needed size.
+    ///
+    /// [`Poly`](crate::Poly) multiplication uses this for large operands.
+    #[cfg(feature = "alloc")]
+    fn transform_mul(_a: &[Self], _b: &[Self]) -> Option<Vec<Self>> {
+        None
+    }
+}
+
+/// # Binary Field
+///
+/// A field of characteristic two, `GF(2^m)`, whose elements are the bit
+/// vectors of their coordinates in the polynomial basis.
+pub trait BinaryField: Field {
+    /// `m`, the dimension over `GF(2)`.
+    const DEGREE: u32;
+
+    fn from_bits(bits: u32) -> Self;
+
+    fn to_bits(self) -> u32;
+
+    /// Discrete logarithm to the base of a fixed primitive element, in
+    /// `0..ORDER`, `None` for zero.
+    fn log(self) -> Option<usize>;
+
+    /// The primitive element of [`log`](Self::log) raised to `e`.
+    fn exp(e: usize) -> Self;
+}
@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 pub const fn gen_tables_u8(genpoly: usize) -> ([usize; 256], [u8; 1023]) {
     let mut logtable: [usize; 256] = [0; 256];
     let mut alogtable: [u8; 1023] = [0; 1023];
@@ -31,3 +32,83 @@ pub const fn gen_tables_u8(genpoly: usize) -> ([usize; 256], [u8; 1023]) {
 
     (logtable, alogtable)
 }
+
+/// The smallest primitive element of the binary field defined by `poly`, for
+/// degrees up to 16. This is `x` itself whenever `poly` is primitive.
+pub const fn primitive_element(poly: u32) -> u32 {
+    let degree = 31 - poly.leading_zeros();
+    assert!(
+        degree >= 1 && degree <= 16,
+        "polynomial degree must be 1..=16"
+    );
+    let order = (1 << degree) - 1;
+
+    let mut generator = if order == 1 { 1 } else { 2 };
+    loop {
+        assert!(generator <= order, "polynomial is not irreducible");
+
+        let mut x = generator;
+        let mut i = 1;
+        while x != 1 && i <= order {
+            x = mul_slow(x, generator, poly, degree);
+            i += 1;
+        }
+        if i == order {
+            return generator;
+        }
+        generator += 1;
+    }
+}
+
+/// Log table of the binary field defined by `poly` to the base `generator`,
+/// with `N = 2^m` entries. `logtable[0]` is unused.
+pub const fn log_table_u16<const N: usize>(poly: u32, generator: u32) -> [u16; N] {
+    let degree = 31 - poly.leading_zeros();
+    assert!(N == 1 << degree, "table size does not match the degree");
+    let order = N as u32 - 1;
+
+    let mut logtable = [0; N];
+    let mut x = 1;
+    let mut i = 0;
+    while i < order {
+        logtable[x as usize] = i as u16;
+        x = mul_slow(x, generator, poly, degree);
+        i += 1;
+    }
+    logtable
+}
+
+/// Antilog table of the binary field defined by `poly` to the base
+/// `generator`, with `N = 2^(m + 1)` entries. It is repeated once so that sums
+/// of two logarithms can be looked up directly.
+pub const fn alog_table_u16<const N: usize>(poly: u32, generator: u32) -> [u16; N] {
+    let degree = 31 - poly.leading_zeros();
+    assert!(N == 2 << degree, "table size does not match the degree");
+    let order = (N as u32) / 2 - 1;
+
+    let mut alogtable = [0; N];
+    let mut x = 1;
+    let mut i = 0;
+    while i < order {
+        alogtable[i as usize] = x as u16;
+        alogtable[(i + order) as usize] = x as u16;
+        x = mul_slow(x, generator, poly, degree);
+        i += 1;
+    }
+    alogtable
+}
+
+const fn mul_slow(mut a: u32, mut b: u32, poly: u32, degree: u32) -> u32 {
+    let mut acc = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            acc ^= a;
+        }
+        b >>= 1;
+        a <<= 1;
+        if a >> degree != 0 {
+            a ^= poly;
+        }
+    }
+    acc
+}
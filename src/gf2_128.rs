@@ -0,0 +1,254 @@
+#![allow(clippy::suspicious_arithmetic_impl)]
+
+use core::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crate::Field;
+
+/// # GF(2^128)
+///
+/// The binary field modulo `x^128 + x^7 + x^2 + x + 1`, with bit `i` holding
+/// the coefficient of `x^i`. This is the field of XTS and CMAC; GCM uses the
+/// same polynomial with the bits in reverse order.
+///
+/// Too large for log tables, so multiplication is carry-less shift and add,
+/// and inversion Fermat's little theorem. Elements are big enough to share a
+/// 128-bit key as a single one.
+///
+/// ```
+/// use gf::GF2_128;
+///
+/// let x = GF2_128(2);
+/// assert_eq!(x.pow(128), GF2_128(0x87));
+///
+/// let key = GF2_128(0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210);
+/// assert_eq!(key * key.inv(), GF2_128::ONE);
+/// ```
+#[derive(PartialEq, Eq, Clone, Copy, Default, Hash, Debug)]
+#[repr(transparent)]
+pub struct GF2_128(pub u128);
+
+impl GF2_128 {
+    /// The terms of the modulus below `x^128`.
+    pub const POLY: u128 = 0x87;
+
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1);
+
+    /// Multiplicative inverse, zero for zero.
+    pub fn inv(self) -> Self {
+        // a^(2^128 - 2) = a^2 a^4 ... a^(2^127)
+        let mut square = self;
+        let mut acc = Self::ONE;
+        for _ in 1..128 {
+            square = square * square;
+            acc *= square;
+        }
+        acc
+    }
+
+    pub fn pow(self, mut exp: usize) -> Self {
+        let mut base = self;
+        let mut acc = Self::ONE;
+        while exp != 0 {
+            if exp & 1 == 1 {
+                acc *= base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        acc
+    }
+}
+
+impl From<GF2_128> for u128 {
+    #[inline]
+    fn from(GF2_128(v): GF2_128) -> Self {
+        v
+    }
+}
+
+macro_rules! gf2_128_fmt {
+    ($($trait:ident),*) => {$(
+        impl fmt::$trait for GF2_128 {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    )*}
+}
+
+gf2_128_fmt! { Display, Binary, Octal, LowerHex, UpperHex }
+
+impl Add for GF2_128 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl Sub for GF2_128 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl Neg for GF2_128 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+impl Mul for GF2_128 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let (mut a, mut b) = (self.0, rhs.0);
+        let mut acc = 0;
+        while b != 0 {
+            if b & 1 == 1 {
+                acc ^= a;
+            }
+            // a *= x, folding x^128 back in
+            a = (a << 1) ^ (Self::POLY & (a >> 127).wrapping_neg());
+            b >>= 1;
+        }
+        Self(acc)
+    }
+}
+
+impl Div for GF2_128 {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        assert!(rhs != Self::ZERO, "attempt to divide by zero");
+        self * rhs.inv()
+    }
+}
+
+macro_rules! gf2_128_assign_ops {
+    ($(impl $assign_trait:ident { fn $assign_call:ident => $call:ident })*) => {$(
+        impl $assign_trait for GF2_128 {
+            #[inline]
+            fn $assign_call(&mut self, rhs: Self) {
+                *self = self.$call(rhs);
+            }
+        }
+    )*}
+}
+
+gf2_128_assign_ops! {
+    impl AddAssign { fn add_assign => add }
+    impl SubAssign { fn sub_assign => sub }
+    impl MulAssign { fn mul_assign => mul }
+    impl DivAssign { fn div_assign => div }
+}
+
+impl Sum for GF2_128 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl Product for GF2_128 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, Mul::mul)
+    }
+}
+
+impl Field for GF2_128 {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1);
+    const ORDER: u128 = u128::MAX;
+
+    #[inline]
+    fn inv(self) -> Self {
+        self.inv()
+    }
+
+    #[inline]
+    fn pow(self, exp: usize) -> Self {
+        self.pow(exp)
+    }
+
+    #[inline]
+    fn from_index(i: u128) -> Self {
+        Self(i)
+    }
+
+    #[inline]
+    fn to_index(self) -> u128 {
+        self.0
+    }
+}
+
+#[cfg(feature = "num-traits")]
+mod num_traits_impl {
+    use super::GF2_128;
+
+    impl num_traits::Zero for GF2_128 {
+        fn zero() -> Self {
+            Self::ZERO
+        }
+        fn is_zero(&self) -> bool {
+            *self == Self::ZERO
+        }
+    }
+
+    impl num_traits::One for GF2_128 {
+        fn one() -> Self {
+            Self::ONE
+        }
+    }
+
+    impl num_traits::Inv for GF2_128 {
+        type Output = Self;
+
+        fn inv(self) -> Self::Output {
+            self.inv()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GF2_128;
+
+    #[test]
+    fn reduction() {
+        let x = GF2_128(2);
+        assert_eq!(GF2_128(1 << 127) * x, GF2_128(0x87));
+        assert_eq!(x.pow(129), GF2_128(0x87 << 1));
+        // x^(2^128) = x in a field with 2^128 elements
+        let mut y = x;
+        for _ in 0..128 {
+            y = y * y;
+        }
+        assert_eq!(y, x);
+    }
+
+    #[test]
+    fn mul_div_inv() {
+        let mut a = GF2_128(0x0123_4567_89AB_CDEF_0011_2233_4455_6677);
+        let b = GF2_128(0xFFEE_DDCC_BBAA_9988_7766_5544_3322_1100);
+        for _ in 0..50 {
+            assert_eq!(a * a.inv(), GF2_128::ONE);
+            assert_eq!((a * b) / a, b);
+            assert_eq!(a * (b + GF2_128(5)), a * b + a * GF2_128(5));
+            a = a * b + GF2_128(1);
+        }
+        assert_eq!(GF2_128::ZERO.inv(), GF2_128::ZERO);
+    }
+}
@@ -0,0 +1,396 @@
+#![allow(clippy::suspicious_arithmetic_impl)]
+
+use core::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crate::{
+    gen_table::{alog_table_u16, log_table_u16, primitive_element},
+    BinaryField, Field,
+};
+
+/// # Binary Extension Field
+///
+/// `GF(2^m)` for `m <= 16`, defined by the irreducible polynomial `POLY` with
+/// bit `i` holding the coefficient of `x^i`, e.g. `0x1002D` for
+/// `x^16 + x^5 + x^3 + x^2 + 1`.
+///
+/// Elements must be below `2^m`. Multiplication goes through log tables which
+/// are built at compile time for every polynomial in use, `6 * 2^m` bytes for
+/// a field of degree `m`.
+///
+/// ```
+/// use gf::GF2m;
+///
+/// type GF65536 = GF2m<0x1002D>;
+///
+/// let x = GF65536::new(0x1234);
+/// assert_eq!(x * x.inv(), GF65536::ONE);
+/// assert_eq!(GF65536::DEGREE, 16);
+/// ```
+#[derive(PartialEq, Eq, Clone, Copy, Default, Hash, Debug)]
+#[repr(transparent)]
+pub struct GF2m<const POLY: u32>(pub u16);
+
+struct Tables<const POLY: u32>;
+
+/// A table for each possible degree `m`, of length `$len << m`; only the one
+/// matching `POLY` is built.
+macro_rules! sized_table {
+    ($table:ident, $len:literal) => {
+        match 31 - POLY.leading_zeros() {
+            1 => &$table::<{ $len << 1 }>(POLY, Self::GENERATOR),
+            2 => &$table::<{ $len << 2 }>(POLY, Self::GENERATOR),
+            3 => &$table::<{ $len << 3 }>(POLY, Self::GENERATOR),
+            4 => &$table::<{ $len << 4 }>(POLY, Self::GENERATOR),
+            5 => &$table::<{ $len << 5 }>(POLY, Self::GENERATOR),
+            6 => &$table::<{ $len << 6 }>(POLY, Self::GENERATOR),
+            7 => &$table::<{ $len << 7 }>(POLY, Self::GENERATOR),
+            8 => &$table::<{ $len << 8 }>(POLY, Self::GENERATOR),
+            9 => &$table::<{ $len << 9 }>(POLY, Self::GENERATOR),
+            10 => &$table::<{ $len << 10 }>(POLY, Self::GENERATOR),
+            11 => &$table::<{ $len << 11 }>(POLY, Self::GENERATOR),
+            12 => &$table::<{ $len << 12 }>(POLY, Self::GENERATOR),
+            13 => &$table::<{ $len << 13 }>(POLY, Self::GENERATOR),
+            14 => &$table::<{ $len << 14 }>(POLY, Self::GENERATOR),
+            15 => &$table::<{ $len << 15 }>(POLY, Self::GENERATOR),
+            16 => &$table::<{ $len << 16 }>(POLY, Self::GENERATOR),
+            _ => panic!("polynomial degree must be 1..=16"),
+        }
+    };
+}
+
+impl<const POLY: u32> Tables<POLY> {
+    const GENERATOR: u32 = primitive_element(POLY);
+    /// `2^m` entries.
+    const LOG: &'static [u16] = sized_table!(log_table_u16, 1);
+    /// `2^(m + 1)` entries.
+    const ALOG: &'static [u16] = sized_table!(alog_table_u16, 2);
+}
+
+impl<const POLY: u32> GF2m<POLY> {
+    /// `m`, the degree of `POLY`.
+    pub const DEGREE: u32 = 31 - POLY.leading_zeros();
+    /// Number of nonzero elements, `2^m - 1`.
+    pub const ORDER: u32 = (1 << Self::DEGREE) - 1;
+
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1);
+    /// The primitive element the log tables are built on, `x` for primitive
+    /// polynomials.
+    pub const GENERATOR: Self = Self(Tables::<POLY>::ALOG[1]);
+
+    /// # Panics
+    ///
+    /// Panics if `v` is not below `2^m`.
+    #[inline]
+    pub const fn new(v: u16) -> Self {
+        assert!((v as u32) <= Self::ORDER, "value out of range");
+        Self(v)
+    }
+
+    /// `GENERATOR^e`
+    #[inline]
+    pub fn exp(e: usize) -> Self {
+        Self(Tables::<POLY>::ALOG[e % Self::ORDER as usize])
+    }
+
+    /// Discrete logarithm to the base [`GENERATOR`](Self::GENERATOR), `None` for zero.
+    #[inline]
+    pub fn log(self) -> Option<usize> {
+        (self.0 != 0).then(|| Tables::<POLY>::LOG[self.0 as usize] as usize)
+    }
+
+    #[inline]
+    pub fn inv(self) -> Self {
+        match self.log() {
+            Some(l) => Self(Tables::<POLY>::ALOG[Self::ORDER as usize - l]),
+            None => Self::ZERO,
+        }
+    }
+
+    #[inline]
+    pub fn pow(self, exp: usize) -> Self {
+        match self.log() {
+            Some(l) => Self::exp(
+                (l as u64 * (exp % Self::ORDER as usize) as u64 % Self::ORDER as u64) as usize,
+            ),
+            None if exp == 0 => Self::ONE,
+            None => Self::ZERO,
+        }
+    }
+
+    #[inline]
+    pub fn from_slice(slice: &[u16]) -> &[Self] {
+        unsafe { core::slice::from_raw_parts(slice.as_ptr().cast::<Self>(), slice.len()) }
+    }
+
+    #[inline]
+    pub fn from_slice_mut(slice: &mut [u16]) -> &mut [Self] {
+        unsafe { core::slice::from_raw_parts_mut(slice.as_mut_ptr().cast::<Self>(), slice.len()) }
+    }
+}
+
+impl<const POLY: u32> From<GF2m<POLY>> for u16 {
+    #[inline]
+    fn from(GF2m(v): GF2m<POLY>) -> Self {
+        v
+    }
+}
+
+macro_rules! gf2m_fmt {
+    ($($trait:ident),*) => {$(
+        impl<const POLY: u32> fmt::$trait for GF2m<POLY> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    )*}
+}
+
+gf2m_fmt! { Display, Binary, Octal, LowerHex, UpperHex }
+
+impl<const POLY: u32> Add for GF2m<POLY> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl<const POLY: u32> Sub for GF2m<POLY> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl<const POLY: u32> Neg for GF2m<POLY> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+impl<const POLY: u32> Mul for GF2m<POLY> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        if self.0 == 0 || rhs.0 == 0 {
+            return Self::ZERO;
+        }
+        let log = Tables::<POLY>::LOG;
+        Self(Tables::<POLY>::ALOG[log[self.0 as usize] as usize + log[rhs.0 as usize] as usize])
+    }
+}
+
+impl<const POLY: u32> Div for GF2m<POLY> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        assert!(rhs != Self::ZERO, "attempt to divide by zero");
+        if self.0 == 0 {
+            return Self::ZERO;
+        }
+        let log = Tables::<POLY>::LOG;
+        Self(
+            Tables::<POLY>::ALOG[log[self.0 as usize] as usize + Self::ORDER as usize
+                - log[rhs.0 as usize] as usize],
+        )
+    }
+}
+
+macro_rules! gf2m_assign_ops {
+    ($(impl $assign_trait:ident { fn $assign_call:ident => $call:ident })*) => {$(
+        impl<const POLY: u32> $assign_trait for GF2m<POLY> {
+            #[inline]
+            fn $assign_call(&mut self, rhs: Self) {
+                *self = self.$call(rhs);
+            }
+        }
+    )*}
+}
+
+gf2m_assign_ops! {
+    impl AddAssign { fn add_assign => add }
+    impl SubAssign { fn sub_assign => sub }
+    impl MulAssign { fn mul_assign => mul }
+    impl DivAssign { fn div_assign => div }
+}
+
+impl<const POLY: u32> Sum for GF2m<POLY> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl<const POLY: u32> Product for GF2m<POLY> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, Mul::mul)
+    }
+}
+
+impl<const POLY: u32> Field for GF2m<POLY> {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1);
+    const ORDER: u128 = Self::ORDER as u128;
+
+    #[inline]
+    fn inv(self) -> Self {
+        self.inv()
+    }
+
+    #[inline]
+    fn pow(self, exp: usize) -> Self {
+        self.pow(exp)
+    }
+
+    #[inline]
+    fn from_index(i: u128) -> Self {
+        Self::new(i as u16)
+    }
+
+    #[inline]
+    fn to_index(self) -> u128 {
+        self.0 as u128
+    }
+
+    fn mul_add_slice(dst: &mut [Self], src: &[Self], c: Self) {
+        assert_eq!(dst.len(), src.len(), "slice lengths differ");
+        let Some(log_c) = c.log() else {
+            return;
+        };
+        let (log, alog) = (Tables::<POLY>::LOG, Tables::<POLY>::ALOG);
+        for (d, s) in dst.iter_mut().zip(src) {
+            if s.0 != 0 {
+                d.0 ^= alog[log_c + log[s.0 as usize] as usize];
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn transform_mul(a: &[Self], b: &[Self]) -> Option<alloc::vec::Vec<Self>> {
+        crate::additive_fft::transform_mul(a, b)
+    }
+}
+
+impl<const POLY: u32> BinaryField for GF2m<POLY> {
+    const DEGREE: u32 = Self::DEGREE;
+
+    #[inline]
+    fn from_bits(bits: u32) -> Self {
+        Self(bits as u16)
+    }
+
+    #[inline]
+    fn to_bits(self) -> u32 {
+        self.0 as u32
+    }
+
+    #[inline]
+    fn log(self) -> Option<usize> {
+        self.log()
+    }
+
+    #[inline]
+    fn exp(e: usize) -> Self {
+        Self::exp(e)
+    }
+}
+
+#[cfg(feature = "num-traits")]
+mod num_traits_impl {
+    use super::GF2m;
+
+    impl<const POLY: u32> num_traits::Zero for GF2m<POLY> {
+        fn zero() -> Self {
+            Self::ZERO
+        }
+        fn is_zero(&self) -> bool {
+            *self == Self::ZERO
+        }
+    }
+
+    impl<const POLY: u32> num_traits::One for GF2m<POLY> {
+        fn one() -> Self {
+            Self::ONE
+        }
+    }
+
+    impl<const POLY: u32> num_traits::Inv for GF2m<POLY> {
+        type Output = Self;
+
+        fn inv(self) -> Self::Output {
+            self.inv()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GF2m, Tables};
+    use crate::GF;
+
+    #[test]
+    fn matches_gf256() {
+        type F = GF2m<0x11D>;
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!(
+                    (F::new(a as u16) * F::new(b as u16)).0,
+                    (GF(a) * GF(b)).0 as u16
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn non_primitive() {
+        // the AES polynomial is irreducible but x only has order 51
+        type F = GF2m<0x11B>;
+        assert_eq!(F::GENERATOR, F::new(3));
+        assert_eq!(F::new(0x53) * F::new(0xCA), F::ONE);
+    }
+
+    #[test]
+    fn mul_div_inv() {
+        type F = GF2m<0x1100B>;
+        for a in (1..=0xFFFF).step_by(97).map(F::new) {
+            assert_eq!(a * a.inv(), F::ONE);
+            assert_eq!((a * F::new(0x4321)) / a, F::new(0x4321));
+            assert_eq!(a.pow(F::ORDER as usize), F::ONE);
+        }
+    }
+
+    #[test]
+    fn small_field() {
+        type F = GF2m<0b100101>;
+        assert_eq!(F::DEGREE, 5);
+        assert_eq!(F::GENERATOR.pow(31), F::ONE);
+        assert_eq!(F::exp(5), F::new(0b00101));
+        for a in (0..32).map(F::new) {
+            for b in (1..32).map(F::new) {
+                assert_eq!(a * b / b, a);
+            }
+        }
+    }
+
+    #[test]
+    fn table_sizes() {
+        assert_eq!(Tables::<0b111>::LOG.len(), 4);
+        assert_eq!(Tables::<0b111>::ALOG.len(), 8);
+        assert_eq!(Tables::<0x29>::LOG.len(), 32);
+        assert_eq!(Tables::<0x409>::ALOG.len(), 2048);
+        assert_eq!(Tables::<0x1100B>::LOG.len(), 65536);
+        assert_eq!(Tables::<0x1100B>::ALOG.len(), 131072);
+    }
+}
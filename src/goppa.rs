@@ -0,0 +1,549 @@
+//! # Binary Goppa Codes
+//!
+//! The Goppa code `Γ(L, g)` for a support `L = (α_0, ..., α_{n-1})` of
+//! distinct elements of `GF(2^m)` and a Goppa polynomial `g` of degree `t`
+//! consists of the bit vectors `c` with `sum_i c_i / (x - α_i) = 0 mod g`.
+//! For an irreducible `g` the code has dimension at least `n - m t` and
+//! Patterson's algorithm corrects `t` errors. This is the code behind Classic
+//! McEliece, which uses `m = 12` or `13`.
+//!
+//! The binary parity-check matrix has the `m` bits of `α_j^i / g(α_j)` in rows
+//! `m i` to `m i + m - 1` of column `j`, bit `0` first.
+//!
+//! ```
+//! use gf::{goppa::Goppa, GF2m};
+//! use rand::{rngs::OsRng, RngCore};
+//!
+//! let code = Goppa::<GF2m<0x1009>>::random(600, 12, || OsRng.next_u32());
+//! assert_eq!(code.k(), 600 - 12 * 12);
+//!
+//! let message: Vec<bool> = (0..code.k()).map(|i| i % 7 < 3).collect();
+//! let mut codeword = code.encode(&message);
+//! for i in (0..600).step_by(50) {
+//!     codeword[i] = !codeword[i];
+//! }
+//! assert_eq!(code.decode(&mut codeword), Ok(12));
+//! assert_eq!(code.message(&codeword), message);
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{BinaryField, Poly};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    /// The Goppa polynomial is not irreducible.
+    ReduciblePolynomial,
+    /// The support is empty, has duplicates, or contains a root of the Goppa
+    /// polynomial.
+    InvalidSupport,
+    /// More errors than the decoder can correct.
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ReduciblePolynomial => "Goppa polynomial is reducible".fmt(f),
+            Error::InvalidSupport => "invalid support".fmt(f),
+            Error::TooManyErrors => "too many errors to correct".fmt(f),
+        }
+    }
+}
+
+fn mul_mod<F: BinaryField>(a: &Poly<F>, b: &Poly<F>, g: &Poly<F>) -> Poly<F> {
+    (a * b).div_rem(g).1
+}
+
+/// `p^2 mod g`, squaring is linear in characteristic two.
+fn square_mod<F: BinaryField>(p: &Poly<F>, g: &Poly<F>) -> Poly<F> {
+    let mut coeffs = vec![F::ZERO; 2 * p.coeffs().len()];
+    for (i, &c) in p.coeffs().iter().enumerate() {
+        coeffs[2 * i] = c * c;
+    }
+    Poly::new(coeffs).div_rem(g).1
+}
+
+/// `a^(2^(m - 1))`, the square root in `GF(2^m)`.
+fn sqrt<F: BinaryField>(mut a: F) -> F {
+    for _ in 1..F::DEGREE {
+        a *= a;
+    }
+    a
+}
+
+/// Rabin's test: `g` of degree `t` is irreducible if and only if it divides
+/// `x^(q^t) - x` and is coprime to `x^(q^(t/p)) - x` for every prime `p | t`.
+fn is_irreducible<F: BinaryField>(g: &Poly<F>) -> bool {
+    let t = match g.degree() {
+        None | Some(0) => return false,
+        Some(1) => return true,
+        Some(t) => t,
+    };
+
+    let x = Poly::monomial(F::ONE, 1);
+    // x^(q^i) mod g for i in 0..=t
+    let mut frobenius = vec![x.clone()];
+    for i in 0..t {
+        let mut p = frobenius[i].clone();
+        for _ in 0..F::DEGREE {
+            p = square_mod(&p, g);
+        }
+        frobenius.push(p);
+    }
+    if frobenius[t] != x {
+        return false;
+    }
+
+    (2..=t)
+        .filter(|p| t.is_multiple_of(*p) && (2..*p).all(|d| !p.is_multiple_of(d)))
+        .all(|p| (&frobenius[t / p] - &x).gcd(g) == Poly::one())
+}
+
+/// A row of bits packed into words.
+fn get(row: &[u64], i: usize) -> bool {
+    row[i / 64] >> (i % 64) & 1 != 0
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Goppa<F> {
+    g: Poly<F>,
+    support: Vec<F>,
+    /// `1 / g(α)` for every support element
+    weights: Vec<F>,
+    /// `sqrt(x) mod g`
+    sqrt_x: Poly<F>,
+    /// the binary parity-check matrix in reduced row echelon form
+    reduced: Vec<Vec<u64>>,
+    /// the pivot column of every row of `reduced`
+    pivots: Vec<usize>,
+}
+
+impl<F: BinaryField> Goppa<F> {
+    /// The code for the Goppa polynomial `g`, made monic, and `support`.
+    pub fn new(g: Poly<F>, support: Vec<F>) -> Result<Self, Error> {
+        if !is_irreducible(&g) {
+            return Err(Error::ReduciblePolynomial);
+        }
+        let g = g.monic();
+
+        let mut sorted: Vec<u128> = support.iter().map(|a| a.to_index()).collect();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if support.is_empty() || sorted.len() != support.len() {
+            return Err(Error::InvalidSupport);
+        }
+        let values: Vec<F> = support.iter().map(|&a| g.eval(a)).collect();
+        if values.iter().any(F::is_zero) {
+            return Err(Error::InvalidSupport);
+        }
+        let weights = values.into_iter().map(F::inv).collect();
+
+        let t = g.degree().unwrap();
+        let mut sqrt_x = Poly::monomial(F::ONE, 1);
+        for _ in 1..F::DEGREE as usize * t {
+            sqrt_x = square_mod(&sqrt_x, &g);
+        }
+
+        let mut code = Self {
+            g,
+            support,
+            weights,
+            sqrt_x,
+            reduced: Vec::new(),
+            pivots: Vec::new(),
+        };
+        code.reduce();
+        Ok(code)
+    }
+
+    /// A code with a random irreducible Goppa polynomial of degree `t` and a
+    /// random support of `n` elements, drawing numbers from `random`. An
+    /// irreducible `g` of degree one has a root that could fall in the
+    /// support, so `t` must be at least two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t` is less than two or `n` exceeds the field size.
+    pub fn random(n: usize, t: usize, mut random: impl FnMut() -> u32) -> Self {
+        assert!(t >= 2, "t must be at least 2");
+        assert!(n as u128 <= F::ORDER + 1, "support larger than the field");
+
+        let mut element = || F::from_index(random() as u128 % (F::ORDER + 1));
+        let g = loop {
+            let mut coeffs: Vec<F> = (0..t).map(|_| element()).collect();
+            coeffs.push(F::ONE);
+            let g = Poly::new(coeffs);
+            if is_irreducible(&g) {
+                break g;
+            }
+        };
+
+        let mut elements: Vec<F> = (0..=F::ORDER).map(F::from_index).collect();
+        for i in 0..n {
+            let j = i + (random() as usize) % (elements.len() - i);
+            elements.swap(i, j);
+        }
+        elements.truncate(n);
+
+        Self::new(g, elements).expect("random code is valid")
+    }
+
+    #[inline]
+    pub fn goppa_polynomial(&self) -> &Poly<F> {
+        &self.g
+    }
+
+    #[inline]
+    pub fn support(&self) -> &[F] {
+        &self.support
+    }
+
+    /// Code length.
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.support.len()
+    }
+
+    /// Number of correctable errors, the degree of the Goppa polynomial.
+    #[inline]
+    pub fn t(&self) -> usize {
+        self.g.degree().unwrap()
+    }
+
+    /// Message length, `n` minus the rank of the parity-check matrix.
+    #[inline]
+    pub fn k(&self) -> usize {
+        self.n() - self.pivots.len()
+    }
+
+    /// The `m t` by `n` binary parity-check matrix.
+    pub fn parity_check(&self) -> Vec<Vec<bool>> {
+        let m = F::DEGREE as usize;
+        let mut rows = vec![vec![false; self.n()]; m * self.t()];
+        for (j, (&a, &w)) in self.support.iter().zip(&self.weights).enumerate() {
+            let mut entry = w;
+            for i in 0..self.t() {
+                let bits = entry.to_bits();
+                for b in 0..m {
+                    rows[m * i + b][j] = bits >> b & 1 != 0;
+                }
+                entry *= a;
+            }
+        }
+        rows
+    }
+
+    fn reduce(&mut self) {
+        let n = self.n();
+        let mut rows: Vec<Vec<u64>> = self
+            .parity_check()
+            .iter()
+            .map(|row| {
+                let mut packed = vec![0; n.div_ceil(64)];
+                for (i, _) in row.iter().enumerate().filter(|(_, &b)| b) {
+                    packed[i / 64] |= 1 << (i % 64);
+                }
+                packed
+            })
+            .collect();
+
+        let mut pivots = Vec::new();
+        for col in 0..n {
+            let r = pivots.len();
+            let Some(p) = (r..rows.len()).find(|&i| get(&rows[i], col)) else {
+                continue;
+            };
+            rows.swap(r, p);
+            let pivot_row = rows[r].clone();
+            for (i, row) in rows.iter_mut().enumerate() {
+                if i != r && get(row, col) {
+                    for (a, b) in row.iter_mut().zip(&pivot_row) {
+                        *a ^= b;
+                    }
+                }
+            }
+            pivots.push(col);
+        }
+        rows.truncate(pivots.len());
+
+        self.reduced = rows;
+        self.pivots = pivots;
+    }
+
+    /// The positions of the message bits in a codeword.
+    pub fn information_set(&self) -> Vec<usize> {
+        (0..self.n()).filter(|c| !self.pivots.contains(c)).collect()
+    }
+
+    /// The codeword with `message` on the [`information_set`] and the parity
+    /// bits on the remaining positions.
+    ///
+    /// [`information_set`]: Self::information_set
+    ///
+    /// # Panics
+    ///
+    /// Panics if `message` does not have exactly [`k`](Self::k) bits.
+    pub fn encode(&self, message: &[bool]) -> Vec<bool> {
+        assert_eq!(message.len(), self.k(), "wrong message length");
+        let mut codeword = vec![false; self.n()];
+        for (i, &b) in self.information_set().into_iter().zip(message) {
+            codeword[i] = b;
+        }
+        for (row, &p) in self.reduced.iter().zip(&self.pivots) {
+            codeword[p] = (0..self.n())
+                .filter(|&i| i != p && get(row, i))
+                .fold(false, |acc, i| acc ^ codeword[i]);
+        }
+        codeword
+    }
+
+    /// The message bits of `codeword`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `codeword` does not have exactly [`n`](Self::n) bits.
+    pub fn message(&self, codeword: &[bool]) -> Vec<bool> {
+        assert_eq!(codeword.len(), self.n(), "wrong codeword length");
+        self.information_set()
+            .into_iter()
+            .map(|i| codeword[i])
+            .collect()
+    }
+
+    /// `H word`, the `m t` bit syndrome of [`parity_check`](Self::parity_check).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `word` does not have exactly [`n`](Self::n) bits.
+    pub fn syndrome(&self, word: &[bool]) -> Vec<bool> {
+        assert_eq!(word.len(), self.n(), "wrong word length");
+        let mut sums = vec![F::ZERO; self.t()];
+        for (j, _) in word.iter().enumerate().filter(|(_, &b)| b) {
+            let mut entry = self.weights[j];
+            for s in &mut sums {
+                *s += entry;
+                entry *= self.support[j];
+            }
+        }
+
+        let m = F::DEGREE as usize;
+        sums.iter()
+            .flat_map(|s| (0..m).map(move |b| s.to_bits() >> b & 1 != 0))
+            .collect()
+    }
+
+    /// The positions of the errors behind `syndrome`, with Patterson's
+    /// algorithm.
+    ///
+    /// The syndrome gives the power sums `sum_j e_j α_j^i / g(α_j)`, from
+    /// which `S(x) = sum_j e_j / (x - α_j) mod g` is a linear map. With
+    /// `T = 1 / S` and `v = sqrt(T + x) mod g`, the extended Euclidean
+    /// algorithm finds `a = b v mod g` with `deg a <= t / 2`, and
+    /// `σ = a^2 + x b^2` has the error positions as roots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `syndrome` does not have exactly `m t` bits.
+    pub fn decode_syndrome(&self, syndrome: &[bool]) -> Result<Vec<usize>, Error> {
+        let m = F::DEGREE as usize;
+        let t = self.t();
+        assert_eq!(syndrome.len(), m * t, "wrong syndrome length");
+
+        let sums: Vec<F> = syndrome
+            .chunks_exact(m)
+            .map(|bits| {
+                let value = bits.iter().rev().fold(0, |acc, &b| acc << 1 | b as u32);
+                F::from_bits(value)
+            })
+            .collect();
+        // (g(x) - g(α)) / (x - α) = sum_i x^i sum_{l > i} g_l α^(l - 1 - i)
+        let s = Poly::new(
+            (0..t)
+                .map(|i| (i + 1..=t).map(|l| self.g.coeff(l) * sums[l - 1 - i]).sum())
+                .collect::<Vec<F>>(),
+        );
+        if s.is_zero() {
+            return Ok(Vec::new());
+        }
+
+        let x = Poly::monomial(F::ONE, 1);
+        let inv = s.inv_mod(&self.g).ok_or(Error::TooManyErrors)?;
+        let v = self.sqrt_mod(&(&inv + &x));
+        let (a, b) = self.g.partial_gcd(&v, t / 2 + 1);
+        if b.degree().is_some_and(|d| d > (t - 1) / 2) {
+            return Err(Error::TooManyErrors);
+        }
+        let locator = &(&a * &a) + &(&x * &(&b * &b));
+
+        let degree = locator.degree().unwrap_or(0);
+        let positions: Vec<usize> = (0..self.n())
+            .filter(|&j| locator.eval(self.support[j]).is_zero())
+            .collect();
+        if positions.len() != degree {
+            return Err(Error::TooManyErrors);
+        }
+        Ok(positions)
+    }
+
+    /// `sqrt(p) mod g`: with `p = p_0(x)^2 + x p_1(x)^2` from the even and odd
+    /// coefficients, `sqrt(p) = p_0 + sqrt(x) p_1`.
+    fn sqrt_mod(&self, p: &Poly<F>) -> Poly<F> {
+        let half = |offset| {
+            Poly::new(
+                p.coeffs()
+                    .iter()
+                    .skip(offset)
+                    .step_by(2)
+                    .map(|&c| sqrt(c))
+                    .collect::<Vec<F>>(),
+            )
+        };
+        &half(0) + &mul_mod(&self.sqrt_x, &half(1), &self.g)
+    }
+
+    /// Corrects `word` in place and returns the number of flipped bits. On
+    /// failure `word` is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `word` does not have exactly [`n`](Self::n) bits.
+    pub fn decode(&self, word: &mut [bool]) -> Result<usize, Error> {
+        let positions = self.decode_syndrome(&self.syndrome(word))?;
+        for &j in &positions {
+            word[j] = !word[j];
+        }
+        Ok(positions.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    use super::{is_irreducible, sqrt, Error, Goppa};
+    use crate::{Field, GF2m, Poly};
+
+    #[test]
+    fn irreducible_count() {
+        // (q^2 - q) / 2 monic quadratics and (q^3 - q) / 3 monic cubics over
+        // GF(16) are irreducible
+        type F = GF2m<0b10011>;
+        let count = |degree: usize| {
+            (0..16u128.pow(degree as u32))
+                .filter(|&i| {
+                    let mut coeffs: Vec<F> = (0..degree)
+                        .map(|j| F::from_index(i >> (4 * j) & 15))
+                        .collect();
+                    coeffs.push(F::ONE);
+                    is_irreducible(&Poly::new(coeffs))
+                })
+                .count()
+        };
+        assert_eq!(count(2), 120);
+        assert_eq!(count(3), 1360);
+    }
+
+    #[test]
+    fn square_roots() {
+        type F = GF2m<0x201B>;
+        for i in [0, 1, 2, 1000, 8191] {
+            let a = F::new(i);
+            assert_eq!(sqrt(a) * sqrt(a), a);
+        }
+    }
+
+    #[test]
+    fn invalid_codes() {
+        type F = GF2m<0b10011>;
+        let g = Poly::from_roots(&[F::new(3), F::new(5)]);
+        let support = (0..16).map(F::new).collect();
+        assert_eq!(Goppa::new(g, support), Err(Error::ReduciblePolynomial));
+
+        // x^2 + x + α^3 has no roots in GF(16)
+        let g = Poly::new([F::exp(3), F::ONE, F::ONE]);
+        assert!(is_irreducible(&g));
+        assert_eq!(
+            Goppa::new(g.clone(), [F::new(1), F::new(1)].to_vec()),
+            Err(Error::InvalidSupport)
+        );
+        assert!(Goppa::new(g, (0..16).map(F::new).collect()).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "t must be at least 2")]
+    fn random_degree_one() {
+        Goppa::<GF2m<0x13>>::random(16, 1, || 0);
+    }
+
+    fn roundtrip<F: crate::BinaryField>(n: usize, t: usize, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let code = Goppa::<F>::random(n, t, || rng.next_u32());
+        let m = F::DEGREE as usize;
+        assert!(code.k() >= n - m * t);
+
+        let message: Vec<bool> = (0..code.k()).map(|i| (i * 5 + i / 3) % 4 == 0).collect();
+        let codeword = code.encode(&message);
+        assert!(code.syndrome(&codeword).iter().all(|&b| !b));
+        assert_eq!(code.message(&codeword), message);
+
+        for errors in [0, 1, t / 2, t] {
+            let mut received = codeword.clone();
+            for i in 0..errors {
+                received[(i * 37 + 11) % n] ^= true;
+            }
+            assert_eq!(code.decode(&mut received), Ok(errors));
+            assert_eq!(received, codeword);
+        }
+    }
+
+    #[test]
+    fn correct_t_errors() {
+        roundtrip::<GF2m<0x1009>>(700, 16, 1);
+        roundtrip::<GF2m<0x1009>>(500, 11, 2);
+        roundtrip::<GF2m<0x201B>>(400, 10, 3);
+        roundtrip::<GF2m<0x201B>>(300, 7, 4);
+        roundtrip::<GF2m<0x1009>>(4096, 2, 5);
+    }
+
+    #[test]
+    fn niederreiter() {
+        let mut rng = StdRng::seed_from_u64(99);
+        let code = Goppa::<GF2m<0x201B>>::random(512, 12, || rng.next_u32());
+        let mut error = alloc::vec![false; 512];
+        let positions = [3, 40, 77, 100, 150, 199, 256, 300, 311, 400, 450, 511];
+        for &p in &positions {
+            error[p] = true;
+        }
+        assert_eq!(
+            code.decode_syndrome(&code.syndrome(&error)),
+            Ok(positions.to_vec())
+        );
+    }
+
+    #[test]
+    fn too_many_errors() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let code = Goppa::<GF2m<0x1009>>::random(400, 8, || rng.next_u32());
+        let message: Vec<bool> = (0..code.k()).map(|i| i % 3 == 0).collect();
+        let codeword = code.encode(&message);
+
+        let mut received = codeword.clone();
+        for i in 0..9 {
+            received[i * 40] ^= true;
+        }
+        let damaged = received.clone();
+        // nine errors either fail or land on a different codeword
+        match code.decode(&mut received) {
+            Err(Error::TooManyErrors) => assert_eq!(received, damaged),
+            Ok(_) => {
+                assert_ne!(received, codeword);
+                assert!(code.syndrome(&received).iter().all(|&b| !b));
+            }
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
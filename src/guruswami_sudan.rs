@@ -0,0 +1,376 @@
+//! # Guruswami–Sudan List Decoding
+//!
+//! List decoding of Reed–Solomon codes in evaluation form, where the message
+//! `f_0, ..., f_{k-1}` is sent as `f(x_0), ..., f(x_{n-1})`. The decoder
+//! finds a bivariate `Q(x, y)` of low `(1, k - 1)`-weighted degree with a zero
+//! of multiplicity `m` at every received point `(x_i, y_i)`, by Kötter's
+//! interpolation, and then every `y - f(x)` dividing it, by the
+//! Roth–Ruckenstein recursion. Every message within the [radius] is on the
+//! list, which grows towards `n - sqrt(n (k - 1))` with the multiplicity.
+//!
+//! [radius]: GuruswamiSudan::radius
+//!
+//! ```
+//! use gf::{guruswami_sudan::GuruswamiSudan, GF};
+//!
+//! let points: Vec<GF<u8>> = (1..=32).map(GF).collect();
+//! let decoder = GuruswamiSudan::new(&points, 8, 4);
+//! // unique decoding stops at 12 errors
+//! assert_eq!(decoder.radius(), 16);
+//!
+//! let message: Vec<GF<u8>> = (0..8).map(|i| GF(i * 29 + 3)).collect();
+//! let mut received = decoder.encode(&message);
+//! for i in 0..16 {
+//!     received[2 * i] += GF(0x5A);
+//! }
+//! assert!(decoder.decode(&received).contains(&message));
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{Field, Poly};
+
+/// `C(a, r)` in `F`, whose characteristic is two or the prime size of `F`,
+/// by Lucas' theorem.
+fn binomial<F: Field>(mut a: u128, mut r: u128) -> F {
+    let p = if F::ORDER.is_multiple_of(2) {
+        F::ORDER + 1
+    } else {
+        2
+    };
+    let mut acc = F::ONE;
+    while r != 0 {
+        let (ad, rd) = (a % p, r % p);
+        if rd > ad {
+            return F::ZERO;
+        }
+        for j in 0..rd {
+            acc *= F::from_index((ad - j) % p) / F::from_index(j + 1);
+        }
+        a /= p;
+        r /= p;
+    }
+    acc
+}
+
+/// A polynomial in `y` with coefficients in `x`.
+type Bivariate<F> = Vec<Poly<F>>;
+
+/// The Hasse derivative `D_{r,s} Q` at `(x, y)`.
+fn hasse<F: Field>(q: &Bivariate<F>, r: usize, s: usize, x: F, y: F) -> F {
+    let mut acc = F::ZERO;
+    let mut y_pow = F::ONE;
+    for (b, qb) in q.iter().enumerate().skip(s) {
+        let mut inner = F::ZERO;
+        let mut x_pow = F::ONE;
+        for (a, &c) in qb.coeffs().iter().enumerate().skip(r) {
+            if !c.is_zero() {
+                inner += binomial::<F>(a as u128, r as u128) * c * x_pow;
+            }
+            x_pow *= x;
+        }
+        acc += binomial::<F>(b as u128, s as u128) * y_pow * inner;
+        y_pow *= y;
+    }
+    acc
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GuruswamiSudan<F> {
+    points: Vec<F>,
+    k: usize,
+    multiplicity: usize,
+    /// bound on the `(1, k - 1)`-weighted degree of the interpolation
+    degree: usize,
+}
+
+impl<F: Field> GuruswamiSudan<F> {
+    /// A decoder for messages of `k` symbols evaluated at `points`, with
+    /// zeros of the given multiplicity. Higher multiplicities decode further
+    /// at a cost growing with its fourth power.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is not in `2..n`, `multiplicity` is zero or `points` has
+    /// duplicates.
+    pub fn new(points: &[F], k: usize, multiplicity: usize) -> Self {
+        let n = points.len();
+        assert!(2 <= k && k < n, "k out of range");
+        assert!(multiplicity >= 1, "multiplicity must be positive");
+        let mut sorted: Vec<u128> = points.iter().map(|x| x.to_index()).collect();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), n, "duplicate points");
+
+        // the smallest degree with more monomials than conditions
+        let conditions = n * multiplicity * (multiplicity + 1) / 2;
+        let v = k - 1;
+        let monomials = |d: usize| (0..=d / v).map(|b| d - b * v + 1).sum::<usize>();
+        let mut degree = 0;
+        while monomials(degree) <= conditions {
+            degree += 1;
+        }
+
+        Self {
+            points: points.to_vec(),
+            k,
+            multiplicity,
+            degree,
+        }
+    }
+
+    #[inline]
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// Message length.
+    #[inline]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    #[inline]
+    pub fn multiplicity(&self) -> usize {
+        self.multiplicity
+    }
+
+    /// Maximum number of candidates, the `y`-degree of the interpolation.
+    #[inline]
+    pub fn list_size(&self) -> usize {
+        self.degree / (self.k - 1)
+    }
+
+    /// Number of errors up to which the sent message is guaranteed to be on
+    /// the list: with `n - e` agreements, `Q(x, f(x))` has more roots counted
+    /// with multiplicity than its degree, so it vanishes.
+    pub fn radius(&self) -> usize {
+        self.points
+            .len()
+            .saturating_sub(self.degree / self.multiplicity + 1)
+    }
+
+    /// `f(x_i)` for the polynomial `f` with the coefficients `message`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `message` does not have exactly [`k`](Self::k) symbols.
+    pub fn encode(&self, message: &[F]) -> Vec<F> {
+        assert_eq!(message.len(), self.k, "wrong message length");
+        let f = Poly::new(message.to_vec());
+        self.points.iter().map(|&x| f.eval(x)).collect()
+    }
+
+    /// All messages whose codewords differ from `received` in at most
+    /// [`radius`](Self::radius) positions, closest first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `received` does not have one symbol per point.
+    pub fn decode(&self, received: &[F]) -> Vec<Vec<F>> {
+        assert_eq!(received.len(), self.points.len(), "wrong word length");
+
+        let q = self.interpolate(received);
+        let mut candidates = Vec::new();
+        roth_ruckenstein(q, self.k, &mut Vec::new(), &mut candidates);
+
+        let min_agreement = self.points.len() - self.radius();
+        let mut list: Vec<(usize, Vec<F>)> = candidates
+            .into_iter()
+            .map(|message| {
+                let agreement = self
+                    .encode(&message)
+                    .iter()
+                    .zip(received)
+                    .filter(|(a, b)| a == b)
+                    .count();
+                (agreement, message)
+            })
+            .filter(|&(agreement, _)| agreement >= min_agreement)
+            .collect();
+        list.sort_by_key(|(agreement, _)| core::cmp::Reverse(*agreement));
+        list.into_iter().map(|(_, message)| message).collect()
+    }
+
+    /// Kötter's algorithm: starting from `1, y, ..., y^L`, every condition
+    /// is imposed by cancelling it with the polynomial of least weighted
+    /// degree among those that violate it, which is then multiplied by
+    /// `x - x_i`. The conditions of a point are ordered so that this keeps
+    /// the earlier ones intact.
+    fn interpolate(&self, received: &[F]) -> Bivariate<F> {
+        let v = self.k - 1;
+        let list_size = self.list_size();
+        let weighted_degree = |q: &Bivariate<F>| {
+            q.iter()
+                .enumerate()
+                .filter_map(|(b, qb)| qb.degree().map(|d| d + b * v))
+                .max()
+                .unwrap_or(0)
+        };
+
+        let mut basis: Vec<Bivariate<F>> = (0..=list_size)
+            .map(|j| {
+                let mut q = vec![Poly::zero(); list_size + 1];
+                q[j] = Poly::one();
+                q
+            })
+            .collect();
+
+        for (&x, &y) in self.points.iter().zip(received) {
+            let linear = Poly::new([-x, F::ONE]);
+            for s in 0..self.multiplicity {
+                for r in 0..self.multiplicity - s {
+                    let deltas: Vec<F> = basis.iter().map(|q| hasse(q, r, s, x, y)).collect();
+                    let Some(pivot) = (0..basis.len())
+                        .filter(|&j| !deltas[j].is_zero())
+                        .min_by_key(|&j| weighted_degree(&basis[j]))
+                    else {
+                        continue;
+                    };
+
+                    let pivot_poly = basis[pivot].clone();
+                    for (j, q) in basis.iter_mut().enumerate() {
+                        if j != pivot && !deltas[j].is_zero() {
+                            for (qb, pb) in q.iter_mut().zip(&pivot_poly) {
+                                *qb = &qb.scale(deltas[pivot]) - &pb.scale(deltas[j]);
+                            }
+                        }
+                    }
+                    for qb in &mut basis[pivot] {
+                        *qb = &*qb * &linear;
+                    }
+                }
+            }
+        }
+
+        basis
+            .into_iter()
+            .min_by_key(|q| weighted_degree(q))
+            .unwrap()
+    }
+}
+
+/// Collects every `f` of `k` coefficients with `y - f(x)` dividing `q`,
+/// one coefficient per level: the constant terms are the roots of `Q(0, y)`,
+/// and for a root `γ` the rest divides `Q(x, x y + γ)` with the common power
+/// of `x` removed.
+fn roth_ruckenstein<F: Field>(
+    mut q: Bivariate<F>,
+    k: usize,
+    prefix: &mut Vec<F>,
+    out: &mut Vec<Vec<F>>,
+) {
+    let Some(shift) = q
+        .iter()
+        .filter_map(|p| p.coeffs().iter().position(|c| !c.is_zero()))
+        .min()
+    else {
+        return;
+    };
+    for p in &mut q {
+        *p = Poly::new(p.coeffs().get(shift..).unwrap_or_default().to_vec());
+    }
+    while q.last().is_some_and(Poly::is_zero) {
+        q.pop();
+    }
+
+    if prefix.len() == k {
+        out.push(prefix.clone());
+        return;
+    }
+
+    let constant = Poly::new(q.iter().map(|p| p.coeff(0)).collect::<Vec<F>>());
+    for gamma in constant.roots() {
+        // Taylor shift to Q(x, y + γ), then y -> x y
+        let mut next = q.clone();
+        for i in 0..next.len() {
+            for j in (i..next.len() - 1).rev() {
+                next[j] = &next[j] + &next[j + 1].scale(gamma);
+            }
+        }
+        for (j, p) in next.iter_mut().enumerate() {
+            *p = &*p * &Poly::monomial(F::ONE, j);
+        }
+
+        prefix.push(gamma);
+        roth_ruckenstein(next, k, prefix, out);
+        prefix.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{binomial, GuruswamiSudan};
+    use crate::{Field, GF2m, GFp, GF};
+
+    #[test]
+    fn binomials() {
+        type F = GFp<7>;
+        assert_eq!(binomial::<F>(10, 3), F::new(120 % 7));
+        assert_eq!(binomial::<F>(14, 7), F::new(2));
+        assert_eq!(binomial::<GF<u8>>(6, 2), GF(1));
+        assert_eq!(binomial::<GF<u8>>(6, 1), GF(0));
+        assert_eq!(binomial::<GF<u8>>(2, 3), GF(0));
+    }
+
+    fn check<F: Field>(decoder: &GuruswamiSudan<F>, message: &[F], error: F, errors: usize) {
+        let codeword = decoder.encode(message);
+        let n = codeword.len();
+        let mut received = codeword.clone();
+        for i in 0..errors {
+            received[(i * 7 + 3) % n] += error;
+        }
+
+        let list = decoder.decode(&received);
+        assert!(list.len() <= decoder.list_size());
+        assert!(list.iter().any(|m| m == message));
+        for m in &list {
+            let agreement = decoder
+                .encode(m)
+                .iter()
+                .zip(&received)
+                .filter(|(a, b)| a == b)
+                .count();
+            assert!(agreement >= n - decoder.radius());
+        }
+    }
+
+    #[test]
+    fn sudan() {
+        let points: Vec<GF<u8>> = (1..=32).map(GF).collect();
+        let decoder = GuruswamiSudan::new(&points, 8, 1);
+        assert_eq!(decoder.radius(), 14);
+        let message: Vec<GF<u8>> = (1..=8).map(GF).collect();
+        check(&decoder, &message, GF(1), 14);
+    }
+
+    #[test]
+    fn beyond_half_distance() {
+        let points: Vec<GF<u8>> = (1..=32).map(GF).collect();
+        let decoder = GuruswamiSudan::new(&points, 8, 4);
+        assert_eq!(decoder.radius(), 16);
+        for e in 0..=16 {
+            let message: Vec<GF<u8>> = (0..8).map(|i| GF(i * 17 + e as u8)).collect();
+            check(&decoder, &message, GF(e as u8 + 1), e);
+        }
+    }
+
+    #[test]
+    fn wider_fields() {
+        type F = GF2m<0x1002D>;
+        let points: Vec<F> = (0..48).map(|i| F::new(i * 1000 + 7)).collect();
+        let decoder = GuruswamiSudan::new(&points, 12, 2);
+        assert!(decoder.radius() > (48 - 12) / 2);
+        let message: Vec<F> = (0..12).map(|i| F::new(i * 4099 + 1)).collect();
+        check(&decoder, &message, F::new(0xBEEF), decoder.radius());
+
+        type P = GFp<998244353>;
+        let points: Vec<P> = (0..20).map(|i| P::new(i * i + 1)).collect();
+        let decoder = GuruswamiSudan::new(&points, 4, 2);
+        assert!(decoder.radius() > (20 - 4) / 2);
+        let message: Vec<P> = (0..4).map(|i| P::new(998244350 + i)).collect();
+        check(&decoder, &message, P::new(12345), decoder.radius());
+    }
+}
@@ -352,6 +352,81 @@ macro_rules! gf_impl_mul {
             }
         }
 
+        impl crate::Field for GF<$t> {
+            const ZERO: Self = GF(0);
+            const ONE: Self = GF(1);
+            const ORDER: u128 = <$t>::MAX as u128;
+
+            #[inline]
+            fn inv(self) -> Self {
+                self.inv()
+            }
+
+            #[inline]
+            fn pow(self, exp: usize) -> Self {
+                self.pow(exp)
+            }
+
+            #[inline]
+            fn from_index(i: u128) -> Self {
+                GF(i as $t)
+            }
+
+            #[inline]
+            fn to_index(self) -> u128 {
+                self.0 as u128
+            }
+
+            fn mul_add_slice(dst: &mut [Self], src: &[Self], c: Self) {
+                assert_eq!(dst.len(), src.len(), "slice lengths differ");
+                if src.len() < 256 {
+                    for (d, &s) in dst.iter_mut().zip(src) {
+                        *d += c * s;
+                    }
+                    return;
+                }
+
+                // one table row per constant, LOGTABLE[0] lands in the zero tail of ALOGTABLE
+                let log_c = LOGTABLE[c.0 as usize];
+                let mut row = [0; 256];
+                for (x, r) in row.iter_mut().enumerate() {
+                    *r = ALOGTABLE[log_c + LOGTABLE[x]];
+                }
+                for (d, s) in dst.iter_mut().zip(src) {
+                    d.0 ^= row[s.0 as usize];
+                }
+            }
+
+            #[cfg(feature = "alloc")]
+            fn transform_mul(a: &[Self], b: &[Self]) -> Option<alloc::vec::Vec<Self>> {
+                crate::additive_fft::transform_mul(a, b)
+            }
+        }
+
+        impl crate::BinaryField for GF<$t> {
+            const DEGREE: u32 = <$t>::BITS;
+
+            #[inline]
+            fn from_bits(bits: u32) -> Self {
+                GF(bits as $t)
+            }
+
+            #[inline]
+            fn to_bits(self) -> u32 {
+                self.0 as u32
+            }
+
+            #[inline]
+            fn log(self) -> Option<usize> {
+                (self.0 != 0).then(|| LOGTABLE[self.0 as usize] % 255)
+            }
+
+            #[inline]
+            fn exp(e: usize) -> Self {
+                GF(ALOGTABLE[e % 255])
+            }
+        }
+
         #[cfg(feature = "num-traits")]
         mod num_traits_impl {
             use super::*;
@@ -400,6 +475,25 @@ gf_impl_mul! {u8}
 
 #[cfg(test)]
 mod tests {
+    use crate::{BinaryField, GF2m, GF};
+
+    fn check_logs<F: BinaryField>() {
+        assert_eq!(F::ZERO.log(), None);
+        assert_eq!(F::ONE.log(), Some(0));
+        for i in 1..=F::ORDER {
+            let x = F::from_index(i);
+            let log = x.log().unwrap();
+            assert!((log as u128) < F::ORDER);
+            assert_eq!(F::exp(log), x);
+        }
+    }
+
+    #[test]
+    fn binary_field_logs() {
+        check_logs::<GF<u8>>();
+        check_logs::<GF2m<0x11D>>();
+        check_logs::<GF2m<0x409>>();
+    }
 
     #[test]
     fn check_logtables() {
@@ -410,6 +504,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::absurd_extreme_comparisons, unused_comparisons)]
     fn checkt_table_bounds() {
         for e in super::ALOGTABLE.iter() {
             assert!(*e <= 255);
@@ -0,0 +1,355 @@
+//! # Polynomial Interpolation
+//!
+//! All the work that only depends on the x-coordinates is done once, in
+//! [`Barycentric::new`] and [`Barycentric::at`], so reconstructing many values
+//! from the same set of points is just a weighted sum.
+//!
+//! ```
+//! use gf::{interpolate::Lagrange, GF};
+//!
+//! // f(x) = 7 + 3x, sampled at x = 1, 2
+//! let xs = [GF(1u8), GF(2)];
+//! let ys = [GF(7u8) + GF(3), GF(7) + GF(3) * GF(2)];
+//!
+//! assert_eq!(Lagrange::at(&xs, GF(0)).eval(&ys), GF(7));
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{batch_inv, Field, Poly};
+
+/// Barycentric weights `w_j = 1 / prod_{m != j} (x_j - x_m)` for a fixed set
+/// of x-coordinates.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Barycentric<F> {
+    xs: Vec<F>,
+    weights: Vec<F>,
+}
+
+impl<F: Field> Barycentric<F> {
+    /// Computes the weights with `O(n^2)` multiplications and one inversion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `xs` contains duplicates.
+    pub fn new(xs: &[F]) -> Self {
+        let mut weights: Vec<F> = xs
+            .iter()
+            .enumerate()
+            .map(|(j, &xj)| {
+                xs.iter()
+                    .enumerate()
+                    .filter(|&(m, _)| m != j)
+                    .map(|(_, &xm)| xj - xm)
+                    .product()
+            })
+            .collect();
+        batch_inv(&mut weights);
+
+        Self {
+            xs: xs.to_vec(),
+            weights,
+        }
+    }
+
+    #[inline]
+    pub fn xs(&self) -> &[F] {
+        &self.xs
+    }
+
+    #[inline]
+    pub fn weights(&self) -> &[F] {
+        &self.weights
+    }
+
+    /// The Lagrange basis polynomials evaluated at `x`, in `O(n)`.
+    pub fn at(&self, x: F) -> Lagrange<F> {
+        // l_j(x) = w_j * prod_{m != j} (x - x_m), from prefix and suffix products
+        let mut coeffs = Vec::with_capacity(self.xs.len());
+        let mut acc = F::ONE;
+        for &xm in &self.xs {
+            coeffs.push(acc);
+            acc *= x - xm;
+        }
+
+        acc = F::ONE;
+        for ((c, &w), &xm) in coeffs.iter_mut().zip(&self.weights).zip(&self.xs).rev() {
+            *c *= acc * w;
+            acc *= x - xm;
+        }
+
+        Lagrange { coeffs }
+    }
+
+    /// Evaluates the interpolating polynomial of `ys` at `x`.
+    #[inline]
+    pub fn eval(&self, ys: &[F], x: F) -> F {
+        self.at(x).eval(ys)
+    }
+
+    /// The interpolating polynomial of `ys` in coefficient form, in `O(n^2)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ys` and the x-coordinates differ in length.
+    pub fn interpolate(&self, ys: &[F]) -> Poly<F> {
+        assert_eq!(self.xs.len(), ys.len(), "slice lengths differ");
+
+        let scaled: Vec<F> = ys.iter().zip(&self.weights).map(|(&y, &w)| y * w).collect();
+        combination(&self.xs, &scaled)
+    }
+}
+
+/// `sum_j c_j * L(x) / (x - x_j)` with `L(x) = prod_m (x - x_m)`.
+pub(crate) fn combination<F: Field>(xs: &[F], coeffs: &[F]) -> Poly<F> {
+    let l = Poly::from_roots(xs);
+    let l = l.coeffs();
+    let n = xs.len();
+
+    let mut out = vec![F::ZERO; n];
+    let mut quot = vec![F::ZERO; n];
+    for (&xj, &c) in xs.iter().zip(coeffs) {
+        // synthetic division of L(x) by (x - x_j)
+        let mut carry = F::ZERO;
+        for i in (0..n).rev() {
+            carry = l[i + 1] + carry * xj;
+            quot[i] = carry;
+        }
+        F::mul_add_slice(&mut out, &quot, c);
+    }
+
+    Poly::new(out)
+}
+
+/// The Lagrange basis polynomials `l_j` of a set of x-coordinates, evaluated
+/// at a single point.
+///
+/// With these, the value of the interpolating polynomial at that point is a
+/// plain linear combination of the y-values, which is how Shamir secrets are
+/// reconstructed at `x = 0`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Lagrange<F> {
+    coeffs: Vec<F>,
+}
+
+impl<F: Field> Lagrange<F> {
+    /// # Panics
+    ///
+    /// Panics if `xs` contains duplicates.
+    #[inline]
+    pub fn at(xs: &[F], x: F) -> Self {
+        Barycentric::new(xs).at(x)
+    }
+
+    #[inline]
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `ys` has the wrong length.
+    pub fn eval(&self, ys: &[F]) -> F {
+        assert_eq!(self.coeffs.len(), ys.len(), "slice lengths differ");
+        self.coeffs.iter().zip(ys).map(|(&c, &y)| c * y).sum()
+    }
+
+    /// Evaluates every position of the `ys` slices at once, writing the
+    /// results to `out`.
+    ///
+    /// ```
+    /// use gf::{interpolate::Lagrange, GF};
+    ///
+    /// // two byte strings sampled at x = 1, 2 from lines through the secret "hi"
+    /// let at_1 = [b'h' ^ 3, b'i' ^ 4];
+    /// let at_2 = [GF(b'h') + GF(3) * GF(2), GF(b'i') + GF(4) * GF(2)];
+    ///
+    /// let mut secret = [0u8; 2];
+    /// Lagrange::at(&[GF(1), GF(2)], GF(0)).combine(
+    ///     [GF::from_slice(&at_1), &at_2[..]],
+    ///     GF::from_slice_mut(&mut secret),
+    /// );
+    ///
+    /// assert_eq!(&secret, b"hi");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of slices or their lengths do not match.
+    pub fn combine<Y: AsRef<[F]>>(&self, ys: impl IntoIterator<Item = Y>, out: &mut [F]) {
+        out.fill(F::ZERO);
+        let mut count = 0;
+        for (y, &c) in ys.into_iter().zip(&self.coeffs) {
+            F::mul_add_slice(out, y.as_ref(), c);
+            count += 1;
+        }
+        assert_eq!(count, self.coeffs.len(), "wrong number of slices");
+    }
+}
+
+/// Newton form of the interpolating polynomial,
+/// `c_0 + c_1 (x - x_0) + c_2 (x - x_0)(x - x_1) + ...`
+///
+/// Points can be added one at a time without redoing earlier work.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Newton<F> {
+    xs: Vec<F>,
+    coeffs: Vec<F>,
+}
+
+impl<F: Field> Newton<F> {
+    /// Divided differences of the points, in `O(n^2)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `xs` contains duplicates or the slices differ in length.
+    pub fn new(xs: &[F], ys: &[F]) -> Self {
+        assert_eq!(xs.len(), ys.len(), "slice lengths differ");
+
+        let mut coeffs = ys.to_vec();
+        let mut denoms = Vec::with_capacity(xs.len());
+        for k in 1..xs.len() {
+            denoms.clear();
+            denoms.extend((k..xs.len()).map(|i| xs[i] - xs[i - k]));
+            batch_inv(&mut denoms);
+
+            for i in (k..xs.len()).rev() {
+                coeffs[i] = (coeffs[i] - coeffs[i - 1]) * denoms[i - k];
+            }
+        }
+
+        Self {
+            xs: xs.to_vec(),
+            coeffs,
+        }
+    }
+
+    #[inline]
+    pub fn xs(&self) -> &[F] {
+        &self.xs
+    }
+
+    /// The divided differences `c_i`.
+    #[inline]
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    /// Adds the point `(x, y)`, in `O(n)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is already one of the points.
+    pub fn push(&mut self, x: F, y: F) {
+        let basis: F = self.xs.iter().map(|&xm| x - xm).product();
+        assert!(!basis.is_zero(), "attempt to divide by zero");
+
+        self.coeffs.push((y - self.eval(x)) / basis);
+        self.xs.push(x);
+    }
+
+    pub fn eval(&self, x: F) -> F {
+        self.coeffs
+            .iter()
+            .zip(&self.xs)
+            .rev()
+            .fold(F::ZERO, |acc, (&c, &xm)| acc * (x - xm) + c)
+    }
+
+    /// Converts to coefficient form.
+    pub fn to_poly(&self) -> Poly<F> {
+        let mut coeffs = Vec::with_capacity(self.coeffs.len());
+        for (&c, &xm) in self.coeffs.iter().zip(&self.xs).rev() {
+            // coeffs = coeffs * (x - x_m) + c
+            coeffs.insert(0, F::ZERO);
+            for i in 0..coeffs.len() - 1 {
+                let next = coeffs[i + 1];
+                coeffs[i] -= xm * next;
+            }
+            coeffs[0] += c;
+        }
+        Poly::new(coeffs)
+    }
+}
+
+/// The unique polynomial of degree below `xs.len()` through all the points.
+///
+/// # Panics
+///
+/// Panics if `xs` contains duplicates or the slices differ in length.
+pub fn interpolate<F: Field>(xs: &[F], ys: &[F]) -> Poly<F> {
+    Barycentric::new(xs).interpolate(ys)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{interpolate, Barycentric, Lagrange, Newton};
+    use crate::{Poly, GF};
+
+    type Points = Vec<GF<u8>>;
+
+    fn sample() -> (Poly<GF<u8>>, Points, Points) {
+        let f = Poly::new(GF::from_slice(&[42, 7, 0, 199, 3]).to_vec());
+        let xs: Vec<_> = [1u8, 2, 3, 100, 255].map(GF).to_vec();
+        let ys = xs.iter().map(|&x| f.eval(x)).collect();
+        (f, xs, ys)
+    }
+
+    #[test]
+    fn coefficient_form() {
+        let (f, xs, ys) = sample();
+        assert_eq!(interpolate(&xs, &ys), f);
+        assert_eq!(Newton::new(&xs, &ys).to_poly(), f);
+    }
+
+    #[test]
+    fn eval_at_points() {
+        let (f, xs, ys) = sample();
+        let bary = Barycentric::new(&xs);
+        let newton = Newton::new(&xs, &ys);
+
+        for x in (0..=255).map(GF) {
+            assert_eq!(bary.eval(&ys, x), f.eval(x));
+            assert_eq!(newton.eval(x), f.eval(x));
+        }
+    }
+
+    #[test]
+    fn newton_push() {
+        let (f, xs, ys) = sample();
+        let mut newton = Newton::default();
+        for (&x, &y) in xs.iter().zip(&ys) {
+            newton.push(x, y);
+        }
+
+        assert_eq!(newton, Newton::new(&xs, &ys));
+        assert_eq!(newton.to_poly(), f);
+    }
+
+    #[test]
+    fn combine() {
+        let (_, xs, _) = sample();
+        let secret: Vec<u8> = (0..1000).map(|i| (i * 7) as u8).collect();
+        let polys: Vec<_> = secret
+            .iter()
+            .map(|&s| Poly::new([GF(s), GF(s ^ 0x55), GF(3), GF(s), GF(1)]))
+            .collect();
+        let shares: Vec<Vec<_>> = xs
+            .iter()
+            .map(|&x| polys.iter().map(|p| p.eval(x)).collect())
+            .collect();
+
+        let mut out = [0; 1000];
+        Lagrange::at(&xs, GF(0)).combine(&shares, GF::from_slice_mut(&mut out));
+
+        assert_eq!(&out[..], &secret[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn duplicate_points() {
+        Barycentric::new(&[GF(1u8), GF(2), GF(1)]);
+    }
+}
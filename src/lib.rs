@@ -14,8 +14,75 @@
 
 use core::fmt;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod additive_fft;
+#[cfg(feature = "alloc")]
+mod batch;
+#[cfg(feature = "alloc")]
+pub mod bch;
+#[cfg(feature = "alloc")]
+pub mod bech32;
+#[cfg(feature = "alloc")]
+pub mod ccsds;
+#[cfg(feature = "alloc")]
+mod checksum;
+#[cfg(feature = "alloc")]
+pub mod circ;
+#[cfg(feature = "alloc")]
+pub mod codex32;
+#[cfg(feature = "alloc")]
+pub mod erasure;
+#[cfg(feature = "alloc")]
+pub mod evaluation;
+mod field;
 mod gen_table;
+mod gf2_128;
+mod gf2m;
+#[cfg(feature = "alloc")]
+pub mod goppa;
+#[cfg(feature = "alloc")]
+pub mod guruswami_sudan;
 mod impls;
+#[cfg(feature = "alloc")]
+pub mod interpolate;
+#[cfg(feature = "alloc")]
+pub mod linear_code;
+#[cfg(feature = "alloc")]
+pub mod multipoint;
+#[cfg(feature = "alloc")]
+pub mod par2;
+#[cfg(feature = "alloc")]
+mod poly;
+mod prime;
+#[cfg(feature = "alloc")]
+pub mod qr;
+pub mod raid6;
+#[cfg(feature = "alloc")]
+pub mod reed_muller;
+#[cfg(feature = "alloc")]
+pub mod reed_solomon;
+#[cfg(feature = "alloc")]
+pub mod shamir;
+#[cfg(feature = "alloc")]
+pub mod share_format;
+#[cfg(feature = "alloc")]
+pub mod slip39;
+#[cfg(feature = "alloc")]
+pub mod ssss;
+#[cfg(feature = "alloc")]
+pub mod vault;
+
+#[cfg(feature = "alloc")]
+pub use batch::{batch_inv, batch_inv_or_zero};
+pub use field::{BinaryField, Field};
+pub use gf2_128::GF2_128;
+pub use gf2m::GF2m;
+#[cfg(feature = "alloc")]
+pub use poly::Poly;
+pub use prime::GFp;
 
 #[cfg(feature = "unstable_simd")]
 mod simd;
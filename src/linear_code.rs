@@ -0,0 +1,583 @@
+//! # Linear Block Codes
+//!
+//! An `[n, k]` code over any [`Field`], given by a generator or a parity-check
+//! matrix. The code keeps its generator matrix in reduced row echelon form, so
+//! two [`LinearCode`]s are equal exactly when they contain the same codewords.
+//!
+//! Everything that enumerates codewords or error patterns is exponential and
+//! meant for the small codes one designs by hand.
+//!
+//! ```
+//! use gf::{
+//!     linear_code::{LinearCode, SyndromeDecoder},
+//!     GFp,
+//! };
+//!
+//! type F = GFp<2>;
+//!
+//! // the [7, 4, 3] Hamming code, column c of the parity check is c in binary
+//! let h: Vec<Vec<F>> = (0..3)
+//!     .map(|bit| (1..=7).map(|c: u64| F::new(c >> bit & 1)).collect())
+//!     .collect();
+//! let code = LinearCode::from_parity_check(&h);
+//! assert_eq!((code.n(), code.k()), (7, 4));
+//! assert_eq!(code.minimum_distance(), Some(3));
+//! assert_eq!(code.weight_distribution(), [1, 0, 0, 7, 7, 0, 0, 1]);
+//!
+//! let decoder = SyndromeDecoder::new(&code, 1);
+//! let sent = code.encode(&[F::ONE, F::ZERO, F::ONE, F::ONE]);
+//! let mut received = sent.clone();
+//! received[5] += F::ONE;
+//! assert_eq!(decoder.decode(&mut received), Ok(1));
+//! assert_eq!(received, sent);
+//! ```
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+use crate::{reed_solomon::Error, Field};
+
+/// Brings `rows` into reduced row echelon form, taking the pivots from
+/// `columns` in the given order. Returns the pivot columns; row `i` has its
+/// pivot in column `pivots[i]` and the remaining rows are zero on all of
+/// `columns`.
+pub(crate) fn row_reduce<F: Field>(
+    rows: &mut [Vec<F>],
+    columns: impl IntoIterator<Item = usize>,
+) -> Vec<usize> {
+    let mut pivots = Vec::new();
+    for col in columns {
+        let r = pivots.len();
+        if r == rows.len() {
+            break;
+        }
+        let Some(p) = (r..rows.len()).find(|&i| !rows[i][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(r, p);
+        let inv = rows[r][col].inv();
+        F::mul_slice(&mut rows[r], inv);
+
+        let pivot_row = rows[r].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != r && !row[col].is_zero() {
+                let factor = -row[col];
+                F::mul_add_slice(row, &pivot_row, factor);
+            }
+        }
+        pivots.push(col);
+    }
+    pivots
+}
+
+fn weight<F: Field>(word: &[F]) -> usize {
+    word.iter().filter(|x| !x.is_zero()).count()
+}
+
+/// Calls `f` with every `w` element subset of `0..n`, in lexicographic order.
+fn for_each_subset(n: usize, w: usize, mut f: impl FnMut(&[usize])) {
+    if w > n {
+        return;
+    }
+    let mut subset: Vec<usize> = (0..w).collect();
+    loop {
+        f(&subset);
+        let Some(i) = (0..w).rev().find(|&i| subset[i] < n - w + i) else {
+            return;
+        };
+        subset[i] += 1;
+        for j in i + 1..w {
+            subset[j] = subset[j - 1] + 1;
+        }
+    }
+}
+
+/// Calls `f` with every vector of `len` nonzero elements, or only those
+/// starting with one if `normalized`.
+fn for_each_nonzero<F: Field>(len: usize, normalized: bool, mut f: impl FnMut(&[F])) {
+    let mut digits = vec![1; len];
+    let mut values = vec![F::ONE; len];
+    let first = usize::from(normalized && len > 0);
+    loop {
+        f(&values);
+        let Some(i) = (first..len).rev().find(|&i| digits[i] < F::ORDER) else {
+            return;
+        };
+        digits[i] += 1;
+        values[i] = F::from_index(digits[i]);
+        for j in i + 1..len {
+            digits[j] = 1;
+            values[j] = F::ONE;
+        }
+    }
+}
+
+/// # Linear Code
+///
+/// A `k` dimensional subspace of `F^n`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct LinearCode<F> {
+    n: usize,
+    /// the generator matrix in reduced row echelon form
+    generator: Vec<Vec<F>>,
+    /// the pivot column of every row of `generator`
+    pivots: Vec<usize>,
+    parity_check: Vec<Vec<F>>,
+}
+
+impl<F: Field> LinearCode<F> {
+    /// The code spanned by the rows of `generator`. Dependent rows are allowed
+    /// and reduce the dimension.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `generator` has no rows or rows of different lengths.
+    pub fn from_generator<R: AsRef<[F]>>(generator: &[R]) -> Self {
+        let (n, rows) = Self::matrix(generator);
+        Self::from_rows(n, rows)
+    }
+
+    /// The code of all words `c` with `H c = 0`, for the parity-check matrix
+    /// `H`. Dependent rows are allowed and increase the dimension.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parity_check` has no rows or rows of different lengths.
+    pub fn from_parity_check<R: AsRef<[F]>>(parity_check: &[R]) -> Self {
+        let (n, rows) = Self::matrix(parity_check);
+        Self::from_rows(n, Self::from_rows(n, rows).parity_check)
+    }
+
+    fn matrix<R: AsRef<[F]>>(rows: &[R]) -> (usize, Vec<Vec<F>>) {
+        assert!(!rows.is_empty(), "empty matrix");
+        let n = rows[0].as_ref().len();
+        assert!(
+            rows.iter().all(|r| r.as_ref().len() == n),
+            "rows differ in length"
+        );
+        (n, rows.iter().map(|r| r.as_ref().to_vec()).collect())
+    }
+
+    fn from_rows(n: usize, mut generator: Vec<Vec<F>>) -> Self {
+        let pivots = row_reduce(&mut generator, 0..n);
+        generator.truncate(pivots.len());
+
+        // one row per redundant column c: c minus its expansion in the pivots
+        let mut is_pivot = vec![false; n];
+        for &p in &pivots {
+            is_pivot[p] = true;
+        }
+        let parity_check = (0..n)
+            .filter(|&c| !is_pivot[c])
+            .map(|c| {
+                let mut row = vec![F::ZERO; n];
+                row[c] = F::ONE;
+                for (g, &p) in generator.iter().zip(&pivots) {
+                    row[p] = -g[c];
+                }
+                row
+            })
+            .collect();
+
+        Self {
+            n,
+            generator,
+            pivots,
+            parity_check,
+        }
+    }
+
+    /// Length of the codewords.
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Dimension of the code.
+    #[inline]
+    pub fn k(&self) -> usize {
+        self.generator.len()
+    }
+
+    /// The generator matrix in reduced row echelon form.
+    #[inline]
+    pub fn generator(&self) -> &[Vec<F>] {
+        &self.generator
+    }
+
+    /// A parity-check matrix with `n - k` independent rows.
+    #[inline]
+    pub fn parity_check(&self) -> &[Vec<F>] {
+        &self.parity_check
+    }
+
+    /// The positions where [`encode`](Self::encode) puts the message.
+    #[inline]
+    pub fn information_set(&self) -> &[usize] {
+        &self.pivots
+    }
+
+    /// Whether the information set is `0..k`, so the generator matrix already
+    /// is `[I | A]`.
+    pub fn is_systematic(&self) -> bool {
+        self.pivots.iter().copied().eq(0..self.k())
+    }
+
+    /// The generator matrix `[I | A]` of the equivalent code with the
+    /// information set moved to the front, and for every column of it the
+    /// column of this code it comes from.
+    pub fn systematic(&self) -> (Vec<Vec<F>>, Vec<usize>) {
+        let mut order = self.pivots.clone();
+        order.extend((0..self.n).filter(|c| !self.pivots.contains(c)));
+        let generator = self
+            .generator
+            .iter()
+            .map(|row| order.iter().map(|&c| row[c]).collect())
+            .collect();
+        (generator, order)
+    }
+
+    /// The code of all words orthogonal to this one, generated by its
+    /// parity-check matrix.
+    pub fn dual(&self) -> Self {
+        Self::from_rows(self.n, self.parity_check.clone())
+    }
+
+    /// The codeword for `message`, which appears unchanged at the positions
+    /// of the [`information_set`](Self::information_set).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `message` does not have exactly [`k`](Self::k) symbols.
+    pub fn encode(&self, message: &[F]) -> Vec<F> {
+        assert_eq!(message.len(), self.k(), "wrong message length");
+        let mut codeword = vec![F::ZERO; self.n];
+        for (row, &m) in self.generator.iter().zip(message) {
+            F::mul_add_slice(&mut codeword, row, m);
+        }
+        codeword
+    }
+
+    /// `H word`, zero exactly for codewords.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `word` does not have exactly [`n`](Self::n) symbols.
+    pub fn syndrome(&self, word: &[F]) -> Vec<F> {
+        assert_eq!(word.len(), self.n, "wrong word length");
+        self.parity_check
+            .iter()
+            .map(|row| row.iter().zip(word).map(|(&h, &c)| h * c).sum())
+            .collect()
+    }
+
+    /// `A_i`, the number of codewords of weight `i`, for `i` in `0..=n`, by
+    /// enumerating all `q^k` codewords.
+    pub fn weight_distribution(&self) -> Vec<u64> {
+        let mut distribution = vec![0; self.n + 1];
+        distribution[0] = 1;
+        // one codeword per line through the origin, the others are multiples
+        for lead in 0..self.k() {
+            let mut message = vec![F::ZERO; self.k()];
+            message[lead] = F::ONE;
+            self.for_each_tail(&mut message, lead + 1, &mut |codeword| {
+                distribution[weight(codeword)] += F::ORDER as u64;
+            });
+        }
+        distribution
+    }
+
+    /// Calls `f` with the codeword of every message that agrees with
+    /// `message` before `from`.
+    fn for_each_tail(&self, message: &mut [F], from: usize, f: &mut impl FnMut(&[F])) {
+        if from == message.len() {
+            f(&self.encode(message));
+            return;
+        }
+        for i in 0..=F::ORDER {
+            message[from] = F::from_index(i);
+            self.for_each_tail(message, from + 1, f);
+        }
+    }
+
+    /// The minimum distance by enumerating all codewords, `None` for the zero
+    /// code.
+    pub fn minimum_distance_exhaustive(&self) -> Option<usize> {
+        let distribution = self.weight_distribution();
+        (1..=self.n).find(|&w| distribution[w] != 0)
+    }
+
+    /// The minimum distance with the Brouwer–Zimmermann algorithm, `None` for
+    /// the zero code.
+    ///
+    /// The generator matrix is brought into systematic form on as many
+    /// disjoint information sets as there are. Every codeword that is not a
+    /// combination of at most `w` rows of any of these matrices has at least
+    /// `w + 1` nonzero symbols on every one of the sets, so the search stops as
+    /// soon as that lower bound reaches the lightest codeword found.
+    pub fn minimum_distance(&self) -> Option<usize> {
+        let k = self.k();
+        if k == 0 {
+            return None;
+        }
+
+        let mut matrices = Vec::new();
+        let mut used = vec![false; self.n];
+        loop {
+            let mut rows = self.generator.clone();
+            let pivots = row_reduce(&mut rows, (0..self.n).filter(|&c| !used[c]));
+            if pivots.is_empty() {
+                break;
+            }
+            for &p in &pivots {
+                used[p] = true;
+            }
+            matrices.push((rows, pivots.len()));
+        }
+
+        let mut upper = self.n;
+        let mut codeword = vec![F::ZERO; self.n];
+        for w in 1..=k {
+            for (rows, _) in &matrices {
+                for_each_subset(k, w, |subset| {
+                    for_each_nonzero(w, true, |coeffs: &[F]| {
+                        codeword.fill(F::ZERO);
+                        for (&r, &c) in subset.iter().zip(coeffs) {
+                            F::mul_add_slice(&mut codeword, &rows[r], c);
+                        }
+                        upper = upper.min(weight(&codeword));
+                    });
+                });
+            }
+
+            // a set of rank r only guarantees w + 1 - (k - r) symbols
+            let lower: usize = matrices
+                .iter()
+                .map(|&(_, rank)| (w + 1).saturating_sub(k - rank))
+                .sum();
+            if lower >= upper {
+                break;
+            }
+        }
+        Some(upper)
+    }
+
+    /// Whether the code meets the Singleton bound `d = n - k + 1`.
+    pub fn is_mds(&self) -> bool {
+        self.minimum_distance()
+            .is_none_or(|d| d == self.n - self.k() + 1)
+    }
+}
+
+/// # Syndrome Decoder
+///
+/// A table from syndromes to coset leaders, the lightest error patterns that
+/// produce them.
+#[derive(Clone, Debug)]
+pub struct SyndromeDecoder<F> {
+    code: LinearCode<F>,
+    leaders: BTreeMap<Vec<u128>, Vec<F>>,
+}
+
+impl<F: Field> SyndromeDecoder<F> {
+    /// Tables every error pattern of up to `max_weight` symbols. Patterns are
+    /// tried from light to heavy, so each syndrome keeps one of its lightest
+    /// patterns; with `max_weight` at least the covering radius, every word
+    /// decodes.
+    pub fn new(code: &LinearCode<F>, max_weight: usize) -> Self {
+        let n = code.n();
+        let syndromes = (F::ORDER + 1).checked_pow((n - code.k()) as u32);
+        let mut leaders = BTreeMap::new();
+        leaders.insert(vec![0; n - code.k()], vec![F::ZERO; n]);
+
+        let mut error = vec![F::ZERO; n];
+        for w in 1..=max_weight.min(n) {
+            for_each_subset(n, w, |positions| {
+                for_each_nonzero(w, false, |values: &[F]| {
+                    for (&p, &v) in positions.iter().zip(values) {
+                        error[p] = v;
+                    }
+                    let key = code.syndrome(&error).into_iter().map(F::to_index).collect();
+                    leaders.entry(key).or_insert_with(|| error.clone());
+                });
+                for &p in positions {
+                    error[p] = F::ZERO;
+                }
+            });
+            if Some(leaders.len() as u128) == syndromes {
+                break;
+            }
+        }
+
+        Self {
+            code: code.clone(),
+            leaders,
+        }
+    }
+
+    /// Number of syndromes in the table, including zero.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.leaders.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.leaders.is_empty()
+    }
+
+    /// Subtracts the coset leader of the syndrome of `received` and returns
+    /// its weight. A syndrome missing from the table means more errors than
+    /// the table covers, and leaves `received` untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `received` does not have exactly `n` symbols.
+    pub fn decode(&self, received: &mut [F]) -> Result<usize, Error> {
+        let key: Vec<u128> = self
+            .code
+            .syndrome(received)
+            .into_iter()
+            .map(F::to_index)
+            .collect();
+        let leader = self.leaders.get(&key).ok_or(Error::TooManyErrors)?;
+        for (r, &e) in received.iter_mut().zip(leader) {
+            *r -= e;
+        }
+        Ok(weight(leader))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::{LinearCode, SyndromeDecoder};
+    use crate::{reed_solomon::Error, GFp, GF};
+
+    type F2 = GFp<2>;
+
+    fn binary(rows: &[&str]) -> Vec<Vec<F2>> {
+        rows.iter()
+            .map(|r| r.bytes().map(|b| F2::new((b - b'0') as u64)).collect())
+            .collect()
+    }
+
+    /// The [23, 12, 7] Golay code from its generator polynomial.
+    fn golay() -> LinearCode<F2> {
+        let g = binary(&["101011100011"]).remove(0);
+        let rows: Vec<Vec<F2>> = (0..12)
+            .map(|i| {
+                let mut row = vec![F2::ZERO; 23];
+                row[i..i + 12].copy_from_slice(&g);
+                row
+            })
+            .collect();
+        LinearCode::from_generator(&rows)
+    }
+
+    #[test]
+    fn golay_distance() {
+        let code = golay();
+        assert_eq!((code.n(), code.k()), (23, 12));
+        assert_eq!(code.minimum_distance(), Some(7));
+        assert_eq!(code.minimum_distance_exhaustive(), Some(7));
+
+        let mut expected = vec![0; 24];
+        for (w, a) in [(0, 1), (7, 253), (8, 506), (11, 1288), (12, 1288)] {
+            expected[w] = a;
+            expected[23 - w] = a;
+        }
+        assert_eq!(code.weight_distribution(), expected);
+        assert!(!code.is_mds());
+    }
+
+    #[test]
+    fn dual() {
+        let code = golay();
+        let dual = code.dual();
+        assert_eq!(dual.k(), 11);
+        assert_eq!(dual.dual(), code);
+        for a in code.generator() {
+            for b in dual.generator() {
+                let dot: F2 = a.iter().zip(b).map(|(&x, &y)| x * y).sum();
+                assert_eq!(dot, F2::ZERO);
+            }
+        }
+    }
+
+    #[test]
+    fn parity_check_roundtrip() {
+        let code = golay();
+        assert_eq!(LinearCode::from_parity_check(code.parity_check()), code);
+    }
+
+    #[test]
+    fn dependent_rows_and_systematic_form() {
+        let code = LinearCode::from_generator(&binary(&["01101", "00110", "01011"]));
+        assert_eq!(code.k(), 2);
+        assert!(!code.is_systematic());
+        assert_eq!(code.information_set(), [1, 2]);
+
+        let (generator, order) = code.systematic();
+        assert_eq!(order, [1, 2, 0, 3, 4]);
+        assert_eq!(generator, binary(&["10011", "01010"]));
+        assert!(LinearCode::from_generator(&generator).is_systematic());
+
+        let message = [F2::ONE, F2::ONE];
+        let codeword = code.encode(&message);
+        assert_eq!(codeword, binary(&["01101"])[0]);
+        assert_eq!([codeword[1], codeword[2]], message);
+    }
+
+    #[test]
+    fn mds() {
+        // the ternary tetracode
+        type F3 = GFp<3>;
+        let g: Vec<Vec<F3>> = [[1, 0, 1, 1], [0, 1, 1, 2]]
+            .iter()
+            .map(|r| r.iter().map(|&x| F3::new(x)).collect())
+            .collect();
+        let code = LinearCode::from_generator(&g);
+        assert!(code.is_mds());
+        assert_eq!(code.weight_distribution(), [1, 0, 0, 8, 0]);
+
+        // a [10, 4] Reed-Solomon code
+        let rows: Vec<Vec<GF<u8>>> = (0..4)
+            .map(|i| (1..=10).map(|x| GF(x as u8).pow(i)).collect())
+            .collect();
+        let code = LinearCode::from_generator(&rows);
+        assert_eq!(code.minimum_distance(), Some(7));
+        assert!(code.is_mds());
+        assert!(code.dual().is_mds());
+    }
+
+    #[test]
+    fn perfect_syndrome_decoding() {
+        let code = golay();
+        let decoder = SyndromeDecoder::new(&code, 5);
+        assert_eq!(decoder.len(), 1 << 11);
+
+        let message: Vec<F2> = (0..12).map(|i| F2::new(i % 3 % 2)).collect();
+        let sent = code.encode(&message);
+        let mut received = sent.clone();
+        for i in [0, 11, 22] {
+            received[i] += F2::ONE;
+        }
+        assert_eq!(decoder.decode(&mut received), Ok(3));
+        assert_eq!(received, sent);
+    }
+
+    #[test]
+    fn incomplete_table() {
+        let code = golay();
+        let decoder = SyndromeDecoder::new(&code, 1);
+        assert_eq!(decoder.len(), 24);
+
+        let mut received = code.encode(&[F2::ONE; 12]);
+        received[3] += F2::ONE;
+        received[4] += F2::ONE;
+        let damaged = received.clone();
+        assert_eq!(decoder.decode(&mut received), Err(Error::TooManyErrors));
+        assert_eq!(received, damaged);
+    }
+}
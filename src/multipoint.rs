@@ -0,0 +1,186 @@
+//! # Multipoint Evaluation and Interpolation
+//!
+//! Both run down or up a [`SubproductTree`] of the points, so with fast
+//! [`Poly`] multiplication and division they take quasi-linear time instead of
+//! the `O(n^2)` of one Horner evaluation per point.
+//!
+//! ```
+//! use gf::{multipoint, Poly, GF};
+//!
+//! let f = Poly::new([GF(1u8), GF(2), GF(3)]);
+//! let points = [GF(10), GF(20), GF(30), GF(40)];
+//!
+//! let values = multipoint::evaluate_many(&f, &points);
+//! assert_eq!(values[1], f.eval(GF(20)));
+//!
+//! assert_eq!(multipoint::interpolate_many(&points, &values), f);
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{batch_inv, interpolate, Field, Poly};
+
+/// Subtrees with at most this many points are handled with Horner's method.
+const LEAF_SIZE: usize = 16;
+
+/// Products of `(x - x_i)` over ever larger ranges of the points, built once
+/// and reused for any number of polynomials or value sets.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SubproductTree<F> {
+    points: Vec<F>,
+    /// `levels[0]` holds the products over chunks of [`LEAF_SIZE`] points, every
+    /// following level the pairwise products of the one below, up to the root.
+    levels: Vec<Vec<Poly<F>>>,
+}
+
+impl<F: Field> SubproductTree<F> {
+    pub fn new(points: &[F]) -> Self {
+        let mut levels = vec![points
+            .chunks(LEAF_SIZE)
+            .map(Poly::from_roots)
+            .collect::<Vec<_>>()];
+
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => a * b,
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Self {
+            points: points.to_vec(),
+            levels,
+        }
+    }
+
+    #[inline]
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// `prod (x - x_i)` over all points.
+    pub fn root(&self) -> Poly<F> {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .cloned()
+            .unwrap_or_else(Poly::one)
+    }
+
+    /// Evaluates `poly` at every point.
+    pub fn evaluate(&self, poly: &Poly<F>) -> Vec<F> {
+        let mut rems = vec![poly.div_rem(&self.root()).1];
+        for level in self.levels.iter().rev().skip(1) {
+            rems = level
+                .chunks(2)
+                .zip(&rems)
+                .flat_map(|(children, rem)| children.iter().map(|m| rem.div_rem(m).1))
+                .collect();
+        }
+
+        let mut values = Vec::with_capacity(self.points.len());
+        for (points, rem) in self.points.chunks(LEAF_SIZE).zip(&rems) {
+            values.extend(points.iter().map(|&x| rem.eval(x)));
+        }
+        values
+    }
+
+    /// The polynomial of degree below the number of points that takes
+    /// `values[i]` at `points[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the points contain duplicates or `values` has the wrong length.
+    pub fn interpolate(&self, values: &[F]) -> Poly<F> {
+        assert_eq!(self.points.len(), values.len(), "slice lengths differ");
+
+        // Lagrange weights 1 / M'(x_i) for M = prod (x - x_i)
+        let mut weights = self.evaluate(&self.root().derivative());
+        batch_inv(&mut weights);
+
+        let mut sums: Vec<Poly<F>> = self
+            .points
+            .chunks(LEAF_SIZE)
+            .zip(values.chunks(LEAF_SIZE).zip(weights.chunks(LEAF_SIZE)))
+            .map(|(points, (values, weights))| {
+                let scaled: Vec<F> = values.iter().zip(weights).map(|(&y, &w)| y * w).collect();
+                interpolate::combination(points, &scaled)
+            })
+            .collect();
+
+        // sum_i c_i M / (x - x_i), merged as left * M_right + right * M_left
+        for level in &self.levels[..self.levels.len() - 1] {
+            sums = sums
+                .chunks(2)
+                .zip(level.chunks(2))
+                .map(|pair| match pair {
+                    ([l, r], [ml, mr]) => l * mr + r * ml,
+                    ([l], _) => l.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+
+        sums.pop().unwrap_or_else(Poly::zero)
+    }
+}
+
+/// Evaluates `poly` at every one of `points`.
+pub fn evaluate_many<F: Field>(poly: &Poly<F>, points: &[F]) -> Vec<F> {
+    SubproductTree::new(points).evaluate(poly)
+}
+
+/// The polynomial of degree below `points.len()` through all `(points[i], values[i])`.
+///
+/// # Panics
+///
+/// Panics if `points` contains duplicates or the slices differ in length.
+pub fn interpolate_many<F: Field>(points: &[F], values: &[F]) -> Poly<F> {
+    SubproductTree::new(points).interpolate(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{evaluate_many, interpolate_many, SubproductTree};
+    use crate::{GFp, Poly, GF};
+
+    #[test]
+    fn evaluate_gf256() {
+        let f = Poly::new((0..100).map(|i| GF((i * 3 + 1) as u8)).collect::<Vec<_>>());
+        let points: Vec<_> = (0..=255).map(GF).collect();
+
+        let values = evaluate_many(&f, &points);
+        for (&x, &y) in points.iter().zip(&values) {
+            assert_eq!(f.eval(x), y);
+        }
+    }
+
+    #[test]
+    fn roundtrip_prime() {
+        type F = GFp<998244353>;
+
+        let f = Poly::new((0..1000u64).map(|i| F::new(i * i + 7)).collect::<Vec<_>>());
+        let points: Vec<_> = (0..1000u64).map(|i| F::new(i * 31 + 2)).collect();
+
+        let tree = SubproductTree::new(&points);
+        let values = tree.evaluate(&f);
+        assert_eq!(values[500], f.eval(points[500]));
+        assert_eq!(tree.interpolate(&values), f);
+    }
+
+    #[test]
+    fn small() {
+        let f = Poly::new([GF(9u8)]);
+        assert_eq!(interpolate_many(&[GF(3)], &evaluate_many(&f, &[GF(3)])), f);
+        assert_eq!(interpolate_many::<GF<u8>>(&[], &[]), Poly::zero());
+    }
+}
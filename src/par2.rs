@@ -0,0 +1,847 @@
+//! # PAR2
+//!
+//! Reading and writing of PAR 2.0 packets together with the Reed–Solomon code
+//! behind them, compatible with par2cmdline.
+//!
+//! Every file is cut into slices of `slice_size` bytes, the last one padded
+//! with zeros, and the slices of all files numbered in the order of the
+//! [`Main`] packet. Slice `i` gets the constant `c_i = 2^n_i` in
+//! `GF(2^16)` with the polynomial `0x1100B`, where `n_i` runs through the
+//! exponents coprime to `65535`. The recovery slice with exponent `e` is
+//! `sum_i c_i^e D_i`, taken over little endian 16-bit words.
+//!
+//! ```
+//! use gf::par2::{Packet, RecoverySet};
+//!
+//! let mut contents = vec![b"hello world, hello par2".to_vec(), b"second file".to_vec()];
+//! let set = RecoverySet::new(8, &[("a.txt", &contents[0]), ("b.txt", &contents[1])]);
+//! let recovery = set.recovery_slices(&contents, 0..2);
+//!
+//! let mut par2 = Vec::new();
+//! set.write_packets(&mut par2);
+//! for slice in recovery {
+//!     Packet::RecoverySlice(slice).write(&set.id(), &mut par2);
+//! }
+//!
+//! // read everything back, damage both files and repair them
+//! let set = RecoverySet::from_packets(gf::par2::packets(&par2)).unwrap();
+//! let recovery: Vec<_> = gf::par2::packets(&par2)
+//!     .filter_map(|(_, packet)| match packet {
+//!         Packet::RecoverySlice(slice) => Some(slice),
+//!         _ => None,
+//!     })
+//!     .collect();
+//!
+//! contents[0][3] ^= 1;
+//! contents[1].truncate(8);
+//! assert_eq!(set.damaged_slices(&contents).len(), 2);
+//!
+//! set.repair(&mut contents, &recovery).unwrap();
+//! assert_eq!(contents[1], b"second file");
+//! ```
+
+use alloc::{string::String, vec, vec::Vec};
+use core::{fmt, ops::Range};
+
+use crate::{
+    checksum::{crc32, Md5},
+    Field, GF2m,
+};
+
+type G = GF2m<0x1100B>;
+
+pub type Md5Hash = [u8; 16];
+
+const MAGIC: &[u8; 8] = b"PAR2\0PKT";
+const HEADER_LEN: usize = 64;
+
+const MAIN: &[u8; 16] = b"PAR 2.0\0Main\0\0\0\0";
+const FILE_DESCRIPTION: &[u8; 16] = b"PAR 2.0\0FileDesc";
+const IFSC: &[u8; 16] = b"PAR 2.0\0IFSC\0\0\0\0";
+const RECOVERY_SLICE: &[u8; 16] = b"PAR 2.0\0RecvSlic";
+const CREATOR: &[u8; 16] = b"PAR 2.0\0Creator\0";
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    /// Fewer bytes than the packet header announces.
+    Truncated,
+    /// The packet hash does not match its contents.
+    Corrupt,
+    /// A packet body that does not fit its type.
+    Malformed,
+    /// No main packet, or a file without description or checksums.
+    MissingPacket,
+    /// Fewer recovery slices than damaged slices.
+    NotEnoughRecovery,
+    /// The recovery slices could not restore the original data.
+    Unrecoverable,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Truncated => "truncated packet".fmt(f),
+            Error::Corrupt => "packet hash mismatch".fmt(f),
+            Error::Malformed => "malformed packet".fmt(f),
+            Error::MissingPacket => "missing packet".fmt(f),
+            Error::NotEnoughRecovery => "not enough recovery slices".fmt(f),
+            Error::Unrecoverable => "data could not be recovered".fmt(f),
+        }
+    }
+}
+
+/// Body of the main packet.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Main {
+    pub slice_size: u64,
+    /// IDs of the files covered by recovery slices, sorted.
+    pub files: Vec<Md5Hash>,
+    /// IDs of files that are only checksummed, sorted.
+    pub non_recovery_files: Vec<Md5Hash>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FileDescription {
+    pub id: Md5Hash,
+    pub hash: Md5Hash,
+    /// MD5 of the first 16 KiB of the file.
+    pub hash_16k: Md5Hash,
+    pub length: u64,
+    pub name: String,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SliceChecksum {
+    pub hash: Md5Hash,
+    pub crc32: u32,
+}
+
+/// Input file slice checksums.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Ifsc {
+    pub file_id: Md5Hash,
+    pub checksums: Vec<SliceChecksum>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RecoverySlice {
+    pub exponent: u32,
+    pub data: Vec<u8>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Packet {
+    Main(Main),
+    FileDescription(FileDescription),
+    Ifsc(Ifsc),
+    RecoverySlice(RecoverySlice),
+    Creator(String),
+    /// Any packet type not listed above, kept as is.
+    Unknown {
+        kind: [u8; 16],
+        body: Vec<u8>,
+    },
+}
+
+/// Splits fixed size fields off the front of a packet body.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        if self.0.len() < N {
+            return Err(Error::Malformed);
+        }
+        let (head, rest) = self.0.split_at(N);
+        self.0 = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn hashes(self) -> Result<Vec<Md5Hash>, Error> {
+        let mut chunks = self.0.chunks_exact(16);
+        let hashes = chunks.by_ref().map(|h| h.try_into().unwrap()).collect();
+        if chunks.remainder().is_empty() {
+            Ok(hashes)
+        } else {
+            Err(Error::Malformed)
+        }
+    }
+
+    /// The rest as a string, without the zero padding.
+    fn string(self) -> Result<String, Error> {
+        let len = self.0.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        String::from_utf8(self.0[..len].to_vec()).map_err(|_| Error::Malformed)
+    }
+}
+
+impl Packet {
+    fn kind(&self) -> &[u8; 16] {
+        match self {
+            Packet::Main(_) => MAIN,
+            Packet::FileDescription(_) => FILE_DESCRIPTION,
+            Packet::Ifsc(_) => IFSC,
+            Packet::RecoverySlice(_) => RECOVERY_SLICE,
+            Packet::Creator(_) => CREATOR,
+            Packet::Unknown { kind, .. } => kind,
+        }
+    }
+
+    fn body(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        match self {
+            Packet::Main(main) => {
+                body.extend_from_slice(&main.slice_size.to_le_bytes());
+                body.extend_from_slice(&(main.files.len() as u32).to_le_bytes());
+                for id in main.files.iter().chain(&main.non_recovery_files) {
+                    body.extend_from_slice(id);
+                }
+            }
+            Packet::FileDescription(file) => {
+                body.extend_from_slice(&file.id);
+                body.extend_from_slice(&file.hash);
+                body.extend_from_slice(&file.hash_16k);
+                body.extend_from_slice(&file.length.to_le_bytes());
+                body.extend_from_slice(file.name.as_bytes());
+            }
+            Packet::Ifsc(ifsc) => {
+                body.extend_from_slice(&ifsc.file_id);
+                for checksum in &ifsc.checksums {
+                    body.extend_from_slice(&checksum.hash);
+                    body.extend_from_slice(&checksum.crc32.to_le_bytes());
+                }
+            }
+            Packet::RecoverySlice(slice) => {
+                body.extend_from_slice(&slice.exponent.to_le_bytes());
+                body.extend_from_slice(&slice.data);
+            }
+            Packet::Creator(creator) => body.extend_from_slice(creator.as_bytes()),
+            Packet::Unknown { body: b, .. } => body.extend_from_slice(b),
+        }
+        body.resize(body.len().next_multiple_of(4), 0);
+        body
+    }
+
+    fn parse_body(kind: &[u8; 16], body: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader(body);
+        Ok(match kind {
+            MAIN => {
+                let slice_size = r.u64()?;
+                let count = r.u32()? as usize;
+                let mut files = r.hashes()?;
+                if count > files.len() || slice_size == 0 || slice_size % 4 != 0 {
+                    return Err(Error::Malformed);
+                }
+                let non_recovery_files = files.split_off(count);
+                Packet::Main(Main {
+                    slice_size,
+                    files,
+                    non_recovery_files,
+                })
+            }
+            FILE_DESCRIPTION => Packet::FileDescription(FileDescription {
+                id: r.take()?,
+                hash: r.take()?,
+                hash_16k: r.take()?,
+                length: r.u64()?,
+                name: r.string()?,
+            }),
+            IFSC => {
+                let file_id = r.take()?;
+                let mut entries = r.0.chunks_exact(20);
+                let checksums = entries
+                    .by_ref()
+                    .map(|e| SliceChecksum {
+                        hash: e[..16].try_into().unwrap(),
+                        crc32: u32::from_le_bytes(e[16..].try_into().unwrap()),
+                    })
+                    .collect();
+                if !entries.remainder().is_empty() {
+                    return Err(Error::Malformed);
+                }
+                Packet::Ifsc(Ifsc { file_id, checksums })
+            }
+            RECOVERY_SLICE => Packet::RecoverySlice(RecoverySlice {
+                exponent: r.u32()?,
+                data: r.0.to_vec(),
+            }),
+            CREATOR => Packet::Creator(r.string()?),
+            _ => Packet::Unknown {
+                kind: *kind,
+                body: body.to_vec(),
+            },
+        })
+    }
+
+    /// Appends the packet, with header, for the recovery set `set_id`.
+    pub fn write(&self, set_id: &Md5Hash, out: &mut Vec<u8>) {
+        let body = self.body();
+
+        let mut md5 = Md5::new();
+        md5.update(set_id);
+        md5.update(self.kind());
+        md5.update(&body);
+
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&((HEADER_LEN + body.len()) as u64).to_le_bytes());
+        out.extend_from_slice(&md5.finish());
+        out.extend_from_slice(set_id);
+        out.extend_from_slice(self.kind());
+        out.extend_from_slice(&body);
+    }
+
+    /// Reads the packet at the start of `bytes`, returning its recovery set ID
+    /// and its total length.
+    pub fn read(bytes: &[u8]) -> Result<(Md5Hash, Packet, usize), Error> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(Error::Truncated);
+        }
+        let mut header = Reader(&bytes[8..HEADER_LEN]);
+        let len = header.u64()?;
+        let hash: Md5Hash = header.take()?;
+        let set_id: Md5Hash = header.take()?;
+        let kind: [u8; 16] = header.take()?;
+
+        if len < HEADER_LEN as u64 || len % 4 != 0 {
+            return Err(Error::Malformed);
+        }
+        if len > bytes.len() as u64 {
+            return Err(Error::Truncated);
+        }
+        let len = len as usize;
+        if Md5::digest(&bytes[32..len]) != hash {
+            return Err(Error::Corrupt);
+        }
+
+        let packet = Self::parse_body(&kind, &bytes[HEADER_LEN..len])?;
+        Ok((set_id, packet, len))
+    }
+}
+
+/// Iterates over all intact packets in `bytes`, skipping over damaged ones.
+pub fn packets(bytes: &[u8]) -> Packets<'_> {
+    Packets { bytes }
+}
+
+#[derive(Clone, Debug)]
+pub struct Packets<'a> {
+    bytes: &'a [u8],
+}
+
+impl Iterator for Packets<'_> {
+    type Item = (Md5Hash, Packet);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.bytes.windows(8).position(|w| w == MAGIC)?;
+            self.bytes = &self.bytes[start..];
+            match Packet::read(self.bytes) {
+                Ok((set_id, packet, len)) => {
+                    self.bytes = &self.bytes[len..];
+                    return Some((set_id, packet));
+                }
+                Err(_) => self.bytes = &self.bytes[1..],
+            }
+        }
+    }
+}
+
+/// `c_i` for the first `count` input slices.
+fn input_constants(count: usize) -> Vec<G> {
+    let mut constants = Vec::with_capacity(count);
+    let mut log = 0usize;
+    while constants.len() < count {
+        log += 1;
+        if [3, 5, 17, 257].iter().all(|&p| !log.is_multiple_of(p)) {
+            constants.push(G::exp(log));
+        }
+    }
+    constants
+}
+
+fn to_words(bytes: &[u8], len: usize) -> Vec<G> {
+    let mut words = vec![G::ZERO; len / 2];
+    for (w, b) in words.iter_mut().zip(bytes.chunks(2)) {
+        *w = G::new(u16::from_le_bytes([b[0], b.get(1).copied().unwrap_or(0)]));
+    }
+    words
+}
+
+fn to_bytes(words: &[G]) -> Vec<u8> {
+    words.iter().flat_map(|w| w.0.to_le_bytes()).collect()
+}
+
+/// Inverts a square matrix by Gauss–Jordan elimination.
+fn invert(mut matrix: Vec<Vec<G>>) -> Option<Vec<Vec<G>>> {
+    let n = matrix.len();
+    let mut inverse: Vec<Vec<G>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if i == j { G::ONE } else { G::ZERO })
+                .collect()
+        })
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|&r| matrix[r][col] != G::ZERO)?;
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let scale = matrix[col][col].inv();
+        G::mul_slice(&mut matrix[col], scale);
+        G::mul_slice(&mut inverse[col], scale);
+
+        for row in 0..n {
+            let factor = matrix[row][col];
+            if row != col && factor != G::ZERO {
+                let (pivot_row, pivot_inv) = (matrix[col].clone(), inverse[col].clone());
+                G::mul_add_slice(&mut matrix[row], &pivot_row, factor);
+                G::mul_add_slice(&mut inverse[row], &pivot_inv, factor);
+            }
+        }
+    }
+    Some(inverse)
+}
+
+/// The critical packets of a recovery set: main, file descriptions and slice
+/// checksums.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RecoverySet {
+    main: Main,
+    files: Vec<FileDescription>,
+    checksums: Vec<Ifsc>,
+    /// index of the first input slice of every file
+    first_slice: Vec<usize>,
+}
+
+impl RecoverySet {
+    /// Describes `files` as `(name, contents)` pairs. All other methods take
+    /// the contents in this order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice_size` is zero or not a multiple of 4.
+    pub fn new(slice_size: u64, files: &[(&str, &[u8])]) -> Self {
+        assert!(
+            slice_size != 0 && slice_size.is_multiple_of(4),
+            "slice size must be a positive multiple of 4"
+        );
+
+        let mut descriptions = Vec::with_capacity(files.len());
+        let mut checksums = Vec::with_capacity(files.len());
+        for &(name, data) in files {
+            let hash_16k = Md5::digest(&data[..data.len().min(16384)]);
+            let mut id = Md5::new();
+            id.update(&hash_16k);
+            id.update(&(data.len() as u64).to_le_bytes());
+            id.update(name.as_bytes());
+            let id = id.finish();
+
+            descriptions.push(FileDescription {
+                id,
+                hash: Md5::digest(data),
+                hash_16k,
+                length: data.len() as u64,
+                name: name.into(),
+            });
+            checksums.push(Ifsc {
+                file_id: id,
+                checksums: data
+                    .chunks(slice_size as usize)
+                    .map(|s| {
+                        let mut slice = s.to_vec();
+                        slice.resize(slice_size as usize, 0);
+                        SliceChecksum {
+                            hash: Md5::digest(&slice),
+                            crc32: crc32(&slice),
+                        }
+                    })
+                    .collect(),
+            });
+        }
+
+        let mut ids: Vec<Md5Hash> = descriptions.iter().map(|f| f.id).collect();
+        ids.sort_by_key(|&id| u128::from_le_bytes(id));
+        ids.dedup();
+        let main = Main {
+            slice_size,
+            files: ids,
+            non_recovery_files: Vec::new(),
+        };
+
+        Self::assemble(main, descriptions, checksums)
+    }
+
+    /// Collects the recovery set of the first main packet among `packets`.
+    pub fn from_packets(
+        packets: impl IntoIterator<Item = (Md5Hash, Packet)>,
+    ) -> Result<Self, Error> {
+        let mut set_id = None;
+        let mut main = None;
+        let mut descriptions = Vec::new();
+        let mut checksums = Vec::new();
+
+        for (id, packet) in packets {
+            if set_id.is_some_and(|set_id| set_id != id) {
+                continue;
+            }
+            match packet {
+                Packet::Main(m) if main.is_none() => {
+                    set_id = Some(id);
+                    main = Some(m);
+                }
+                Packet::FileDescription(file) => descriptions.push((id, file)),
+                Packet::Ifsc(ifsc) => checksums.push((id, ifsc)),
+                _ => {}
+            }
+        }
+
+        let (set_id, main) = set_id.zip(main).ok_or(Error::MissingPacket)?;
+        let mut files = Vec::with_capacity(main.files.len());
+        let mut file_checksums = Vec::with_capacity(main.files.len());
+        for file_id in &main.files {
+            let file = descriptions
+                .iter()
+                .find(|(id, f)| *id == set_id && f.id == *file_id)
+                .ok_or(Error::MissingPacket)?;
+            let ifsc = checksums
+                .iter()
+                .find(|(id, c)| *id == set_id && c.file_id == *file_id)
+                .ok_or(Error::MissingPacket)?;
+            if (ifsc.1.checksums.len() as u64) != file.1.length.div_ceil(main.slice_size) {
+                return Err(Error::Malformed);
+            }
+            files.push(file.1.clone());
+            file_checksums.push(ifsc.1.clone());
+        }
+
+        Ok(Self::assemble(main, files, file_checksums))
+    }
+
+    fn assemble(main: Main, files: Vec<FileDescription>, checksums: Vec<Ifsc>) -> Self {
+        let mut order: Vec<usize> = (0..files.len()).collect();
+        order.sort_by_key(|&k| main.files.iter().position(|id| *id == files[k].id));
+
+        let mut first_slice = vec![0; files.len()];
+        let mut next = 0;
+        for k in order {
+            first_slice[k] = next;
+            next += checksums[k].checksums.len();
+        }
+
+        Self {
+            main,
+            files,
+            checksums,
+            first_slice,
+        }
+    }
+
+    /// The recovery set ID, the MD5 of the main packet body.
+    pub fn id(&self) -> Md5Hash {
+        Md5::digest(&Packet::Main(self.main.clone()).body())
+    }
+
+    #[inline]
+    pub fn slice_size(&self) -> u64 {
+        self.main.slice_size
+    }
+
+    #[inline]
+    pub fn files(&self) -> &[FileDescription] {
+        &self.files
+    }
+
+    /// Number of input slices over all files.
+    pub fn slice_count(&self) -> usize {
+        self.checksums.iter().map(|c| c.checksums.len()).sum()
+    }
+
+    /// Appends the main, file description and slice checksum packets.
+    pub fn write_packets(&self, out: &mut Vec<u8>) {
+        let id = self.id();
+        Packet::Main(self.main.clone()).write(&id, out);
+        for (file, ifsc) in self.files.iter().zip(&self.checksums) {
+            Packet::FileDescription(file.clone()).write(&id, out);
+            Packet::Ifsc(ifsc.clone()).write(&id, out);
+        }
+    }
+
+    /// Input slices as words, `None` where the contents are missing.
+    fn input_slices<D: AsRef<[u8]>>(&self, contents: &[D]) -> Vec<(usize, Option<Vec<G>>)> {
+        assert_eq!(contents.len(), self.files.len(), "slice lengths differ");
+        let size = self.main.slice_size as usize;
+
+        let mut slices = Vec::with_capacity(self.slice_count());
+        for (k, data) in contents.iter().enumerate() {
+            let data = data.as_ref();
+            for (s, checksum) in self.checksums[k].checksums.iter().enumerate() {
+                let start = (s * size).min(data.len());
+                let end = ((s + 1) * size).min(data.len());
+                let full_end = ((s + 1) * size).min(self.files[k].length as usize);
+
+                let mut slice = data[start..end].to_vec();
+                let intact = end == full_end && {
+                    slice.resize(size, 0);
+                    Md5::digest(&slice) == checksum.hash && crc32(&slice) == checksum.crc32
+                };
+                let words = intact.then(|| to_words(&slice, size));
+                slices.push((self.first_slice[k] + s, words));
+            }
+        }
+        slices.sort_by_key(|&(i, _)| i);
+        slices
+    }
+
+    /// Computes the recovery slices with the given exponents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `contents` does not match the files.
+    pub fn recovery_slices<D: AsRef<[u8]>>(
+        &self,
+        contents: &[D],
+        exponents: Range<u32>,
+    ) -> Vec<RecoverySlice> {
+        let size = self.main.slice_size as usize;
+        let inputs = self.input_slices(contents);
+        let constants = input_constants(inputs.len());
+
+        exponents
+            .map(|exponent| {
+                let mut sum = vec![G::ZERO; size / 2];
+                for ((_, words), c) in inputs.iter().zip(&constants) {
+                    let words = words.as_ref().expect("contents do not match the set");
+                    G::mul_add_slice(&mut sum, words, c.pow(exponent as usize));
+                }
+                RecoverySlice {
+                    exponent,
+                    data: to_bytes(&sum),
+                }
+            })
+            .collect()
+    }
+
+    /// Global indices of all input slices that are missing or fail their
+    /// checksums.
+    pub fn damaged_slices<D: AsRef<[u8]>>(&self, contents: &[D]) -> Vec<usize> {
+        self.input_slices(contents)
+            .into_iter()
+            .filter(|(_, words)| words.is_none())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Restores every damaged slice from the intact ones and `recovery`,
+    /// resizing the contents to their original lengths, and checks the result
+    /// against the MD5 of every file.
+    pub fn repair(
+        &self,
+        contents: &mut [Vec<u8>],
+        recovery: &[RecoverySlice],
+    ) -> Result<(), Error> {
+        let size = self.main.slice_size as usize;
+        let inputs = self.input_slices(contents);
+        let constants = input_constants(inputs.len());
+
+        let damaged: Vec<usize> = inputs
+            .iter()
+            .filter(|(_, words)| words.is_none())
+            .map(|&(i, _)| i)
+            .collect();
+        if damaged.is_empty() {
+            self.resize_contents(contents);
+            return self.check_hashes(contents);
+        }
+
+        let mut recovery: Vec<&RecoverySlice> =
+            recovery.iter().filter(|r| r.data.len() == size).collect();
+        recovery.sort_by_key(|r| r.exponent);
+        recovery.dedup_by_key(|r| r.exponent);
+        if recovery.len() < damaged.len() {
+            return Err(Error::NotEnoughRecovery);
+        }
+        recovery.truncate(damaged.len());
+
+        // R_e - sum over intact slices of c_i^e D_i = sum over damaged of c_j^e D_j
+        let mut rhs: Vec<Vec<G>> = recovery.iter().map(|r| to_words(&r.data, size)).collect();
+        for ((_, words), c) in inputs.iter().zip(&constants) {
+            if let Some(words) = words {
+                for (r, slice) in rhs.iter_mut().zip(&recovery) {
+                    G::mul_add_slice(r, words, c.pow(slice.exponent as usize));
+                }
+            }
+        }
+
+        let matrix = recovery
+            .iter()
+            .map(|r| {
+                damaged
+                    .iter()
+                    .map(|&j| constants[j].pow(r.exponent as usize))
+                    .collect()
+            })
+            .collect();
+        let inverse = invert(matrix).ok_or(Error::Unrecoverable)?;
+
+        self.resize_contents(contents);
+        for (row, &j) in inverse.iter().zip(&damaged) {
+            let mut words = vec![G::ZERO; size / 2];
+            for (&coeff, r) in row.iter().zip(&rhs) {
+                G::mul_add_slice(&mut words, r, coeff);
+            }
+
+            let k = (0..self.files.len())
+                .filter(|&k| self.first_slice[k] <= j)
+                .max_by_key(|&k| self.first_slice[k])
+                .unwrap();
+            let start = (j - self.first_slice[k]) * size;
+            let end = (start + size).min(contents[k].len());
+            contents[k][start..end].copy_from_slice(&to_bytes(&words)[..end - start]);
+        }
+        self.check_hashes(contents)
+    }
+
+    fn resize_contents(&self, contents: &mut [Vec<u8>]) {
+        for (data, file) in contents.iter_mut().zip(&self.files) {
+            data.resize(file.length as usize, 0);
+        }
+    }
+
+    /// Compares every file with the MD5 of its description packet.
+    fn check_hashes(&self, contents: &[Vec<u8>]) -> Result<(), Error> {
+        let intact = contents
+            .iter()
+            .zip(&self.files)
+            .all(|(data, file)| Md5::digest(data) == file.hash);
+        intact.then_some(()).ok_or(Error::Unrecoverable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{input_constants, packets, Error, Packet, RecoverySet, G};
+    use crate::checksum::Md5;
+
+    #[test]
+    fn constants() {
+        assert_eq!(G::GENERATOR, G::new(2));
+        let c: Vec<u16> = input_constants(5).iter().map(|c| c.0).collect();
+        // 2^1, 2^2, 2^4, 2^7, 2^8
+        assert_eq!(c, [2, 4, 16, 128, 256]);
+    }
+
+    #[test]
+    fn by_hand() {
+        // one slice with the words 0x8001 and 0x6261 and the constant 2, so
+        // the recovery slices are the words times 1, 2 and 4 modulo 0x1100B
+        let data = [0x01, 0x80, b'a', b'b'];
+        let set = RecoverySet::new(4, &[("f", &data)]);
+        let recovery = set.recovery_slices(&[data], 0..3);
+        let words: Vec<(u32, &[u8])> = recovery.iter().map(|r| (r.exponent, &r.data[..])).collect();
+        assert_eq!(
+            words,
+            [
+                (0, &[0x01, 0x80, 0x61, 0x62][..]),
+                (1, &[0x09, 0x10, 0xC2, 0xC4][..]),
+                (2, &[0x12, 0x20, 0x8F, 0x99][..]),
+            ]
+        );
+
+        // magic, length, MD5 of the rest, set ID, type, exponent, data
+        let mut bytes = Vec::new();
+        Packet::RecoverySlice(recovery[1].clone()).write(&set.id(), &mut bytes);
+        assert_eq!(bytes.len(), 72);
+        assert_eq!(&bytes[..8], b"PAR2\0PKT");
+        assert_eq!(bytes[8..16], 72u64.to_le_bytes());
+        assert_eq!(bytes[16..32], Md5::digest(&bytes[32..]));
+        assert_eq!(bytes[32..48], set.id());
+        assert_eq!(&bytes[48..64], b"PAR 2.0\0RecvSlic");
+        assert_eq!(bytes[64..], [1, 0, 0, 0, 0x09, 0x10, 0xC2, 0xC4]);
+    }
+
+    fn contents() -> Vec<Vec<u8>> {
+        (0..3)
+            .map(|k| {
+                (0..1000 + k * 333)
+                    .map(|i| (i * 7 + k * 13) as u8)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn roundtrip_packets() {
+        let data = contents();
+        let files: Vec<(&str, &[u8])> = ["x", "dir/y", "z.bin"]
+            .into_iter()
+            .zip(data.iter().map(|d| &d[..]))
+            .collect();
+        let set = RecoverySet::new(128, &files);
+
+        let mut bytes = Vec::new();
+        set.write_packets(&mut bytes);
+        Packet::Creator("gf".into()).write(&set.id(), &mut bytes);
+        // garbage and a damaged copy of the first packet in between
+        let damaged = bytes[..100].to_vec();
+        bytes.splice(0..0, [1, 2, 3].into_iter().chain(damaged));
+
+        let parsed: Vec<_> = packets(&bytes).collect();
+        assert_eq!(parsed.len(), 2 * files.len() + 2);
+        assert!(parsed.iter().all(|(id, _)| *id == set.id()));
+        assert_eq!(parsed.last().unwrap().1, Packet::Creator("gf".into()));
+
+        let read = RecoverySet::from_packets(parsed).unwrap();
+        assert_eq!(read.id(), set.id());
+        assert_eq!(read.slice_count(), 8 + 11 + 14);
+
+        let mut bytes = Vec::new();
+        Packet::Creator("gf".into()).write(&set.id(), &mut bytes);
+        bytes[40] ^= 1;
+        assert_eq!(Packet::read(&bytes).unwrap_err(), Error::Corrupt);
+    }
+
+    #[test]
+    fn repair() {
+        let original = contents();
+        let files: Vec<(&str, &[u8])> = ["a", "b", "c"]
+            .into_iter()
+            .zip(original.iter().map(|d| &d[..]))
+            .collect();
+        let set = RecoverySet::new(64, &files);
+        let recovery = set.recovery_slices(&original, 0..20);
+
+        let mut data = original.clone();
+        data[0][5] ^= 0xFF;
+        data[0][900] ^= 0xFF;
+        data[1].truncate(700);
+        data[2].clear();
+        let damaged = set.damaged_slices(&data);
+        assert_eq!(damaged.len(), 2 + 11 + 27);
+
+        assert_eq!(
+            set.repair(&mut data.clone(), &recovery),
+            Err(Error::NotEnoughRecovery)
+        );
+
+        data[2] = original[2].clone();
+        assert_eq!(set.damaged_slices(&data).len(), 13);
+        set.repair(&mut data, &recovery[5..]).unwrap();
+        assert_eq!(data, original);
+
+        // whole slices appended to an otherwise intact file are cut off
+        let set = RecoverySet::new(8, &[("a", &original[0])]);
+        let mut data = [[&original[0][..], &[7; 16]].concat()];
+        assert!(set.damaged_slices(&data).is_empty());
+        set.repair(&mut data, &[]).unwrap();
+        assert_eq!(data[0], original[0]);
+    }
+}
@@ -0,0 +1,746 @@
+use alloc::{vec, vec::Vec};
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::Field;
+
+/// # Polynomial over a Finite Field
+///
+/// Coefficients are stored lowest degree first and never carry trailing zeros,
+/// so the zero polynomial has no coefficients at all.
+///
+/// ```
+/// use gf::{Poly, GF};
+///
+/// // x^2 + 3
+/// let p = Poly::new([GF(3u8), GF(0), GF(1)]);
+///
+/// assert_eq!(p.degree(), Some(2));
+/// assert_eq!(p.eval(GF(2)), GF(2) * GF(2) + GF(3));
+/// ```
+#[derive(PartialEq, Eq, Clone, Default, Hash, Debug)]
+pub struct Poly<F> {
+    coeffs: Vec<F>,
+}
+
+impl<F: Field> Poly<F> {
+    pub fn new(coeffs: impl Into<Vec<F>>) -> Self {
+        let mut p = Self {
+            coeffs: coeffs.into(),
+        };
+        p.normalize();
+        p
+    }
+
+    #[inline]
+    pub fn zero() -> Self {
+        Self { coeffs: Vec::new() }
+    }
+
+    #[inline]
+    pub fn one() -> Self {
+        Self::constant(F::ONE)
+    }
+
+    #[inline]
+    pub fn constant(c: F) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// `c * x^deg`
+    pub fn monomial(c: F, deg: usize) -> Self {
+        let mut coeffs = vec![F::ZERO; deg + 1];
+        coeffs[deg] = c;
+        Self::new(coeffs)
+    }
+
+    /// The monic polynomial `(x - r_0)(x - r_1)...(x - r_n)`.
+    pub fn from_roots(roots: &[F]) -> Self {
+        let mut coeffs = Vec::with_capacity(roots.len() + 1);
+        coeffs.push(F::ONE);
+        for &r in roots {
+            coeffs.push(F::ZERO);
+            for i in (1..coeffs.len()).rev() {
+                coeffs[i] = coeffs[i - 1] - r * coeffs[i];
+            }
+            coeffs[0] = -(r * coeffs[0]);
+        }
+        Self { coeffs }
+    }
+
+    #[inline]
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    #[inline]
+    pub fn into_coeffs(self) -> Vec<F> {
+        self.coeffs
+    }
+
+    /// Coefficient of `x^i`, zero past the degree.
+    #[inline]
+    pub fn coeff(&self, i: usize) -> F {
+        self.coeffs.get(i).copied().unwrap_or(F::ZERO)
+    }
+
+    /// `None` for the zero polynomial.
+    #[inline]
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// Leading coefficient, zero for the zero polynomial.
+    #[inline]
+    pub fn lead(&self) -> F {
+        self.coeffs.last().copied().unwrap_or(F::ZERO)
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's method.
+    pub fn eval(&self, x: F) -> F {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(F::ZERO, |acc, &c| acc * x + c)
+    }
+
+    pub fn scale(&self, c: F) -> Self {
+        Self::new(self.coeffs.iter().map(|&a| a * c).collect::<Vec<_>>())
+    }
+
+    /// Scales the polynomial to a leading coefficient of one.
+    ///
+    /// # Panics
+    ///
+    /// Panics on the zero polynomial.
+    pub fn monic(&self) -> Self {
+        assert!(!self.is_zero(), "attempt to divide by zero");
+        self.scale(self.lead().inv())
+    }
+
+    /// Formal derivative.
+    pub fn derivative(&self) -> Self {
+        Self::new(
+            self.coeffs
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, &c)| mul_int(c, i))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Euclidean division, returns `(quotient, remainder)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let Some(rhs_deg) = rhs.degree() else {
+            panic!("attempt to divide by zero")
+        };
+
+        if self.coeffs.len() <= rhs_deg {
+            return (Self::zero(), self.clone());
+        }
+
+        let quot_len = self.coeffs.len() - rhs_deg;
+        if quot_len >= FAST_DIV_THRESHOLD && rhs_deg >= FAST_DIV_THRESHOLD {
+            return self.div_rem_newton(rhs);
+        }
+
+        let lead_inv = rhs.lead().inv();
+        let mut rem = self.coeffs.clone();
+        let mut quot = vec![F::ZERO; rem.len() - rhs_deg];
+
+        for i in (0..quot.len()).rev() {
+            let q = rem[i + rhs_deg] * lead_inv;
+            quot[i] = q;
+            if q.is_zero() {
+                continue;
+            }
+            for (r, &d) in rem[i..=i + rhs_deg].iter_mut().zip(&rhs.coeffs) {
+                *r -= q * d;
+            }
+        }
+
+        rem.truncate(rhs_deg);
+        (Self::new(quot), Self::new(rem))
+    }
+
+    /// The monic greatest common divisor, zero if both are zero.
+    pub fn gcd(&self, rhs: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), rhs.clone());
+        while !b.is_zero() {
+            let r = a.div_rem(&b).1;
+            a = b;
+            b = r;
+        }
+        if a.is_zero() {
+            a
+        } else {
+            a.monic()
+        }
+    }
+
+    /// The extended Euclidean algorithm on `self` and `rhs`, stopped at the
+    /// first remainder of degree below `bound`. Returns that remainder `r`
+    /// and its cofactor `t` with `r = s * self + t * rhs` for some `s`.
+    ///
+    /// This is the key equation solver of many decoders: the result is the
+    /// solution of `r = t * rhs mod self` with `deg r < bound` and the lowest
+    /// possible degree of `t`.
+    pub fn partial_gcd(&self, rhs: &Self, bound: usize) -> (Self, Self) {
+        let below = |p: &Self| p.degree().is_none_or(|d| d < bound);
+        let (mut r0, mut t0) = (self.clone(), Self::zero());
+        let (mut r1, mut t1) = (rhs.clone(), Self::one());
+        if below(&r0) {
+            return (r0, t0);
+        }
+        while !below(&r1) {
+            let (q, r) = r0.div_rem(&r1);
+            let t = &t0 - &(&q * &t1);
+            (r0, t0) = (r1, t1);
+            (r1, t1) = (r, t);
+        }
+        (r1, t1)
+    }
+
+    /// The inverse of `self` modulo `modulus`, `None` if they share a factor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn inv_mod(&self, modulus: &Self) -> Option<Self> {
+        let a = self.div_rem(modulus).1;
+        let (r, t) = modulus.partial_gcd(&a, 1);
+        if r.is_zero() || modulus.degree() == Some(0) {
+            return None;
+        }
+        Some(t.scale(r.lead().inv()))
+    }
+
+    /// `self^exp mod modulus` by square and multiply.
+    fn pow_mod(&self, mut exp: u128, modulus: &Self) -> Self {
+        let mut base = self.div_rem(modulus).1;
+        let mut acc = Self::one().div_rem(modulus).1;
+        while exp != 0 {
+            if exp & 1 == 1 {
+                acc = (&acc * &base).div_rem(modulus).1;
+            }
+            base = (&base * &base).div_rem(modulus).1;
+            exp >>= 1;
+        }
+        acc
+    }
+
+    /// The distinct roots in `F`, ordered by [`Field::to_index`].
+    ///
+    /// `gcd(p, x^q - x)` collects the linear factors, which are then split
+    /// apart by gcds with `(x + d)^((q - 1) / 2) - 1` in odd characteristic
+    /// or with the trace of `d x` in characteristic two.
+    ///
+    /// # Panics
+    ///
+    /// Panics on the zero polynomial.
+    pub fn roots(&self) -> Vec<F> {
+        assert!(!self.is_zero(), "zero polynomial");
+        if self.degree() == Some(0) {
+            return Vec::new();
+        }
+
+        let x = Self::monomial(F::ONE, 1);
+        // x^q, with q itself possibly out of range
+        let power = (&x.pow_mod(F::ORDER, self) * &x).div_rem(self).1;
+        let split = &power - &x;
+        let mut roots = Vec::new();
+        self.gcd(&split).split_linear(&mut roots);
+        roots.sort_unstable_by_key(|r| r.to_index());
+        roots
+    }
+
+    /// Appends the roots of a monic product of distinct linear factors.
+    fn split_linear(&self, roots: &mut Vec<F>) {
+        match self.degree() {
+            None | Some(0) => return,
+            Some(1) => {
+                roots.push(-self.coeff(0));
+                return;
+            }
+            Some(_) => {}
+        }
+
+        let x = Self::monomial(F::ONE, 1);
+        let binary = !F::ORDER.is_multiple_of(2);
+        // in characteristic two only the basis elements are needed, and the
+        // trace form separates any two roots for one of them
+        let basis = (0..if binary { F::ORDER.count_ones() } else { 0 }).map(|i| 1 << i);
+        let shifts = 1..=if binary { 0 } else { F::ORDER };
+        for d in basis.chain(shifts) {
+            let splitter = if binary {
+                let dx = x.scale(F::from_index(d));
+                let mut term = dx.div_rem(self).1;
+                let mut trace = term.clone();
+                for _ in 1..F::ORDER.count_ones() {
+                    term = (&term * &term).div_rem(self).1;
+                    trace = &trace + &term;
+                }
+                trace
+            } else {
+                let shifted = &x + &Self::constant(F::from_index(d));
+                &shifted.pow_mod(F::ORDER / 2, self) - &Self::one()
+            };
+
+            let factor = self.gcd(&splitter);
+            if factor
+                .degree()
+                .is_some_and(|f| f > 0 && Some(f) < self.degree())
+            {
+                let rest = self.div_rem(&factor).0;
+                factor.split_linear(roots);
+                rest.monic().split_linear(roots);
+                return;
+            }
+        }
+        unreachable!("no splitting element");
+    }
+
+    /// Division through the reversed polynomials, `rev(q) = rev(a) / rev(b) mod x^k`,
+    /// which turns it into a few fast multiplications.
+    fn div_rem_newton(&self, rhs: &Self) -> (Self, Self) {
+        let k = self.coeffs.len() - rhs.coeffs.len() + 1;
+
+        let a_rev: Vec<F> = self.coeffs.iter().rev().take(k).copied().collect();
+        let b_rev: Vec<F> = rhs.coeffs.iter().rev().take(k).copied().collect();
+
+        let mut quot = mul_slices(&a_rev, &inv_series(&b_rev, k));
+        quot.resize(k, F::ZERO);
+        quot.reverse();
+        let quot = Self::new(quot);
+
+        let rem = self - &(&quot * rhs);
+        (quot, rem)
+    }
+
+    fn normalize(&mut self) {
+        while self.coeffs.last().is_some_and(Field::is_zero) {
+            self.coeffs.pop();
+        }
+    }
+}
+
+/// `c + c + ... + c`, `n` times.
+fn mul_int<F: Field>(mut c: F, mut n: usize) -> F {
+    let mut acc = F::ZERO;
+    while n != 0 {
+        if n & 1 == 1 {
+            acc += c;
+        }
+        c += c;
+        n >>= 1;
+    }
+    acc
+}
+
+impl<F: Field> From<Vec<F>> for Poly<F> {
+    fn from(coeffs: Vec<F>) -> Self {
+        Self::new(coeffs)
+    }
+}
+
+impl<F: Field> Add<&Poly<F>> for &Poly<F> {
+    type Output = Poly<F>;
+
+    fn add(self, rhs: &Poly<F>) -> Poly<F> {
+        let (long, short) = if self.coeffs.len() >= rhs.coeffs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut coeffs = long.coeffs.clone();
+        for (a, &b) in coeffs.iter_mut().zip(&short.coeffs) {
+            *a += b;
+        }
+        Poly::new(coeffs)
+    }
+}
+
+impl<F: Field> Sub<&Poly<F>> for &Poly<F> {
+    type Output = Poly<F>;
+
+    fn sub(self, rhs: &Poly<F>) -> Poly<F> {
+        self + &-rhs
+    }
+}
+
+impl<F: Field> Mul<&Poly<F>> for &Poly<F> {
+    type Output = Poly<F>;
+
+    fn mul(self, rhs: &Poly<F>) -> Poly<F> {
+        if self.is_zero() || rhs.is_zero() {
+            return Poly::zero();
+        }
+        Poly::new(mul_slices(&self.coeffs, &rhs.coeffs))
+    }
+}
+
+/// Power series inverse of `f` modulo `x^k` by Newton iteration,
+/// `g' = g + g (1 - f g)`, `f[0]` must be nonzero.
+fn inv_series<F: Field>(f: &[F], k: usize) -> Vec<F> {
+    let mut g = vec![f[0].inv()];
+    while g.len() < k {
+        let len = (2 * g.len()).min(k);
+
+        let mut err = mul_slices(&f[..len.min(f.len())], &g);
+        err.resize(len, F::ZERO);
+        for e in err.iter_mut() {
+            *e = -*e;
+        }
+        err[0] += F::ONE;
+
+        let mut step = mul_slices(&g, &err);
+        step.resize(len, F::ZERO);
+        for (s, &x) in step.iter_mut().zip(&g) {
+            *s += x;
+        }
+        g = step;
+    }
+    g
+}
+
+/// From this quotient and divisor length on division uses Newton iteration.
+const FAST_DIV_THRESHOLD: usize = 64;
+/// Below this operand length multiplication is schoolbook.
+const KARATSUBA_THRESHOLD: usize = 32;
+/// From this product length on [`Field::transform_mul`] is tried first.
+const TRANSFORM_THRESHOLD: usize = 256;
+
+/// Product of two nonempty coefficient slices.
+fn mul_slices<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if short.len() < KARATSUBA_THRESHOLD {
+        return schoolbook(long, short);
+    }
+
+    if long.len() + short.len() > TRANSFORM_THRESHOLD {
+        if let Some(product) = F::transform_mul(long, short) {
+            return product;
+        }
+    }
+
+    if long.len() == short.len() {
+        return karatsuba(long, short);
+    }
+
+    // split the longer operand into pieces the size of the shorter one
+    let mut out = vec![F::ZERO; long.len() + short.len() - 1];
+    for (i, chunk) in long.chunks(short.len()).enumerate() {
+        let offset = i * short.len();
+        for (o, p) in out[offset..].iter_mut().zip(mul_slices(chunk, short)) {
+            *o += p;
+        }
+    }
+    out
+}
+
+fn schoolbook<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    let mut out = vec![F::ZERO; a.len() + b.len() - 1];
+    for (i, &c) in b.iter().enumerate() {
+        F::mul_add_slice(&mut out[i..i + a.len()], a, c);
+    }
+    out
+}
+
+/// `a * b` for operands of equal length as `z0 + z1 x^m + z2 x^2m`, with the
+/// middle term from a single product `(a0 + a1)(b0 + b1) - z0 - z2`.
+fn karatsuba<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    let m = a.len() / 2;
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+
+    let z0 = mul_slices(a0, b0);
+    let z2 = mul_slices(a1, b1);
+
+    let mut a_sum = a1.to_vec();
+    let mut b_sum = b1.to_vec();
+    for (s, &x) in a_sum.iter_mut().zip(a0) {
+        *s += x;
+    }
+    for (s, &x) in b_sum.iter_mut().zip(b0) {
+        *s += x;
+    }
+    let mut z1 = mul_slices(&a_sum, &b_sum);
+    for (z, &x) in z1.iter_mut().zip(&z0) {
+        *z -= x;
+    }
+    for (z, &x) in z1.iter_mut().zip(&z2) {
+        *z -= x;
+    }
+
+    let mut out = vec![F::ZERO; a.len() + b.len() - 1];
+    for (o, &x) in out.iter_mut().zip(&z0) {
+        *o += x;
+    }
+    for (o, &x) in out[m..].iter_mut().zip(&z1) {
+        *o += x;
+    }
+    for (o, &x) in out[2 * m..].iter_mut().zip(&z2) {
+        *o += x;
+    }
+    out
+}
+
+impl<F: Field> Neg for &Poly<F> {
+    type Output = Poly<F>;
+
+    fn neg(self) -> Poly<F> {
+        Poly {
+            coeffs: self.coeffs.iter().map(|&c| -c).collect(),
+        }
+    }
+}
+
+impl<F: Field> Neg for Poly<F> {
+    type Output = Poly<F>;
+
+    #[inline]
+    fn neg(self) -> Poly<F> {
+        -&self
+    }
+}
+
+macro_rules! poly_ops {
+    ($(impl $trait:ident, $assign_trait:ident {
+        fn $call:ident, $assign_call:ident
+    })*) => {$(
+        impl<F: Field> $trait<Poly<F>> for Poly<F> {
+            type Output = Poly<F>;
+
+            #[inline]
+            fn $call(self, rhs: Poly<F>) -> Poly<F> {
+                (&self).$call(&rhs)
+            }
+        }
+
+        impl<F: Field> $trait<&Poly<F>> for Poly<F> {
+            type Output = Poly<F>;
+
+            #[inline]
+            fn $call(self, rhs: &Poly<F>) -> Poly<F> {
+                (&self).$call(rhs)
+            }
+        }
+
+        impl<F: Field> $trait<Poly<F>> for &Poly<F> {
+            type Output = Poly<F>;
+
+            #[inline]
+            fn $call(self, rhs: Poly<F>) -> Poly<F> {
+                self.$call(&rhs)
+            }
+        }
+
+        impl<F: Field, U> $assign_trait<U> for Poly<F>
+        where
+            for<'a> &'a Poly<F>: $trait<U, Output = Poly<F>>,
+        {
+            #[inline]
+            fn $assign_call(&mut self, rhs: U) {
+                *self = (&*self).$call(rhs);
+            }
+        }
+    )*}
+}
+
+poly_ops! {
+    impl Add, AddAssign {
+        fn add, add_assign
+    }
+
+    impl Sub, SubAssign {
+        fn sub, sub_assign
+    }
+
+    impl Mul, MulAssign {
+        fn mul, mul_assign
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{schoolbook, Poly};
+    use crate::{Field, GFp, GF, GF2_128};
+
+    fn p(coeffs: &[u8]) -> Poly<GF<u8>> {
+        Poly::new(GF::from_slice(coeffs).to_vec())
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(p(&[1, 2, 0, 0]), p(&[1, 2]));
+        assert_eq!(p(&[0, 0]).degree(), None);
+    }
+
+    #[test]
+    fn add_mul() {
+        assert_eq!(p(&[1, 1]) * p(&[1, 1]), p(&[1, 0, 1]));
+        assert_eq!(p(&[1, 1, 5]) + p(&[1, 1, 5]), Poly::zero());
+    }
+
+    #[test]
+    fn from_roots() {
+        let roots = [GF(3u8), GF(17), GF(200)];
+        let f = Poly::from_roots(&roots);
+
+        assert_eq!(f.degree(), Some(3));
+        for r in roots {
+            assert_eq!(f.eval(r), GF(0));
+        }
+        assert_eq!(f, p(&[3, 1]) * p(&[17, 1]) * p(&[200, 1]));
+    }
+
+    #[test]
+    fn div_rem() {
+        let a = p(&[7, 0, 3, 9, 1, 44]);
+        let b = p(&[2, 5, 1]);
+        let (q, r) = a.div_rem(&b);
+
+        assert!(r.degree() < b.degree());
+        assert_eq!(q * b + r, a);
+    }
+
+    #[test]
+    fn fast_mul() {
+        for (n, m) in [(40, 40), (33, 100), (300, 301), (7, 1000)] {
+            let a = Poly::new((0..n).map(|i| GF((i * 31 + 7) as u8)).collect::<Vec<_>>());
+            let b = Poly::new((0..m).map(|i| GF((i * 17 + 3) as u8)).collect::<Vec<_>>());
+
+            assert_eq!(&a * &b, Poly::new(schoolbook(a.coeffs(), b.coeffs())));
+        }
+    }
+
+    #[test]
+    fn ntt_mul() {
+        type F = GFp<998244353>;
+
+        let a = Poly::new((0..700u64).map(|i| F::new(i * i + 1)).collect::<Vec<_>>());
+        let b = Poly::new((0..500u64).map(|i| F::new(3 * i + 2)).collect::<Vec<_>>());
+
+        assert!(F::transform_mul(a.coeffs(), b.coeffs()).is_some());
+
+        assert_eq!(&a * &b, Poly::new(schoolbook(a.coeffs(), b.coeffs())));
+    }
+
+    #[test]
+    fn div_rem_newton() {
+        let a = Poly::new((0..900).map(|i| GF((i * 13 + 1) as u8)).collect::<Vec<_>>());
+        let b = Poly::new((0..300).map(|i| GF((i * 7 + 5) as u8)).collect::<Vec<_>>());
+        let (q, r) = a.div_rem(&b);
+
+        assert!(r.degree() < b.degree());
+        assert_eq!(q * b + r, a);
+    }
+
+    #[test]
+    fn derivative() {
+        // d/dx (x^3 + x^2 + x) = 3x^2 + 2x + 1 = x^2 + 1 in characteristic 2
+        assert_eq!(p(&[0, 1, 1, 1]).derivative(), p(&[1, 0, 1]));
+    }
+
+    #[test]
+    fn gcd() {
+        let a = Poly::from_roots(&[GF(1u8), GF(2), GF(3)]);
+        let b = Poly::from_roots(&[GF(2u8), GF(3), GF(4), GF(5)]);
+        assert_eq!(a.gcd(&b), Poly::from_roots(&[GF(2u8), GF(3)]));
+        assert_eq!(a.scale(GF(7)).gcd(&Poly::zero()), a);
+    }
+
+    #[test]
+    fn inv_mod() {
+        let m = p(&[1, 2, 3, 4, 5, 6, 7]);
+        let a = p(&[9, 8, 7]);
+        let inv = a.inv_mod(&m).unwrap();
+        assert_eq!((&a * &inv).div_rem(&m).1, Poly::one());
+
+        let m = Poly::from_roots(&[GF(1u8), GF(2)]);
+        assert_eq!(Poly::from_roots(&[GF(2u8)]).inv_mod(&m), None);
+    }
+
+    #[test]
+    fn partial_gcd() {
+        type F = GFp<101>;
+        let a = Poly::monomial(F::ONE, 10);
+        let b = Poly::new((1..=9u64).map(F::new).collect::<Vec<_>>());
+        let (r, t) = a.partial_gcd(&b, 5);
+        assert!(r.degree().unwrap() < 5);
+        // r = t * b mod a, with t as small as the bound allows
+        assert_eq!((&t * &b).div_rem(&a).1, r);
+        assert!(t.degree().unwrap() <= 10 - 5);
+    }
+
+    #[test]
+    fn roots() {
+        // a repeated root and a quadratic factor without roots
+        let f = Poly::from_roots(&[GF(3u8), GF(17), GF(200), GF(17)]);
+        let irreducible = p(&[32, 1, 1]);
+        assert!((0..=255).all(|x| irreducible.eval(GF(x)) != GF(0)));
+        assert_eq!((&f * &irreducible).roots(), [GF(3), GF(17), GF(200)]);
+
+        type F = GFp<998244353>;
+        let roots = [F::new(0), F::new(5), F::new(123456), F::new(998244352)];
+        let f = Poly::from_roots(&roots) * Poly::new([F::new(3), F::ZERO, F::ONE]);
+        assert_eq!(f.roots(), roots);
+
+        type F2 = GFp<2>;
+        assert_eq!(
+            Poly::new([F2::ZERO, F2::ONE, F2::ONE]).roots(),
+            [F2::ZERO, F2::ONE]
+        );
+        assert_eq!(p(&[5]).roots(), []);
+    }
+
+    #[test]
+    fn roots_split_by_last_basis_element() {
+        // d with Tr(x^i d) = 1 for i = 127 only, so no lower basis element
+        // separates a from a + d
+        let trace = |y: GF2_128| {
+            let mut term = y;
+            let mut sum = y;
+            for _ in 1..128 {
+                term = term * term;
+                sum += term;
+            }
+            sum.to_index()
+        };
+        let basis = |i: u32| GF2_128::from_index(1 << i);
+        let mut rows: Vec<(u128, bool)> = (0..128)
+            .map(|i| {
+                let row = (0..128).fold(0, |row, j| row | trace(basis(i) * basis(j)) << j);
+                (row, i == 127)
+            })
+            .collect();
+        for col in 0..128 {
+            let pivot = (col..128).find(|&r| rows[r].0 >> col & 1 == 1).unwrap();
+            rows.swap(col, pivot);
+            for r in 0..128 {
+                if r != col && rows[r].0 >> col & 1 == 1 {
+                    rows[r] = (rows[r].0 ^ rows[col].0, rows[r].1 ^ rows[col].1);
+                }
+            }
+        }
+        let d = GF2_128::from_index((0..128).fold(0, |d, i| d | (rows[i].1 as u128) << i));
+        assert!((0..127).all(|i| trace(basis(i) * d) == 0));
+
+        let a = GF2_128::from_index(0x0123_4567_89ab_cdef);
+        let mut roots = [a, a + d];
+        roots.sort_unstable_by_key(|r| r.to_index());
+        assert_eq!(Poly::from_roots(&roots).roots(), roots);
+    }
+}
@@ -0,0 +1,350 @@
+#![allow(clippy::suspicious_arithmetic_impl)]
+
+use core::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::Field;
+
+/// # Prime Field
+///
+/// Integers modulo the prime `P`. The value is always kept reduced.
+///
+/// ```
+/// use gf::GFp;
+///
+/// type F = GFp<998244353>;
+///
+/// let x = F::new(998244352);
+/// assert_eq!(x + F::new(2), F::new(1));
+/// assert_eq!(x * x, F::new(1));
+/// ```
+#[derive(PartialEq, Eq, Clone, Copy, Default, Hash, Debug)]
+#[repr(transparent)]
+pub struct GFp<const P: u64>(u64);
+
+impl<const P: u64> GFp<P> {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 % P);
+
+    #[inline]
+    pub const fn new(v: u64) -> Self {
+        Self(v % P)
+    }
+
+    #[inline]
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: usize) -> Self {
+        let mut base = self;
+        let mut exp = exp as u128;
+        let mut acc = Self::ONE;
+        while exp != 0 {
+            if exp & 1 == 1 {
+                acc *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        acc
+    }
+
+    fn pow_u64(self, exp: u64) -> Self {
+        let mut base = self;
+        let mut exp = exp;
+        let mut acc = Self::ONE;
+        while exp != 0 {
+            if exp & 1 == 1 {
+                acc *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        acc
+    }
+
+    /// Inverse by Fermat's little theorem, zero maps to zero.
+    #[inline]
+    pub fn inv(self) -> Self {
+        self.pow_u64(P - 2)
+    }
+
+    /// A primitive `2^log_n`-th root of unity, if `2^log_n` divides `P - 1`.
+    pub fn root_of_unity(log_n: u32) -> Option<Self> {
+        let two_adicity = (P - 1).trailing_zeros();
+        if log_n > two_adicity {
+            return None;
+        }
+
+        // g^((P - 1) / 2^two_adicity) generates the 2-Sylow subgroup for any
+        // quadratic non-residue g
+        let minus_one = Self(P - 1);
+        let non_residue = (2..P)
+            .map(Self)
+            .find(|g| g.pow_u64((P - 1) / 2) == minus_one)?;
+        let root = non_residue.pow_u64((P - 1) >> two_adicity);
+
+        Some(root.pow_u64(1 << (two_adicity - log_n)))
+    }
+}
+
+impl<const P: u64> From<u64> for GFp<P> {
+    #[inline]
+    fn from(v: u64) -> Self {
+        Self::new(v)
+    }
+}
+
+impl<const P: u64> From<GFp<P>> for u64 {
+    #[inline]
+    fn from(v: GFp<P>) -> Self {
+        v.0
+    }
+}
+
+impl<const P: u64> fmt::Display for GFp<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<const P: u64> Add for GFp<P> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        let (sum, overflow) = self.0.overflowing_add(rhs.0);
+        if overflow || sum >= P {
+            Self(sum.wrapping_sub(P))
+        } else {
+            Self(sum)
+        }
+    }
+}
+
+impl<const P: u64> Sub for GFp<P> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let (diff, underflow) = self.0.overflowing_sub(rhs.0);
+        if underflow {
+            Self(diff.wrapping_add(P))
+        } else {
+            Self(diff)
+        }
+    }
+}
+
+impl<const P: u64> Neg for GFp<P> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl<const P: u64> Mul for GFp<P> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self((self.0 as u128 * rhs.0 as u128 % P as u128) as u64)
+    }
+}
+
+impl<const P: u64> Div for GFp<P> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        assert!(rhs != Self::ZERO, "attempt to divide by zero");
+        self * rhs.inv()
+    }
+}
+
+macro_rules! gfp_assign_ops {
+    ($(impl $assign_trait:ident { fn $assign_call:ident => $call:ident })*) => {$(
+        impl<const P: u64> $assign_trait for GFp<P> {
+            #[inline]
+            fn $assign_call(&mut self, rhs: Self) {
+                *self = self.$call(rhs);
+            }
+        }
+    )*}
+}
+
+gfp_assign_ops! {
+    impl AddAssign { fn add_assign => add }
+    impl SubAssign { fn sub_assign => sub }
+    impl MulAssign { fn mul_assign => mul }
+    impl DivAssign { fn div_assign => div }
+}
+
+impl<const P: u64> Sum for GFp<P> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl<const P: u64> Product for GFp<P> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, Mul::mul)
+    }
+}
+
+impl<const P: u64> Field for GFp<P> {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1 % P);
+    const ORDER: u128 = P as u128 - 1;
+
+    #[inline]
+    fn inv(self) -> Self {
+        self.inv()
+    }
+
+    #[inline]
+    fn pow(self, exp: usize) -> Self {
+        self.pow(exp)
+    }
+
+    #[inline]
+    fn from_index(i: u128) -> Self {
+        Self::new(i as u64)
+    }
+
+    #[inline]
+    fn to_index(self) -> u128 {
+        self.0 as u128
+    }
+
+    #[cfg(feature = "alloc")]
+    fn transform_mul(a: &[Self], b: &[Self]) -> Option<Vec<Self>> {
+        let len = a.len() + b.len() - 1;
+        let log_n = len.next_power_of_two().trailing_zeros();
+        let root = Self::root_of_unity(log_n)?;
+
+        let mut fa = vec![Self::ZERO; 1 << log_n];
+        let mut fb = vec![Self::ZERO; 1 << log_n];
+        fa[..a.len()].copy_from_slice(a);
+        fb[..b.len()].copy_from_slice(b);
+
+        ntt(&mut fa, root);
+        ntt(&mut fb, root);
+        for (x, y) in fa.iter_mut().zip(&fb) {
+            *x *= *y;
+        }
+        ntt(&mut fa, root.inv());
+
+        let n_inv = Self::new(fa.len() as u64).inv();
+        fa.truncate(len);
+        Self::mul_slice(&mut fa, n_inv);
+        Some(fa)
+    }
+}
+
+/// In-place radix-2 number theoretic transform, `root` must be a primitive
+/// `values.len()`-th root of unity.
+#[cfg(feature = "alloc")]
+fn ntt<const P: u64>(values: &mut [GFp<P>], root: GFp<P>) {
+    let n = values.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let step = root.pow(n / len);
+        for chunk in values.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            let mut w = GFp::ONE;
+            for (u, v) in lo.iter_mut().zip(hi) {
+                let t = *v * w;
+                *v = *u - t;
+                *u += t;
+                w *= step;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(feature = "num-traits")]
+mod num_traits_impl {
+    use super::GFp;
+
+    impl<const P: u64> num_traits::Zero for GFp<P> {
+        fn zero() -> Self {
+            Self::ZERO
+        }
+        fn is_zero(&self) -> bool {
+            *self == Self::ZERO
+        }
+    }
+
+    impl<const P: u64> num_traits::One for GFp<P> {
+        fn one() -> Self {
+            Self::ONE
+        }
+    }
+
+    impl<const P: u64> num_traits::Inv for GFp<P> {
+        type Output = Self;
+
+        fn inv(self) -> Self::Output {
+            self.inv()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GFp;
+
+    type F = GFp<998244353>;
+    type Goldilocks = GFp<0xFFFF_FFFF_0000_0001>;
+
+    #[test]
+    fn add_sub() {
+        assert_eq!(F::new(998244352) + F::new(5), F::new(4));
+        assert_eq!(F::new(3) - F::new(5), -F::new(2));
+
+        let big = Goldilocks::new(u64::MAX);
+        assert_eq!(big + big - big, big);
+    }
+
+    #[test]
+    fn mul_div() {
+        assert_eq!((F::new(5) * F::new(60)) / F::new(5), F::new(60));
+        assert_eq!(
+            Goldilocks::new(12345).inv() * Goldilocks::new(12345),
+            Goldilocks::ONE
+        );
+    }
+
+    #[test]
+    fn root_of_unity() {
+        let w = F::root_of_unity(23).unwrap();
+        assert_eq!(w.pow(1 << 23), F::ONE);
+        assert_ne!(w.pow(1 << 22), F::ONE);
+        assert_eq!(F::root_of_unity(24), None);
+    }
+}
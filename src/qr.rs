@@ -0,0 +1,339 @@
+//! # QR Code Error Correction
+//!
+//! Error correction codewords and block interleaving for every QR code version
+//! and error correction level (ISO/IEC 18004). The code is Reed–Solomon over
+//! [`GF<u8>`] with the generator roots `2^0, 2^1, ...`.
+//!
+//! The data codewords are split into blocks, every block gets its own error
+//! correction codewords, and the symbol holds the data codewords of all blocks
+//! interleaved column by column, followed by the error correction codewords,
+//! interleaved the same way.
+//!
+//! ```
+//! use gf::qr::{Blocks, EcLevel};
+//!
+//! let blocks = Blocks::new(5, EcLevel::Q);
+//! assert_eq!(blocks.data_codewords(), 62);
+//!
+//! let data: Vec<u8> = (0..62).collect();
+//! let mut symbol = blocks.encode(&data);
+//! assert_eq!(symbol.len(), 134);
+//!
+//! symbol[0] ^= 0xFF;
+//! symbol[100] ^= 0x01;
+//! assert_eq!(blocks.decode(&mut symbol), Ok(data));
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{reed_solomon::Error, reed_solomon::ReedSolomon, GF};
+
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum EcLevel {
+    /// Recovers about 7% of the codewords.
+    L,
+    /// Recovers about 15% of the codewords.
+    M,
+    /// Recovers about 25% of the codewords.
+    Q,
+    /// Recovers about 30% of the codewords.
+    H,
+}
+
+/// Error correction codewords per block, by level and version.
+const EC_CODEWORDS_PER_BLOCK: [[u8; 40]; 4] = [
+    [
+        7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30,
+        30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30, 30,
+        30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24, 30,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// Number of error correction blocks, by level and version.
+const BLOCK_COUNT: [[u8; 40]; 4] = [
+    [
+        1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23,
+        25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32, 35,
+        37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// Total codewords of a version: the modules left after the function
+/// patterns and the format and version information, in whole bytes.
+const fn total_codewords(version: u8) -> usize {
+    let v = version as usize;
+    let mut modules = (16 * v + 128) * v + 64;
+    if v >= 2 {
+        let alignment = v / 7 + 2;
+        modules -= (25 * alignment - 10) * alignment - 55;
+        if v >= 7 {
+            modules -= 36;
+        }
+    }
+    modules / 8
+}
+
+/// Block structure of one version and level.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub struct Blocks {
+    version: u8,
+    level: EcLevel,
+    ec_per_block: usize,
+    count: usize,
+    /// blocks with `short_len` data codewords, the others have one more
+    short_count: usize,
+    short_len: usize,
+}
+
+impl Blocks {
+    /// # Panics
+    ///
+    /// Panics if `version` is not in `1..=40`.
+    pub fn new(version: u8, level: EcLevel) -> Self {
+        assert!((1..=40).contains(&version), "version out of range");
+        let ec_per_block = EC_CODEWORDS_PER_BLOCK[level as usize][version as usize - 1] as usize;
+        let count = BLOCK_COUNT[level as usize][version as usize - 1] as usize;
+        let total = total_codewords(version);
+
+        Self {
+            version,
+            level,
+            ec_per_block,
+            count,
+            short_count: count - total % count,
+            short_len: total / count - ec_per_block,
+        }
+    }
+
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    #[inline]
+    pub fn level(&self) -> EcLevel {
+        self.level
+    }
+
+    #[inline]
+    pub fn total_codewords(&self) -> usize {
+        total_codewords(self.version)
+    }
+
+    #[inline]
+    pub fn data_codewords(&self) -> usize {
+        self.total_codewords() - self.count * self.ec_per_block
+    }
+
+    #[inline]
+    pub fn ec_codewords_per_block(&self) -> usize {
+        self.ec_per_block
+    }
+
+    #[inline]
+    pub fn block_count(&self) -> usize {
+        self.count
+    }
+
+    /// Number of data codewords in every block, in order.
+    pub fn data_lens(&self) -> impl Iterator<Item = usize> {
+        let (short_count, short_len) = (self.short_count, self.short_len);
+        (0..self.count).map(move |i| short_len + (i >= short_count) as usize)
+    }
+
+    /// Errors a block may correct. The smallest symbols set aside some error
+    /// correction codewords to detect misdecodes instead.
+    fn max_errors(&self) -> usize {
+        let reserved = match (self.version, self.level) {
+            (1, EcLevel::L) => 3,
+            (1, EcLevel::M) | (2, EcLevel::L) => 2,
+            (1, _) | (3, EcLevel::L) => 1,
+            _ => 0,
+        };
+        (self.ec_per_block - reserved) / 2
+    }
+
+    fn code(&self) -> ReedSolomon<GF<u8>> {
+        ReedSolomon::new(self.ec_per_block, GF(2), 0)
+    }
+
+    /// Adds the error correction codewords to `data` and interleaves the
+    /// blocks into the final codeword sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` does not have exactly [`data_codewords`](Self::data_codewords) bytes.
+    pub fn encode(&self, data: &[u8]) -> Vec<u8> {
+        assert_eq!(
+            data.len(),
+            self.data_codewords(),
+            "wrong number of data codewords"
+        );
+        let code = self.code();
+
+        let mut blocks = Vec::with_capacity(self.count);
+        let mut rest = data;
+        for len in self.data_lens() {
+            let (block, tail) = rest.split_at(len);
+            rest = tail;
+            let ec = code.encode(GF::from_slice(block));
+            blocks.push((block, ec));
+        }
+
+        let mut out = Vec::with_capacity(self.total_codewords());
+        for col in 0..=self.short_len {
+            out.extend(blocks.iter().filter_map(|(data, _)| data.get(col)));
+        }
+        for col in 0..self.ec_per_block {
+            out.extend(blocks.iter().map(|(_, ec)| ec[col].0));
+        }
+        out
+    }
+
+    /// De-interleaves `codewords`, corrects every block and returns the data
+    /// codewords. On success the corrections are also written back to
+    /// `codewords`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `codewords` does not have exactly
+    /// [`total_codewords`](Self::total_codewords) bytes.
+    pub fn decode(&self, codewords: &mut [u8]) -> Result<Vec<u8>, Error> {
+        assert_eq!(
+            codewords.len(),
+            self.total_codewords(),
+            "wrong number of codewords"
+        );
+        let code = self.code();
+        let max_errors = self.max_errors();
+
+        // index of every codeword of every block in the interleaved sequence
+        let mut indices: Vec<Vec<usize>> = self
+            .data_lens()
+            .map(|len| Vec::with_capacity(len + self.ec_per_block))
+            .collect();
+        let mut next = 0..codewords.len();
+        for col in 0..=self.short_len {
+            for (block, len) in indices.iter_mut().zip(self.data_lens()) {
+                if col < len {
+                    block.push(next.next().unwrap());
+                }
+            }
+        }
+        for _ in 0..self.ec_per_block {
+            for block in &mut indices {
+                block.push(next.next().unwrap());
+            }
+        }
+
+        let mut blocks = Vec::with_capacity(self.count);
+        for idx in &indices {
+            let mut block: Vec<GF<u8>> = idx.iter().map(|&i| GF(codewords[i])).collect();
+            if code.decode(&mut block, &[])? > max_errors {
+                return Err(Error::TooManyErrors);
+            }
+            blocks.push(block);
+        }
+
+        let mut data = Vec::with_capacity(self.data_codewords());
+        for ((block, idx), len) in blocks.iter().zip(&indices).zip(self.data_lens()) {
+            for (c, &i) in block.iter().zip(idx) {
+                codewords[i] = c.0;
+            }
+            data.extend(block[..len].iter().map(|c| c.0));
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{Blocks, EcLevel};
+    use crate::reed_solomon::Error;
+
+    #[test]
+    fn hello_world() {
+        // version 1-M "HELLO WORLD"
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        let symbol = Blocks::new(1, EcLevel::M).encode(&data);
+        assert_eq!(&symbol[..16], &data);
+        assert_eq!(
+            &symbol[16..],
+            [196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+    }
+
+    #[test]
+    fn capacities() {
+        let data = |v, l| Blocks::new(v, l).data_codewords();
+        assert_eq!(data(1, EcLevel::L), 19);
+        assert_eq!(data(1, EcLevel::H), 9);
+        assert_eq!(data(10, EcLevel::M), 216);
+        assert_eq!(data(40, EcLevel::L), 2956);
+        assert_eq!(data(40, EcLevel::H), 1276);
+        assert_eq!(Blocks::new(40, EcLevel::H).total_codewords(), 3706);
+
+        let b = Blocks::new(5, EcLevel::Q);
+        assert_eq!(b.data_lens().collect::<Vec<_>>(), [15, 15, 16, 16]);
+    }
+
+    #[test]
+    fn every_version() {
+        for version in 1..=40 {
+            for level in [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H] {
+                let blocks = Blocks::new(version, level);
+                let data: Vec<u8> = (0..blocks.data_codewords())
+                    .map(|i| (i * 7) as u8)
+                    .collect();
+                let mut symbol = blocks.encode(&data);
+                assert_eq!(symbol.len(), blocks.total_codewords());
+
+                // damage as many codewords in a row as every block can take
+                let burst = blocks.block_count() * blocks.max_errors();
+                for c in &mut symbol[..burst] {
+                    *c ^= 0x5A;
+                }
+                assert_eq!(blocks.decode(&mut symbol), Ok(data.clone()));
+                assert_eq!(symbol, blocks.encode(&data));
+            }
+        }
+    }
+
+    #[test]
+    fn misdecode_protection() {
+        // 1-L has 7 error correction codewords, but only corrects 2 errors
+        let blocks = Blocks::new(1, EcLevel::L);
+        let data = [0; 19];
+        let mut symbol = blocks.encode(&data);
+        symbol[0] = 1;
+        symbol[1] = 1;
+        symbol[2] = 1;
+        assert_eq!(blocks.decode(&mut symbol), Err(Error::TooManyErrors));
+    }
+}
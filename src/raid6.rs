@@ -0,0 +1,279 @@
+//! # RAID-6
+//!
+//! P+Q syndromes over `GF(2^8)` with the polynomial `0x11D` and generator `2`,
+//! byte for byte the layout of the Linux md driver:
+//!
+//! ```text
+//! P = D_0 + D_1 + ... + D_(n-1)
+//! Q = g^0 D_0 + g^1 D_1 + ... + g^(n-1) D_(n-1)
+//! ```
+//!
+//! Any two lost disks can be rebuilt from the rest. Failed disks are passed in
+//! place with arbitrary contents and overwritten.
+//!
+//! ```
+//! use gf::raid6;
+//!
+//! let mut data = [*b"abcd", *b"efgh", *b"ijkl"];
+//! let (mut p, mut q) = ([0; 4], [0; 4]);
+//! raid6::gen_syndrome(&data, &mut p, &mut q);
+//!
+//! data[0] = [0; 4];
+//! data[2] = [0; 4];
+//! raid6::recover_data_data(&mut data, 0, 2, &p, &q);
+//! assert_eq!(&data[2], b"ijkl");
+//! ```
+
+use crate::{BinaryField, Field, GF};
+
+/// More data disks would repeat coefficients in `Q`.
+pub const MAX_DATA_DISKS: usize = 255;
+
+fn check_lengths<D: AsRef<[u8]>>(data: &[D], p: &[u8], q: &[u8]) {
+    assert!(data.len() <= MAX_DATA_DISKS, "too many data disks");
+    assert_eq!(p.len(), q.len(), "slice lengths differ");
+    assert!(
+        data.iter().all(|d| d.as_ref().len() == p.len()),
+        "slice lengths differ"
+    );
+}
+
+/// `g^disk`
+#[inline]
+fn coefficient(disk: usize) -> GF<u8> {
+    GF::exp(disk)
+}
+
+fn xor(dst: &mut [u8], src: &[u8]) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d ^= s;
+    }
+}
+
+fn mul_add(dst: &mut [u8], src: &[u8], c: GF<u8>) {
+    GF::mul_add_slice(GF::from_slice_mut(dst), GF::from_slice(src), c);
+}
+
+/// Computes both syndromes of the data disks.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length or there are more than
+/// [`MAX_DATA_DISKS`] data disks.
+pub fn gen_syndrome<D: AsRef<[u8]>>(data: &[D], p: &mut [u8], q: &mut [u8]) {
+    check_lengths(data, p, q);
+
+    p.fill(0);
+    q.fill(0);
+    for (i, d) in data.iter().enumerate() {
+        xor(p, d.as_ref());
+        mul_add(q, d.as_ref(), coefficient(i));
+    }
+}
+
+/// Brings the syndromes up to date after data disk `disk` changed from `old`
+/// to `new`, without touching the other disks.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length or `disk` is out of range.
+pub fn update(disk: usize, old: &[u8], new: &[u8], p: &mut [u8], q: &mut [u8]) {
+    assert!(disk < MAX_DATA_DISKS, "too many data disks");
+    check_lengths(&[old, new], p, q);
+
+    for (((p, q), &o), &n) in p.iter_mut().zip(q.iter_mut()).zip(old).zip(new) {
+        let delta = o ^ n;
+        *p ^= delta;
+        *q ^= (coefficient(disk) * GF(delta)).0;
+    }
+}
+
+/// Splits off the two disks `a < b` from the others, which keep their index.
+fn split_two<D>(
+    data: &mut [D],
+    a: usize,
+    b: usize,
+) -> (&mut D, &mut D, impl Iterator<Item = (usize, &D)>) {
+    let (head, rest) = data.split_at_mut(b);
+    let (db, tail) = rest.split_first_mut().expect("disk out of range");
+    let (head, rest) = head.split_at_mut(a);
+    let (da, mid) = rest.split_first_mut().unwrap();
+
+    let others = head
+        .iter()
+        .enumerate()
+        .chain(mid.iter().enumerate().map(move |(i, d)| (a + 1 + i, d)))
+        .chain(tail.iter().enumerate().map(move |(i, d)| (b + 1 + i, d)));
+    (da, db, others)
+}
+
+/// Rebuilds the data disks `a` and `b` from the rest and both syndromes.
+///
+/// # Panics
+///
+/// Panics if `a == b`, either is out of range, or the slices differ in length.
+pub fn recover_data_data<D>(data: &mut [D], a: usize, b: usize, p: &[u8], q: &[u8])
+where
+    D: AsRef<[u8]> + AsMut<[u8]>,
+{
+    assert_ne!(a, b, "the failed disks must differ");
+    check_lengths(data, p, q);
+    let (a, b) = (a.min(b), a.max(b));
+
+    let (da, db, others) = split_two(data, a, b);
+    let (da, db) = (da.as_mut(), db.as_mut());
+
+    // da = Da + Db and db = g^a Da + g^b Db after adding in P, Q and the rest
+    da.copy_from_slice(p);
+    db.copy_from_slice(q);
+    for (i, d) in others {
+        xor(da, d.as_ref());
+        mul_add(db, d.as_ref(), coefficient(i));
+    }
+
+    // Db = (g^a (Da + Db) + g^a Da + g^b Db) / (g^a + g^b)
+    let denom = (coefficient(a) + coefficient(b)).inv();
+    let px_mul = coefficient(a) * denom;
+    for (x, y) in da.iter_mut().zip(db.iter_mut()) {
+        let (px, qx) = (GF(*x), GF(*y));
+        let dy = px * px_mul + qx * denom;
+        *x = (px + dy).0;
+        *y = dy.0;
+    }
+}
+
+/// Rebuilds data disk `a` from the rest and `Q`, then recomputes `P`.
+///
+/// # Panics
+///
+/// Panics if `a` is out of range or the slices differ in length.
+pub fn recover_data_p<D>(data: &mut [D], a: usize, p: &mut [u8], q: &[u8])
+where
+    D: AsRef<[u8]> + AsMut<[u8]>,
+{
+    check_lengths(data, p, q);
+
+    let (head, rest) = data.split_at_mut(a);
+    let (da, tail) = rest.split_first_mut().expect("disk out of range");
+    let da = da.as_mut();
+
+    da.copy_from_slice(q);
+    for (i, d) in head.iter().enumerate() {
+        mul_add(da, d.as_ref(), coefficient(i));
+    }
+    for (i, d) in tail.iter().enumerate() {
+        mul_add(da, d.as_ref(), coefficient(a + 1 + i));
+    }
+    GF::mul_slice(GF::from_slice_mut(da), coefficient(a).inv());
+
+    p.fill(0);
+    for d in data.iter() {
+        xor(p, d.as_ref());
+    }
+}
+
+/// Rebuilds data disk `a` from the rest and `P`, then recomputes `Q`.
+///
+/// # Panics
+///
+/// Panics if `a` is out of range or the slices differ in length.
+pub fn recover_data_q<D>(data: &mut [D], a: usize, p: &[u8], q: &mut [u8])
+where
+    D: AsRef<[u8]> + AsMut<[u8]>,
+{
+    check_lengths(data, p, q);
+
+    let (head, rest) = data.split_at_mut(a);
+    let (da, tail) = rest.split_first_mut().expect("disk out of range");
+    let da = da.as_mut();
+
+    da.copy_from_slice(p);
+    for d in head.iter().chain(tail.iter()) {
+        xor(da, d.as_ref());
+    }
+
+    q.fill(0);
+    for (i, d) in data.iter().enumerate() {
+        mul_add(q, d.as_ref(), coefficient(i));
+    }
+}
+
+/// Recomputes both lost syndromes, the same as [`gen_syndrome`].
+pub fn recover_pq<D: AsRef<[u8]>>(data: &[D], p: &mut [u8], q: &mut [u8]) {
+    gen_syndrome(data, p, q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernel_layout() {
+        let data = [[0x01], [0x80], [0x01]];
+        let (mut p, mut q) = ([0], [0]);
+        gen_syndrome(&data, &mut p, &mut q);
+        assert_eq!(p, [0x80]);
+        // 1 + 2 * 0x80 + 4 = 1 + 0x1D + 4
+        assert_eq!(q, [0x18]);
+    }
+
+    fn disks() -> [[u8; 64]; 6] {
+        let mut data = [[0; 64]; 6];
+        for (i, d) in data.iter_mut().enumerate() {
+            for (j, b) in d.iter_mut().enumerate() {
+                *b = (i * 67 + j * 13 + (i * j) % 7) as u8;
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn update_matches() {
+        let mut data = disks();
+        let (mut p, mut q) = ([0; 64], [0; 64]);
+        gen_syndrome(&data, &mut p, &mut q);
+
+        let new = [0xA5; 64];
+        update(3, &data[3], &new, &mut p, &mut q);
+        data[3] = new;
+
+        let (mut p2, mut q2) = ([0; 64], [0; 64]);
+        gen_syndrome(&data, &mut p2, &mut q2);
+        assert_eq!((p, q), (p2, q2));
+    }
+
+    #[test]
+    fn all_double_failures() {
+        let original = disks();
+        let (mut p, mut q) = ([0; 64], [0; 64]);
+        gen_syndrome(&original, &mut p, &mut q);
+
+        for a in 0..original.len() {
+            for b in 0..original.len() {
+                if a != b {
+                    let mut data = original;
+                    data[a] = [0xFF; 64];
+                    data[b] = [0; 64];
+                    recover_data_data(&mut data, a, b, &p, &q);
+                    assert_eq!(data, original);
+                }
+            }
+
+            let mut data = original;
+            let mut lost_p = [0; 64];
+            data[a] = [0; 64];
+            recover_data_p(&mut data, a, &mut lost_p, &q);
+            assert_eq!((data, lost_p), (original, p));
+
+            let mut data = original;
+            let mut lost_q = [0; 64];
+            data[a] = [0; 64];
+            recover_data_q(&mut data, a, &p, &mut lost_q);
+            assert_eq!((data, lost_q), (original, q));
+        }
+
+        let (mut lost_p, mut lost_q) = ([0; 64], [0; 64]);
+        recover_pq(&original, &mut lost_p, &mut lost_q);
+        assert_eq!((lost_p, lost_q), (p, q));
+    }
+}
@@ -0,0 +1,396 @@
+//! # Reed–Muller Codes
+//!
+//! `RM(r, m)` consists of the value tables of all boolean polynomials of
+//! degree at most `r` in `m` variables. A codeword has one bit per point
+//! `x` in `0..2^m`, where variable `i` is bit `i` of `x`, and the message
+//! holds the coefficients of the monomials, ordered by degree and then by the
+//! bit mask of their variables. The minimum distance is `2^(m - r)`.
+//!
+//! Soft values are log-likelihood ratios, positive for a likely zero bit; hard
+//! decisions map to `+1.0` and `-1.0`.
+//!
+//! ```
+//! use gf::reed_muller::ReedMuller;
+//!
+//! // the code of Mariner 9, correcting 7 errors
+//! let code = ReedMuller::new(1, 5);
+//! assert_eq!((code.n(), code.k()), (32, 6));
+//!
+//! let message = [true, false, true, true, false, true];
+//! let mut received = code.encode(&message);
+//! for i in [0, 3, 8, 13, 21, 29, 31] {
+//!     received[i] = !received[i];
+//! }
+//! assert_eq!(code.decode_first_order(&received), message);
+//! assert_eq!(code.decode_majority(&received), message);
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+/// The value table of a polynomial from its coefficients or back, both are
+/// the same transform over `GF(2)`.
+fn mobius(bits: &mut [bool]) {
+    let n = bits.len();
+    let mut h = 1;
+    while h < n {
+        for x in (0..n).filter(|x| x & h != 0) {
+            bits[x] ^= bits[x ^ h];
+        }
+        h *= 2;
+    }
+}
+
+/// `f[a] = sum_x f[x] (-1)^(a . x)` in place.
+fn walsh_hadamard(data: &mut [f64]) {
+    let mut h = 1;
+    while h < data.len() {
+        for block in data.chunks_exact_mut(2 * h) {
+            let (a, b) = block.split_at_mut(h);
+            for (x, y) in a.iter_mut().zip(b) {
+                (*x, *y) = (*x + *y, *x - *y);
+            }
+        }
+        h *= 2;
+    }
+}
+
+fn to_soft(bits: &[bool]) -> Vec<f64> {
+    bits.iter().map(|&b| if b { -1.0 } else { 1.0 }).collect()
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ReedMuller {
+    r: u32,
+    m: u32,
+    /// the variables of every message monomial as a bit mask
+    monomials: Vec<usize>,
+}
+
+impl ReedMuller {
+    /// # Panics
+    ///
+    /// Panics if `r > m` or `m` is too large for a codeword to fit in memory.
+    pub fn new(r: u32, m: u32) -> Self {
+        assert!(r <= m, "r must not exceed m");
+        assert!(m < usize::BITS - 1, "m too large");
+
+        let monomials = (0..=r)
+            .flat_map(|d| (0..1usize << m).filter(move |s| s.count_ones() == d))
+            .collect();
+        Self { r, m, monomials }
+    }
+
+    /// Maximum degree of the polynomials.
+    #[inline]
+    pub fn r(&self) -> u32 {
+        self.r
+    }
+
+    /// Number of variables.
+    #[inline]
+    pub fn m(&self) -> u32 {
+        self.m
+    }
+
+    /// Codeword length, `2^m`.
+    #[inline]
+    pub fn n(&self) -> usize {
+        1 << self.m
+    }
+
+    /// Message length, the number of monomials of degree at most `r`.
+    #[inline]
+    pub fn k(&self) -> usize {
+        self.monomials.len()
+    }
+
+    /// Minimum distance, `2^(m - r)`.
+    #[inline]
+    pub fn distance(&self) -> usize {
+        1 << (self.m - self.r)
+    }
+
+    /// The bit masks of the monomials, in message order.
+    #[inline]
+    pub fn monomials(&self) -> &[usize] {
+        &self.monomials
+    }
+
+    /// The value table of the polynomial with the coefficients `message`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `message` does not have exactly [`k`](Self::k) bits.
+    pub fn encode(&self, message: &[bool]) -> Vec<bool> {
+        assert_eq!(message.len(), self.k(), "wrong message length");
+        let mut codeword = vec![false; self.n()];
+        for (&s, &b) in self.monomials.iter().zip(message) {
+            codeword[s] = b;
+        }
+        mobius(&mut codeword);
+        codeword
+    }
+
+    /// The message of a codeword, the inverse of [`encode`](Self::encode).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `codeword` does not have exactly [`n`](Self::n) bits.
+    pub fn message(&self, codeword: &[bool]) -> Vec<bool> {
+        assert_eq!(codeword.len(), self.n(), "wrong codeword length");
+        let mut coeffs = codeword.to_vec();
+        mobius(&mut coeffs);
+        self.monomials.iter().map(|&s| coeffs[s]).collect()
+    }
+
+    /// Reed's majority-logic decoder, correcting up to `2^(m - r - 1) - 1`
+    /// errors.
+    ///
+    /// The coefficients are found from the highest degree down. Summing the
+    /// received bits over any of the `2^(m - d)` cosets of the subcube of a
+    /// degree `d` monomial gives one vote for its coefficient, once the higher
+    /// degrees are removed. Ties count as zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `received` does not have exactly [`n`](Self::n) bits.
+    pub fn decode_majority(&self, received: &[bool]) -> Vec<bool> {
+        let n = self.n();
+        assert_eq!(received.len(), n, "wrong codeword length");
+
+        let mut residue = received.to_vec();
+        let mut message = vec![false; self.k()];
+        for d in (0..=self.r).rev() {
+            let start = self.monomials.partition_point(|s| s.count_ones() < d);
+            let end = self.monomials.partition_point(|s| s.count_ones() <= d);
+
+            for (j, &s) in self.monomials.iter().enumerate().take(end).skip(start) {
+                let mut ones = 0;
+                for t in (0..n).filter(|t| t & s == 0) {
+                    let mut parity = false;
+                    let mut sub = s;
+                    loop {
+                        parity ^= residue[t | sub];
+                        if sub == 0 {
+                            break;
+                        }
+                        sub = (sub - 1) & s;
+                    }
+                    ones += usize::from(parity);
+                }
+                message[j] = 2 * ones > n >> d;
+            }
+
+            for (j, &s) in self.monomials.iter().enumerate().take(end).skip(start) {
+                if message[j] {
+                    for x in (0..n).filter(|x| x & s == s) {
+                        residue[x] ^= true;
+                    }
+                }
+            }
+        }
+        message
+    }
+
+    /// Recursive decoding of hard decisions, see
+    /// [`decode_recursive_soft`](Self::decode_recursive_soft).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `received` does not have exactly [`n`](Self::n) bits.
+    pub fn decode_recursive(&self, received: &[bool]) -> Vec<bool> {
+        self.decode_recursive_soft(&to_soft(received))
+    }
+
+    /// Recursive decoding along the Plotkin construction
+    /// `RM(r, m) = {(u, u + v) : u in RM(r, m - 1), v in RM(r - 1, m - 1)}`,
+    /// splitting on the highest variable.
+    ///
+    /// `v` is decoded first from the min-sum combination of the two halves,
+    /// then `u` from the sum of the first half and the second half with the
+    /// signs of `v` removed. The recursion ends at repetition codes and at the
+    /// full space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `llr` does not have exactly [`n`](Self::n) values.
+    pub fn decode_recursive_soft(&self, llr: &[f64]) -> Vec<bool> {
+        assert_eq!(llr.len(), self.n(), "wrong codeword length");
+        self.message(&plotkin(self.r, self.m, llr))
+    }
+
+    /// Maximum likelihood decoding of first-order codes with the fast
+    /// Hadamard transform, in `O(n log n)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `r` is not one or `received` does not have exactly
+    /// [`n`](Self::n) bits.
+    pub fn decode_first_order(&self, received: &[bool]) -> Vec<bool> {
+        self.decode_first_order_soft(&to_soft(received))
+    }
+
+    /// Soft version of [`decode_first_order`](Self::decode_first_order).
+    ///
+    /// The transform correlates the received values with every linear
+    /// function `a . x` at once; the one with the largest magnitude wins, and
+    /// a negative correlation means the constant term is one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `r` is not one or `llr` does not have exactly
+    /// [`n`](Self::n) values.
+    pub fn decode_first_order_soft(&self, llr: &[f64]) -> Vec<bool> {
+        assert_eq!(self.r, 1, "only for first-order codes");
+        assert_eq!(llr.len(), self.n(), "wrong codeword length");
+
+        let mut correlation = llr.to_vec();
+        walsh_hadamard(&mut correlation);
+        let (a, best) = correlation
+            .iter()
+            .enumerate()
+            .fold((0, 0.0f64), |(a, best), (i, &c)| {
+                if c.abs() > best.abs() {
+                    (i, c)
+                } else {
+                    (a, best)
+                }
+            });
+
+        let mut message = vec![best < 0.0];
+        message.extend((0..self.m).map(|i| a >> i & 1 != 0));
+        message
+    }
+}
+
+/// The codeword of `RM(r, m)` decoded from `llr`.
+fn plotkin(r: u32, m: u32, llr: &[f64]) -> Vec<bool> {
+    if r == 0 {
+        let sum: f64 = llr.iter().sum();
+        return vec![sum < 0.0; llr.len()];
+    }
+    if r == m {
+        return llr.iter().map(|&l| l < 0.0).collect();
+    }
+
+    let (a, b) = llr.split_at(llr.len() / 2);
+    let lv: Vec<f64> = a
+        .iter()
+        .zip(b)
+        .map(|(&x, &y)| x.signum() * y.signum() * x.abs().min(y.abs()))
+        .collect();
+    let v = plotkin(r - 1, m - 1, &lv);
+
+    let lu: Vec<f64> = a
+        .iter()
+        .zip(b)
+        .zip(&v)
+        .map(|((&x, &y), &v)| if v { x - y } else { x + y })
+        .collect();
+    let u = plotkin(r, m - 1, &lu);
+
+    let mut codeword = u.clone();
+    codeword.extend(u.iter().zip(&v).map(|(&u, &v)| u ^ v));
+    codeword
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::ReedMuller;
+    use crate::{linear_code::LinearCode, GFp};
+
+    /// A message and an error pattern of the given weight from a counter.
+    fn damage(code: &ReedMuller, seed: usize, errors: usize) -> (Vec<bool>, Vec<bool>) {
+        let message: Vec<bool> = (0..code.k()).map(|i| (seed * 31 + i * 7) % 5 < 2).collect();
+        let mut received = code.encode(&message);
+        let n = code.n();
+        let mut flipped = 0;
+        let mut i = seed % n;
+        while flipped < errors {
+            if received[i] == code.encode(&message)[i] {
+                received[i] = !received[i];
+                flipped += 1;
+            }
+            i = (i + 2 * seed + 3) % n;
+        }
+        (message, received)
+    }
+
+    #[test]
+    fn parameters() {
+        let code = ReedMuller::new(2, 4);
+        assert_eq!((code.n(), code.k(), code.distance()), (16, 11, 4));
+        assert_eq!(ReedMuller::new(0, 3).k(), 1);
+        assert_eq!(ReedMuller::new(3, 3).k(), 8);
+        assert_eq!(ReedMuller::new(1, 3).monomials(), [0, 1, 2, 4]);
+
+        type F2 = GFp<2>;
+        let code = ReedMuller::new(2, 5);
+        let rows: Vec<Vec<F2>> = (0..code.k())
+            .map(|j| {
+                let mut message = alloc::vec![false; code.k()];
+                message[j] = true;
+                code.encode(&message)
+                    .iter()
+                    .map(|&b| F2::new(b as u64))
+                    .collect()
+            })
+            .collect();
+        let linear = LinearCode::from_generator(&rows);
+        assert_eq!(linear.k(), 16);
+        assert_eq!(linear.minimum_distance(), Some(8));
+    }
+
+    #[test]
+    fn roundtrip() {
+        for (r, m) in [(0, 4), (1, 4), (2, 5), (3, 6), (4, 4)] {
+            let code = ReedMuller::new(r, m);
+            let (message, _) = damage(&code, 1, 0);
+            let codeword = code.encode(&message);
+            assert_eq!(code.message(&codeword), message);
+            assert_eq!(code.decode_majority(&codeword), message);
+            assert_eq!(code.decode_recursive(&codeword), message);
+        }
+    }
+
+    #[test]
+    fn majority_and_recursive() {
+        for (r, m) in [(1, 4), (2, 5), (2, 6), (3, 7)] {
+            let code = ReedMuller::new(r, m);
+            let t = (code.distance() - 1) / 2;
+            for seed in 1..20 {
+                let (message, received) = damage(&code, seed, t);
+                assert_eq!(code.decode_majority(&received), message);
+                assert_eq!(code.decode_recursive(&received), message);
+            }
+        }
+    }
+
+    #[test]
+    fn first_order() {
+        let code = ReedMuller::new(1, 7);
+        for seed in 1..20 {
+            let (message, received) = damage(&code, seed, 31);
+            assert_eq!(code.decode_first_order(&received), message);
+        }
+    }
+
+    #[test]
+    fn soft_beats_hard() {
+        // two errors in RM(1, 3) are beyond hard decoding, but both are
+        // unreliable
+        let code = ReedMuller::new(1, 3);
+        let message = [false, true, true, false];
+        let codeword = code.encode(&message);
+        let mut llr: Vec<f64> = codeword
+            .iter()
+            .map(|&b| if b { -1.0 } else { 1.0 })
+            .collect();
+        llr[2] *= -0.1;
+        llr[5] *= -0.2;
+        assert_eq!(code.decode_first_order_soft(&llr), message);
+        assert_eq!(code.decode_recursive_soft(&llr), message);
+    }
+}
@@ -0,0 +1,295 @@
+//! # Reed–Solomon Codes
+//!
+//! Systematic encoding and errors-and-erasures decoding of narrow-sense or
+//! shifted Reed–Solomon codes over any [`Field`]. The generator polynomial is
+//! `prod (x - alpha^(first_root + i))` over the parity symbols, the form used
+//! by QR codes, CCSDS, compact discs and most other standards, which only
+//! differ in field, `alpha` and `first_root`.
+//!
+//! Codewords are slices with the highest power first, the message followed by
+//! the parity symbols.
+//!
+//! ```
+//! use gf::{reed_solomon::ReedSolomon, GF};
+//!
+//! let rs = ReedSolomon::new(4, GF(2u8), 0);
+//!
+//! let mut codeword = GF::from_slice(b"hello").to_vec();
+//! let parity = rs.encode(&codeword);
+//! codeword.extend(parity);
+//!
+//! codeword[1] = GF(0);
+//! codeword[6] = GF(0);
+//! assert_eq!(rs.decode(&mut codeword, &[]), Ok(2));
+//! assert_eq!(&codeword[..5], GF::from_slice(b"hello"));
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{Field, Poly};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    /// More errors and erasures than the parity symbols can correct.
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooManyErrors => "too many errors to correct".fmt(f),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ReedSolomon<F> {
+    alpha: F,
+    first_root: usize,
+    /// the generator polynomial, highest power first, without the leading one
+    /// and negated
+    feedback: Vec<F>,
+}
+
+impl<F: Field> ReedSolomon<F> {
+    /// A code with `parity` check symbols, where the codeword length must not
+    /// exceed the multiplicative order of `alpha`.
+    pub fn new(parity: usize, alpha: F, first_root: usize) -> Self {
+        let roots: Vec<F> = (0..parity).map(|i| alpha.pow(first_root + i)).collect();
+        let generator = Poly::from_roots(&roots);
+
+        let feedback = generator.coeffs()[..parity]
+            .iter()
+            .rev()
+            .map(|&c| -c)
+            .collect();
+        Self {
+            alpha,
+            first_root,
+            feedback,
+        }
+    }
+
+    #[inline]
+    pub fn parity(&self) -> usize {
+        self.feedback.len()
+    }
+
+    #[inline]
+    pub fn alpha(&self) -> F {
+        self.alpha
+    }
+
+    #[inline]
+    pub fn first_root(&self) -> usize {
+        self.first_root
+    }
+
+    /// `prod (x - alpha^(first_root + i))`
+    pub fn generator(&self) -> Poly<F> {
+        let mut coeffs: Vec<F> = self.feedback.iter().rev().map(|&c| -c).collect();
+        coeffs.push(F::ONE);
+        Poly::new(coeffs)
+    }
+
+    /// The parity symbols for `message`, minus the remainder of
+    /// `message * x^parity` divided by the generator.
+    pub fn encode(&self, message: &[F]) -> Vec<F> {
+        let mut rem = vec![F::ZERO; self.parity()];
+        if rem.is_empty() {
+            return rem;
+        }
+        for &m in message {
+            let factor = m + rem[0];
+            rem.rotate_left(1);
+            *rem.last_mut().unwrap() = F::ZERO;
+            F::mul_add_slice(&mut rem, &self.feedback, factor);
+        }
+        rem.iter().map(|&r| -r).collect()
+    }
+
+    /// `codeword(alpha^(first_root + i))` for every parity symbol, all zero
+    /// for a valid codeword.
+    pub fn syndromes(&self, codeword: &[F]) -> Vec<F> {
+        (0..self.parity())
+            .map(|i| {
+                let x = self.alpha.pow(self.first_root + i);
+                codeword.iter().fold(F::ZERO, |acc, &c| acc * x + c)
+            })
+            .collect()
+    }
+
+    /// Corrects `codeword` in place, given the indices of symbols known to be
+    /// wrong, and returns the number of symbols that changed.
+    ///
+    /// Up to `e` erasures and `v` errors are corrected as long as
+    /// `e + 2v <= parity`. On failure `codeword` is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an erasure index is out of range.
+    pub fn decode(&self, codeword: &mut [F], erasures: &[usize]) -> Result<usize, Error> {
+        let n = codeword.len();
+        let parity = self.parity();
+        assert!(erasures.iter().all(|&j| j < n), "erasure out of range");
+        if erasures.len() > parity {
+            return Err(Error::TooManyErrors);
+        }
+
+        let syndromes = self.syndromes(codeword);
+        if syndromes.iter().all(F::is_zero) {
+            return Ok(0);
+        }
+
+        // symbol j sits at the power n - 1 - j
+        let locator_of = |j: usize| self.alpha.pow(n - 1 - j);
+
+        let mut erasure_locator = Poly::one();
+        for &j in erasures {
+            erasure_locator *= Poly::new([F::ONE, -locator_of(j)]);
+        }
+
+        let locator = berlekamp_massey(&syndromes, erasure_locator, erasures.len());
+        let degree = locator.degree().unwrap_or(0);
+        if 2 * degree > parity + erasures.len() {
+            return Err(Error::TooManyErrors);
+        }
+
+        let alpha_inv = self.alpha.inv();
+        let positions: Vec<usize> = (0..n)
+            .filter(|&j| locator.eval(alpha_inv.pow(n - 1 - j)).is_zero())
+            .collect();
+        if positions.len() != degree {
+            return Err(Error::TooManyErrors);
+        }
+
+        // Forney: e_j = -X^(1 - first_root) Omega(X^-1) / Lambda'(X^-1)
+        let mut omega = (Poly::new(syndromes) * &locator).into_coeffs();
+        omega.truncate(parity);
+        let omega = Poly::new(omega);
+        let derivative = locator.derivative();
+
+        let mut corrections = Vec::with_capacity(positions.len());
+        for &j in &positions {
+            let x = locator_of(j);
+            let x_inv = x.inv();
+            let denom = derivative.eval(x_inv);
+            if denom.is_zero() {
+                return Err(Error::TooManyErrors);
+            }
+            let magnitude = -(x * x_inv.pow(self.first_root) * omega.eval(x_inv) / denom);
+            corrections.push((j, magnitude));
+        }
+
+        for &(j, e) in &corrections {
+            codeword[j] -= e;
+        }
+        if !self.syndromes(codeword).iter().all(F::is_zero) {
+            for &(j, e) in &corrections {
+                codeword[j] += e;
+            }
+            return Err(Error::TooManyErrors);
+        }
+        Ok(corrections.iter().filter(|(_, e)| !e.is_zero()).count())
+    }
+}
+
+/// The error-and-erasure locator for `syndromes`, starting from the locator
+/// of the `erasures` known positions.
+pub(crate) fn berlekamp_massey<F: Field>(
+    syndromes: &[F],
+    erasure_locator: Poly<F>,
+    erasures: usize,
+) -> Poly<F> {
+    let x = Poly::monomial(F::ONE, 1);
+    let mut locator = erasure_locator.clone();
+    let mut prev = erasure_locator;
+    let mut len = erasures;
+
+    for r in erasures..syndromes.len() {
+        let delta: F = (0..=len.min(r))
+            .map(|j| locator.coeff(j) * syndromes[r - j])
+            .sum();
+
+        if delta.is_zero() {
+            prev = &x * &prev;
+        } else if 2 * len <= r + erasures {
+            let next = &locator - &(&x * &prev).scale(delta);
+            prev = locator.scale(delta.inv());
+            len = r + 1 + erasures - len;
+            locator = next;
+        } else {
+            locator = &locator - &(&x * &prev).scale(delta);
+            prev = &x * &prev;
+        }
+    }
+    locator
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{Error, ReedSolomon};
+    use crate::{GFp, GF};
+
+    fn codeword<F: crate::Field>(rs: &ReedSolomon<F>, message: &[F]) -> Vec<F> {
+        let mut c = message.to_vec();
+        c.extend(rs.encode(message));
+        c
+    }
+
+    #[test]
+    fn generator_roots() {
+        let rs = ReedSolomon::new(10, GF(2u8), 0);
+        let g = rs.generator();
+        assert_eq!(g.degree(), Some(10));
+        for i in 0..10 {
+            assert_eq!(g.eval(GF(2).pow(i)), GF(0));
+        }
+    }
+
+    #[test]
+    fn errors_and_erasures() {
+        let rs = ReedSolomon::new(16, GF(2u8), 1);
+        let message: Vec<GF<u8>> = (0..100u8).map(|i| GF(i.wrapping_mul(3) ^ 1)).collect();
+        let original = codeword(&rs, &message);
+        assert!(rs.syndromes(&original).iter().all(|s| *s == GF(0)));
+
+        // 4 erasures and 6 errors, 4 + 2 * 6 = 16
+        let mut received = original.clone();
+        let erasures = [0, 50, 99, 115];
+        for &j in &erasures {
+            received[j] = GF(0x55);
+        }
+        for j in [3, 10, 60, 70, 108, 114] {
+            received[j] += GF(0xA7);
+        }
+        assert_eq!(rs.decode(&mut received, &erasures), Ok(10));
+        assert_eq!(received, original);
+
+        // one error too many leaves the codeword as it was
+        for j in [1, 2, 4, 5, 6, 7, 8, 9, 11] {
+            received[j] += GF(1);
+        }
+        let damaged = received.clone();
+        assert_eq!(rs.decode(&mut received, &[]), Err(Error::TooManyErrors));
+        assert_eq!(received, damaged);
+    }
+
+    #[test]
+    fn prime_field() {
+        type F = GFp<65537>;
+        let rs = ReedSolomon::new(6, F::new(3), 5);
+        let message: Vec<F> = (0..20u64).map(|i| F::new(i * i + 9)).collect();
+        let original = codeword(&rs, &message);
+
+        let mut received = original.clone();
+        received[2] += F::new(1234);
+        received[7] -= F::new(99);
+        received[21] = F::new(0);
+        assert_eq!(rs.decode(&mut received, &[21]), Ok(3));
+        assert_eq!(received, original);
+    }
+}
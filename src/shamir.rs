@@ -0,0 +1,472 @@
+//! # Shamir Secret Sharing
+//!
+//! Every element of the secret is the constant term of its own random
+//! polynomial of degree `threshold - 1`, and the share with index `x` holds
+//! the values of all of them at `x`. Any `threshold` shares determine the
+//! polynomials, fewer reveal nothing about the secret.
+//!
+//! Any [`ShareField`] works: bytes in [`GF<u8>`] for up to 255 shares,
+//! [`GF2m<0x1002D>`](GF2m) for up to 65535, or [`GF2_128`] and [`GFp`] to
+//! share a key as a single element.
+//!
+//! [`combine`] interpolates at zero and trusts every share it is given.
+//! [`combine_robust`] reads the values of all shares at one position as an
+//! [evaluation-form](crate::evaluation) Reed–Solomon codeword, so `n` shares
+//! outvote up to `(n - threshold) / 2` corrupted or malicious ones and name
+//! them. For a check that still holds beyond that, share the secret with
+//! [`append_digest`] and verify it with [`strip_digest`].
+//!
+//! ```
+//! use gf::{shamir, GF};
+//! use rand::{rngs::OsRng, RngCore};
+//!
+//! let secret = GF::from_slice(b"hello world");
+//! let mut shares = shamir::split(secret, 3, 7, |buf| OsRng.fill_bytes(buf));
+//! assert_eq!(shamir::combine(&shares[4..]).unwrap(), secret);
+//!
+//! shares[1].data[3] += GF(0x20);
+//! shares[5].data.fill(GF(0));
+//! let (combined, faulty) = shamir::combine_robust(&shares, 3).unwrap();
+//! assert_eq!(combined, secret);
+//! assert_eq!(faulty, [GF(2), GF(6)]);
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{
+    checksum::Sha256, evaluation::EvaluationCode, interpolate::Lagrange,
+    multipoint::SubproductTree, Field, GF2m, GFp, Poly, GF, GF2_128,
+};
+
+/// Bits of SHA-256 kept by [`append_digest`], rounded up to whole elements.
+pub const DIGEST_BITS: u32 = 64;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    /// No shares, or fewer than the threshold.
+    TooFewShares,
+    /// A share with index zero, which would be the secret itself, or two
+    /// shares with the same index.
+    InvalidIndex,
+    /// Shares of different lengths.
+    LengthMismatch,
+    /// More faulty shares than the others can outvote.
+    TooManyFaulty,
+    /// The secret does not match its digest.
+    DigestMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooFewShares => "not enough shares".fmt(f),
+            Error::InvalidIndex => "zero or duplicate share index".fmt(f),
+            Error::LengthMismatch => "shares differ in length".fmt(f),
+            Error::TooManyFaulty => "too many faulty shares to correct".fmt(f),
+            Error::DigestMismatch => "secret does not match its digest".fmt(f),
+        }
+    }
+}
+
+/// The field a set of shares lives in, as recorded by share encodings.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum FieldId {
+    /// `GF(2^degree)` modulo `x^degree + low`, with bit `i` of `low` the
+    /// coefficient of `x^i`.
+    Binary { degree: u32, low: u128 },
+    /// The integers modulo a prime.
+    Prime(u64),
+}
+
+/// A field to share secrets in.
+///
+/// Elements are encoded as their [index](Field::to_index), big-endian in
+/// the fewest bytes that hold all of them.
+pub trait ShareField: Field {
+    const ID: FieldId;
+}
+
+impl ShareField for GF<u8> {
+    const ID: FieldId = FieldId::Binary {
+        degree: 8,
+        low: 0x1D,
+    };
+}
+
+impl<const POLY: u32> ShareField for GF2m<POLY> {
+    const ID: FieldId = FieldId::Binary {
+        degree: Self::DEGREE,
+        low: (POLY ^ 1 << Self::DEGREE) as u128,
+    };
+}
+
+impl ShareField for GF2_128 {
+    const ID: FieldId = FieldId::Binary {
+        degree: 128,
+        low: GF2_128::POLY,
+    };
+}
+
+impl<const P: u64> ShareField for GFp<P> {
+    const ID: FieldId = FieldId::Prime(P);
+}
+
+/// Bytes per encoded element of `F`.
+pub(crate) fn element_len<F: Field>() -> usize {
+    (128 - F::ORDER.leading_zeros()).div_ceil(8) as usize
+}
+
+/// A uniformly random element, drawing bytes from `fill` until they encode
+/// one.
+fn random<F: Field>(fill: &mut impl FnMut(&mut [u8])) -> F {
+    let len = element_len::<F>();
+    let mask = u128::MAX >> F::ORDER.leading_zeros();
+    let mut buf = [0; 16];
+    loop {
+        fill(&mut buf[16 - len..]);
+        let i = u128::from_be_bytes(buf) & mask;
+        if i <= F::ORDER {
+            return F::from_index(i);
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Share<F> {
+    /// The x-coordinate, never zero.
+    pub index: F,
+    pub data: Vec<F>,
+}
+
+/// Splits `secret` into `count` shares with indices `1..=count`, any
+/// `threshold` of which recover it. `fill` provides the random coefficients
+/// and must be a cryptographically secure source.
+///
+/// # Panics
+///
+/// Panics unless `1 <= threshold <= count`, or if the field has fewer than
+/// `count` nonzero elements.
+pub fn split<F: ShareField>(
+    secret: &[F],
+    threshold: usize,
+    count: usize,
+    mut fill: impl FnMut(&mut [u8]),
+) -> Vec<Share<F>> {
+    assert!(
+        0 < threshold && threshold <= count,
+        "threshold out of range"
+    );
+    assert!(count as u128 <= F::ORDER, "more shares than field elements");
+
+    let xs: Vec<F> = (1..=count as u128).map(F::from_index).collect();
+    let tree = SubproductTree::new(&xs);
+
+    let mut shares: Vec<Share<F>> = xs
+        .iter()
+        .map(|&index| Share {
+            index,
+            data: Vec::with_capacity(secret.len()),
+        })
+        .collect();
+    for &s in secret {
+        let coeffs: Vec<F> = core::iter::once(s)
+            .chain((1..threshold).map(|_| random(&mut fill)))
+            .collect();
+        let ys = tree.evaluate(&Poly::new(coeffs));
+        for (share, y) in shares.iter_mut().zip(ys) {
+            share.data.push(y);
+        }
+    }
+    shares
+}
+
+/// The share indices, after checking that they are distinct and nonzero and
+/// that all shares have the same length.
+fn points<F: Field>(shares: &[Share<F>]) -> Result<Vec<F>, Error> {
+    let first = shares.first().ok_or(Error::TooFewShares)?;
+    if shares.iter().any(|s| s.data.len() != first.data.len()) {
+        return Err(Error::LengthMismatch);
+    }
+
+    let mut sorted: Vec<u128> = shares.iter().map(|s| s.index.to_index()).collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.len() != shares.len() || sorted[0] == 0 {
+        return Err(Error::InvalidIndex);
+    }
+    Ok(shares.iter().map(|s| s.index).collect())
+}
+
+/// Recovers the secret by interpolation at zero. Any set of at least
+/// `threshold` correct shares gives the secret, but nothing detects too few
+/// or corrupted shares.
+pub fn combine<F: ShareField>(shares: &[Share<F>]) -> Result<Vec<F>, Error> {
+    let xs = points(shares)?;
+
+    let mut secret = vec![F::ZERO; shares[0].data.len()];
+    Lagrange::at(&xs, F::ZERO).combine(shares.iter().map(|s| &s.data), &mut secret);
+    Ok(secret)
+}
+
+/// Recovers the secret from shares of which up to `(n - threshold) / 2` may
+/// be wrong, and returns it with the sorted indices of the faulty shares.
+///
+/// The first `threshold` shares are interpolated and checked against the
+/// rest, and only the positions where they disagree are decoded. A share
+/// counts as faulty as soon as one of its elements is wrong.
+pub fn combine_robust<F: ShareField>(
+    shares: &[Share<F>],
+    threshold: usize,
+) -> Result<(Vec<F>, Vec<F>), Error> {
+    let xs = points(shares)?;
+    if threshold == 0 || shares.len() < threshold {
+        return Err(Error::TooFewShares);
+    }
+    let code = EvaluationCode::new(&xs, threshold);
+
+    let len = shares[0].data.len();
+    let (base, rest) = shares.split_at(threshold);
+    let base_data = || base.iter().map(|s| &s.data);
+    let mut secret = vec![F::ZERO; len];
+    Lagrange::at(&xs[..threshold], F::ZERO).combine(base_data(), &mut secret);
+
+    let mut inconsistent = vec![false; len];
+    let mut expected = vec![F::ZERO; len];
+    for share in rest {
+        Lagrange::at(&xs[..threshold], share.index).combine(base_data(), &mut expected);
+        for ((bad, a), b) in inconsistent.iter_mut().zip(&expected).zip(&share.data) {
+            *bad |= a != b;
+        }
+    }
+
+    let mut faulty = vec![false; shares.len()];
+    for i in (0..len).filter(|&i| inconsistent[i]) {
+        let received: Vec<F> = shares.iter().map(|s| s.data[i]).collect();
+        let message = code
+            .decode_gao(&received)
+            .map_err(|_| Error::TooManyFaulty)?;
+        for ((f, a), b) in faulty.iter_mut().zip(code.encode(&message)).zip(received) {
+            *f |= a != b;
+        }
+        secret[i] = message[0];
+    }
+
+    let mut indices: Vec<F> = shares
+        .iter()
+        .zip(faulty)
+        .filter(|(_, f)| *f)
+        .map(|(s, _)| s.index)
+        .collect();
+    if indices.len() > code.radius() {
+        return Err(Error::TooManyFaulty);
+    }
+    indices.sort_unstable_by_key(|x| x.to_index());
+    Ok((secret, indices))
+}
+
+/// At least [`DIGEST_BITS`] bits of the SHA-256 of the encoded `secret`, as
+/// elements that each take as many bits as always fit.
+fn digest<F: Field>(secret: &[F]) -> Vec<F> {
+    let len = element_len::<F>();
+    let bytes: Vec<u8> = secret
+        .iter()
+        .flat_map(|x| x.to_index().to_be_bytes()[16 - len..].to_vec())
+        .collect();
+    let hash = Sha256::digest(&bytes);
+
+    let size_is_power = F::ORDER.checked_add(1).is_none_or(u128::is_power_of_two);
+    let bits = 128 - F::ORDER.leading_zeros() - u32::from(!size_is_power);
+    let mut pos = 0;
+    (0..DIGEST_BITS.div_ceil(bits))
+        .map(|_| {
+            let mut i = 0u128;
+            for _ in 0..bits {
+                i = i << 1 | (hash[pos / 8] >> (7 - pos % 8) & 1) as u128;
+                pos += 1;
+            }
+            F::from_index(i)
+        })
+        .collect()
+}
+
+/// `secret` followed by its digest, to be split in place of the secret. For
+/// bytes this is the first eight bytes of SHA-256.
+///
+/// The digest is shared along with the secret, so fewer than `threshold`
+/// shares still reveal nothing about either.
+pub fn append_digest<F: ShareField>(secret: &[F]) -> Vec<F> {
+    let mut data = secret.to_vec();
+    data.extend(digest(secret));
+    data
+}
+
+/// The secret from the result of combining shares of [`append_digest`]
+/// output, if its digest matches.
+pub fn strip_digest<F: ShareField>(data: &[F]) -> Result<&[F], Error> {
+    let len = digest::<F>(&[]).len();
+    if data.len() < len {
+        return Err(Error::DigestMismatch);
+    }
+    let (secret, tag) = data.split_at(data.len() - len);
+    if digest(secret) != tag {
+        return Err(Error::DigestMismatch);
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn any_threshold_subset() {
+        let secret = GF::from_slice(b"correct horse battery staple");
+        let mut rng = StdRng::seed_from_u64(1);
+        let shares = split(secret, 3, 5, |b| rng.fill_bytes(b));
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                    assert_eq!(combine(&subset).unwrap(), secret);
+                }
+            }
+        }
+        assert_ne!(combine(&shares[..2]).unwrap(), secret);
+
+        let once = split(secret, 1, 3, |b| rng.fill_bytes(b));
+        assert!(once.iter().all(|s| s.data == secret));
+    }
+
+    #[test]
+    fn invalid_sets() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut shares = split(GF::from_slice(b"abc"), 2, 4, |b| rng.fill_bytes(b));
+        assert_eq!(combine::<GF<u8>>(&[]), Err(Error::TooFewShares));
+        assert_eq!(combine_robust(&shares[..1], 2), Err(Error::TooFewShares));
+
+        let mut twice = shares.clone();
+        twice[1].index = twice[0].index;
+        assert_eq!(combine(&twice), Err(Error::InvalidIndex));
+
+        shares[2].data.pop();
+        assert_eq!(combine_robust(&shares, 2), Err(Error::LengthMismatch));
+    }
+
+    #[test]
+    fn robust() {
+        let secret = GF::from_slice(b"attack at dawn");
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut shares = split(secret, 4, 12, |b| rng.fill_bytes(b));
+        assert_eq!(combine_robust(&shares, 4), Ok((secret.to_vec(), vec![])));
+
+        // four cheaters, each with a different pattern, outvoted by eight
+        shares[0].data[0] += GF(1);
+        shares[3].data.reverse();
+        shares[7].data = shares[8].data.clone();
+        shares[11].data[13] *= GF(3);
+        assert_eq!(
+            combine_robust(&shares, 4),
+            Ok((secret.to_vec(), vec![GF(1), GF(4), GF(8), GF(12)]))
+        );
+
+        // only as long as they stay within the radius
+        shares[5].data[6] += GF(0x80);
+        assert_eq!(combine_robust(&shares[..11], 4), Err(Error::TooManyFaulty));
+
+        // a long secret with a single wrong element in one of the first
+        // `threshold` shares, which the interpolation starts from
+        let secret: Vec<u8> = (0..4096).map(|i| (i * 7) as u8).collect();
+        let mut shares = split(GF::from_slice(&secret), 5, 9, |b| rng.fill_bytes(b));
+        shares[1].data[2000] += GF(9);
+        let (combined, faulty) = combine_robust(&shares, 5).unwrap();
+        assert_eq!(combined, GF::from_slice(&secret));
+        assert_eq!(faulty, [GF(2)]);
+    }
+
+    #[test]
+    fn digest() {
+        let secret = GF::from_slice(b"key material");
+        let data = append_digest(secret);
+        assert_eq!(data.len(), 12 + 8);
+        assert_eq!(
+            &data[12..],
+            GF::from_slice(&Sha256::digest(b"key material")[..8])
+        );
+        assert_eq!(strip_digest(&data), Ok(secret));
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let shares = split(&data, 2, 3, |b| rng.fill_bytes(b));
+        let mut mixed = [shares[0].clone(), shares[2].clone()];
+        assert_eq!(strip_digest(&combine(&mixed).unwrap()), Ok(secret));
+
+        mixed[1].data[0] += GF(4);
+        assert_eq!(
+            strip_digest(&combine(&mixed).unwrap()),
+            Err(Error::DigestMismatch)
+        );
+        assert_eq!(
+            strip_digest(GF::from_slice(b"short")),
+            Err(Error::DigestMismatch)
+        );
+
+        // two 61-bit elements in a 62-bit prime field, one in GF(2^128)
+        type P = GFp<{ (1 << 62) - 57 }>;
+        assert_eq!(append_digest(&[P::new(5)]).len(), 3);
+        assert_eq!(append_digest(&[GF2_128(5)]).len(), 2);
+    }
+
+    #[test]
+    fn many_shares() {
+        // more shares than GF(256) has points
+        type F = GF2m<0x1002D>;
+        let secret: Vec<F> = [0xCAFE, 0xBABE, 0x1234].map(F::new).to_vec();
+        let mut rng = StdRng::seed_from_u64(6);
+        let mut shares = split(&secret, 100, 300, |b| rng.fill_bytes(b));
+        assert_eq!(shares[299].index, F::new(300));
+        assert_eq!(combine(&shares[150..250]).unwrap(), secret);
+
+        for i in (0..300).step_by(7) {
+            shares[i].data[i % 3] += F::new(1);
+        }
+        let (combined, faulty) = combine_robust(&shares, 100).unwrap();
+        assert_eq!(combined, secret);
+        assert_eq!(faulty.len(), 43);
+    }
+
+    #[test]
+    fn single_element_keys() {
+        let key = [GF2_128(0x000102030405060708090A0B0C0D0E0F)];
+        let mut rng = StdRng::seed_from_u64(7);
+        let shares = split(&key, 2, 3, |b| rng.fill_bytes(b));
+        assert_eq!(combine(&shares[1..]).unwrap(), key);
+
+        type Goldilocks = GFp<0xFFFF_FFFF_0000_0001>;
+        let key = [Goldilocks::new(0xFFFF_FFFF_0000_0000)];
+        let mut shares = split(&key, 3, 5, |b| rng.fill_bytes(b));
+        assert!(shares
+            .iter()
+            .all(|s| s.data[0].value() < 0xFFFF_FFFF_0000_0001));
+        shares[2].data[0] += Goldilocks::ONE;
+        assert_eq!(
+            combine_robust(&shares, 3),
+            Ok((key.to_vec(), vec![Goldilocks::new(3)]))
+        );
+    }
+
+    #[test]
+    fn field_ids() {
+        assert_eq!(<GF<u8>>::ID, <GF2m<0x11D>>::ID);
+        assert_ne!(<GF<u8>>::ID, <GF2m<0x11B>>::ID);
+        assert_eq!(
+            <GF2m<0x1002D>>::ID,
+            FieldId::Binary {
+                degree: 16,
+                low: 0x2D
+            }
+        );
+        assert_eq!(<GFp<65537>>::ID, FieldId::Prime(65537));
+    }
+}
@@ -0,0 +1,512 @@
+//! # Share Format
+//!
+//! A self-describing encoding of one [`Share`] together with everything
+//! needed to put it to use: format version, field, threshold, share index,
+//! secret length and a checksum. [`Record::to_bytes`] gives the compact
+//! binary form and [`Record::to_text`] the same bytes in Crockford base32,
+//! grouped for reading aloud or copying from paper.
+//!
+//! Version 1 is laid out as
+//!
+//! | field     | encoding                                                   |
+//! |-----------|------------------------------------------------------------|
+//! | version   | one byte, `1`                                              |
+//! | field     | `0`, degree, low terms for `GF(2^m)`, or `1`, prime for `GF(p)` |
+//! | threshold | number                                                     |
+//! | index     | element                                                    |
+//! | length    | number of secret elements                                  |
+//! | data      | `length` elements                                          |
+//! | checksum  | CRC-32 of everything before, four bytes big-endian         |
+//!
+//! where numbers are unsigned LEB128 and elements their
+//! [index](crate::Field::to_index) in the fewest big-endian bytes that hold
+//! every element of the field.
+//!
+//! ```
+//! use gf::{
+//!     shamir::{self, Share},
+//!     share_format::{self, Error, Record},
+//!     GF,
+//! };
+//!
+//! let share = Share {
+//!     index: GF(3u8),
+//!     data: GF::from_slice(b"secret").to_vec(),
+//! };
+//! let record = Record { threshold: 2, share };
+//!
+//! let text = record.to_text();
+//! assert_eq!(text, "0400-G782-0C37-6SB3-E9JQ-955R-4W1G");
+//! assert_eq!(Record::from_text(&text.to_lowercase()), Ok(record.clone()));
+//!
+//! let bytes = record.to_bytes();
+//! assert_eq!(share_format::field(&bytes), Ok(<GF<u8> as shamir::ShareField>::ID));
+//! assert_eq!(Record::<GF<u8>>::from_bytes(&bytes[..9]), Err(Error::ChecksumMismatch));
+//! assert_eq!(
+//!     Record::<GF<u8>>::from_text("0400-G782-0C37-6SB3-E9JQ-955R-4U1G"),
+//!     Err(Error::InvalidCharacter { position: 31, character: 'U' })
+//! );
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::{
+    checksum::crc32,
+    shamir::{element_len, FieldId, Share, ShareField},
+};
+
+/// The format version written by [`Record::to_bytes`].
+pub const VERSION: u8 = 1;
+
+const BINARY: u8 = 0;
+const PRIME: u8 = 1;
+
+/// Crockford's base32 alphabet, without `I`, `L`, `O` and `U`.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Characters per group in the text form.
+const GROUP: usize = 4;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    /// The input ends before the record does.
+    Truncated,
+    /// A format version this crate cannot read.
+    UnsupportedVersion(u8),
+    /// The checksum does not match, the record was mistyped or damaged.
+    ChecksumMismatch,
+    /// A field tag other than binary or prime, at byte `offset`.
+    UnknownField { offset: usize },
+    /// The record is for a different field than the one asked for.
+    FieldMismatch { expected: FieldId, found: FieldId },
+    /// A number longer than its type, at byte `offset`.
+    Overflow { offset: usize },
+    /// A threshold of zero.
+    InvalidThreshold,
+    /// An element out of range for the field, or a zero share index, at byte
+    /// `offset`.
+    InvalidElement { offset: usize },
+    /// Bytes after the checksum, starting at byte `offset`.
+    TrailingBytes { offset: usize },
+    /// A character outside the base32 alphabet, at char `position` of the
+    /// text.
+    InvalidCharacter { position: usize, character: char },
+    /// Base32 text whose last character carries set bits beyond the bytes.
+    InvalidPadding,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Truncated => "share record is truncated".fmt(f),
+            Error::UnsupportedVersion(v) => write!(f, "unsupported share format version {v}"),
+            Error::ChecksumMismatch => "share checksum mismatch".fmt(f),
+            Error::UnknownField { offset } => write!(f, "unknown field tag at byte {offset}"),
+            Error::FieldMismatch { expected, found } => {
+                write!(f, "share is over {found:?}, expected {expected:?}")
+            }
+            Error::Overflow { offset } => write!(f, "number too large at byte {offset}"),
+            Error::InvalidThreshold => "threshold of zero".fmt(f),
+            Error::InvalidElement { offset } => {
+                write!(f, "invalid field element at byte {offset}")
+            }
+            Error::TrailingBytes { offset } => write!(f, "trailing bytes from byte {offset}"),
+            Error::InvalidCharacter {
+                position,
+                character,
+            } => write!(f, "invalid character {character:?} at position {position}"),
+            Error::InvalidPadding => "nonzero padding bits in base32 text".fmt(f),
+        }
+    }
+}
+
+/// A share with the threshold of its set.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Record<F> {
+    pub threshold: usize,
+    pub share: Share<F>,
+}
+
+fn push_number(out: &mut Vec<u8>, mut n: u128) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/// Reads the fields of a record front to back, tracking the offset for
+/// errors.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], Error> {
+        let rest = &self.bytes[self.offset..];
+        if rest.len() < len {
+            return Err(Error::Truncated);
+        }
+        self.offset += len;
+        Ok(&rest[..len])
+    }
+
+    fn number(&mut self, bits: u32) -> Result<u128, Error> {
+        let start = self.offset;
+        let mut n = 0u128;
+        let mut shift = 0;
+        loop {
+            let b = self.take(1)?[0];
+            let digit = (b & 0x7F) as u128;
+            if shift >= bits || (bits - shift < 7 && digit >> (bits - shift) != 0) {
+                return Err(Error::Overflow { offset: start });
+            }
+            n |= digit << shift;
+            shift += 7;
+            if b & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+    }
+
+    fn field(&mut self) -> Result<FieldId, Error> {
+        let offset = self.offset;
+        match self.take(1)?[0] {
+            BINARY => {
+                let degree = self.number(32)? as u32;
+                let low = self.number(128)?;
+                Ok(FieldId::Binary { degree, low })
+            }
+            PRIME => Ok(FieldId::Prime(self.number(64)? as u64)),
+            _ => Err(Error::UnknownField { offset }),
+        }
+    }
+
+    fn element<F: ShareField>(&mut self) -> Result<F, Error> {
+        let offset = self.offset;
+        let bytes = self.take(element_len::<F>())?;
+        let i = bytes.iter().fold(0, |acc, &b| acc << 8 | b as u128);
+        if i > F::ORDER {
+            return Err(Error::InvalidElement { offset });
+        }
+        Ok(F::from_index(i))
+    }
+}
+
+/// Checks the version and returns a reader at the field.
+fn open(bytes: &[u8]) -> Result<Reader<'_>, Error> {
+    let (&version, _) = bytes.split_first().ok_or(Error::Truncated)?;
+    if version != VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+    Ok(Reader { bytes, offset: 1 })
+}
+
+/// The field of a binary record, to choose the type to decode it as.
+pub fn field(bytes: &[u8]) -> Result<FieldId, Error> {
+    open(bytes)?.field()
+}
+
+impl<F: ShareField> Record<F> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let len = element_len::<F>();
+        let mut out = Vec::with_capacity(16 + (self.share.data.len() + 1) * len);
+        out.push(VERSION);
+        match F::ID {
+            FieldId::Binary { degree, low } => {
+                out.push(BINARY);
+                push_number(&mut out, degree as u128);
+                push_number(&mut out, low);
+            }
+            FieldId::Prime(p) => {
+                out.push(PRIME);
+                push_number(&mut out, p as u128);
+            }
+        }
+        push_number(&mut out, self.threshold as u128);
+
+        let element = |out: &mut Vec<u8>, x: F| {
+            out.extend_from_slice(&x.to_index().to_be_bytes()[16 - len..]);
+        };
+        element(&mut out, self.share.index);
+        push_number(&mut out, self.share.data.len() as u128);
+        for &x in &self.share.data {
+            element(&mut out, x);
+        }
+
+        let crc = crc32(&out);
+        out.extend_from_slice(&crc.to_be_bytes());
+        out
+    }
+
+    /// Parses a binary record, which must be exactly one record long. The
+    /// checksum is verified before anything after the version is read, so a
+    /// typo shows up as [`Error::ChecksumMismatch`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        open(bytes)?;
+        let end = bytes.len().checked_sub(4).ok_or(Error::Truncated)?;
+        let crc = u32::from_be_bytes(bytes[end..].try_into().unwrap());
+        if crc != crc32(&bytes[..end]) {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        let bytes = &bytes[..end];
+        let mut reader = open(bytes)?;
+        let found = reader.field()?;
+        if found != F::ID {
+            return Err(Error::FieldMismatch {
+                expected: F::ID,
+                found,
+            });
+        }
+
+        let threshold = reader.number(usize::BITS)? as usize;
+        if threshold == 0 {
+            return Err(Error::InvalidThreshold);
+        }
+        let offset = reader.offset;
+        let index = reader.element::<F>()?;
+        if index.is_zero() {
+            return Err(Error::InvalidElement { offset });
+        }
+
+        let len = reader.number(usize::BITS)? as usize;
+        let element_len = element_len::<F>();
+        if len.saturating_mul(element_len) > bytes.len() - reader.offset {
+            return Err(Error::Truncated);
+        }
+        let data = (0..len)
+            .map(|_| reader.element::<F>())
+            .collect::<Result<Vec<F>, Error>>()?;
+
+        if reader.offset < bytes.len() {
+            return Err(Error::TrailingBytes {
+                offset: reader.offset,
+            });
+        }
+
+        Ok(Self {
+            threshold,
+            share: Share { index, data },
+        })
+    }
+
+    /// The binary record in base32, in groups of four characters joined by
+    /// dashes.
+    pub fn to_text(&self) -> String {
+        let bytes = self.to_bytes();
+        let mut text = String::with_capacity(bytes.len() * 2);
+        let (mut acc, mut bits) = (0u32, 0);
+        let mut count = 0;
+        let mut push = |text: &mut String, digit: u32| {
+            if count > 0 && count % GROUP == 0 {
+                text.push('-');
+            }
+            text.push(ALPHABET[digit as usize] as char);
+            count += 1;
+        };
+        for &b in &bytes {
+            acc = acc << 8 | b as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                push(&mut text, acc >> bits & 31);
+            }
+        }
+        if bits > 0 {
+            push(&mut text, acc << (5 - bits) & 31);
+        }
+        text
+    }
+
+    /// Parses the text form. Case, dashes and whitespace do not matter, and
+    /// `I` and `L` read as `1`, `O` as `0`.
+    pub fn from_text(text: &str) -> Result<Self, Error> {
+        let mut bytes = Vec::with_capacity(text.len() * 5 / 8);
+        let (mut acc, mut bits) = (0u32, 0);
+        for (position, character) in text.chars().enumerate() {
+            let digit = match character.to_ascii_uppercase() {
+                '-' => continue,
+                c if c.is_whitespace() => continue,
+                'I' | 'L' => 1,
+                'O' => 0,
+                c => match ALPHABET.iter().position(|&a| a as char == c) {
+                    Some(d) => d as u32,
+                    None => {
+                        return Err(Error::InvalidCharacter {
+                            position,
+                            character,
+                        })
+                    }
+                },
+            };
+            acc = (acc << 5 | digit) & 0xFFF;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((acc >> bits) as u8);
+            }
+        }
+        if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+            return Err(Error::InvalidPadding);
+        }
+        Self::from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    use crate::{shamir, GF2m, GFp, GF, GF2_128};
+
+    fn round_trip<F: ShareField>(secret: &[F], threshold: usize, count: usize) {
+        let mut rng = StdRng::seed_from_u64(1);
+        let shares = shamir::split(secret, threshold, count, |b| rng.fill_bytes(b));
+        let records: Vec<Record<F>> = shares
+            .into_iter()
+            .map(|share| Record { threshold, share })
+            .collect();
+        for record in &records {
+            let bytes = record.to_bytes();
+            assert_eq!(field(&bytes), Ok(F::ID));
+            assert_eq!(Record::from_bytes(&bytes).as_ref(), Ok(record));
+            assert_eq!(Record::from_text(&record.to_text()).as_ref(), Ok(record));
+        }
+
+        let decoded: Vec<Share<F>> = records
+            .iter()
+            .map(|r| Record::<F>::from_text(&r.to_text()).unwrap().share)
+            .collect();
+        assert_eq!(shamir::combine(&decoded[..threshold]).unwrap(), secret);
+    }
+
+    #[test]
+    fn fields() {
+        round_trip(GF::from_slice(b"bytes"), 2, 3);
+        round_trip(&[GF2m::<0x1002D>::new(0xABCD)], 3, 300);
+        round_trip(&[GF2m::<0b100101>::new(17)], 2, 5);
+        round_trip(&[GF2_128(u128::MAX - 1)], 2, 2);
+        round_trip(&[GFp::<{ (1 << 61) - 1 }>::new(12345)], 4, 7);
+        round_trip::<GF<u8>>(&[], 1, 1);
+    }
+
+    #[test]
+    fn layout() {
+        let record = Record {
+            threshold: 200,
+            share: Share {
+                index: GF2m::<0x1002D>::new(0x0102),
+                data: vec![GF2m::new(0xFFFF)],
+            },
+        };
+        let bytes = record.to_bytes();
+        assert_eq!(
+            bytes[..11],
+            [1, BINARY, 16, 0x2D, 0xC8, 0x01, 0x01, 0x02, 1, 0xFF, 0xFF]
+        );
+        assert_eq!(bytes.len(), 15);
+    }
+
+    #[test]
+    fn errors() {
+        type F = GF<u8>;
+        let record = Record {
+            threshold: 2,
+            share: Share {
+                index: GF(7u8),
+                data: vec![GF(1), GF(2), GF(3)],
+            },
+        };
+        let bytes = record.to_bytes();
+        let parse = |bytes: &[u8]| Record::<F>::from_bytes(bytes);
+        let with_crc = |mut body: Vec<u8>| {
+            let crc = crc32(&body);
+            body.extend_from_slice(&crc.to_be_bytes());
+            body
+        };
+
+        assert_eq!(parse(&[]), Err(Error::Truncated));
+        assert_eq!(parse(&[2, 0]), Err(Error::UnsupportedVersion(2)));
+        for len in 1..bytes.len() {
+            assert!(parse(&bytes[..len]).is_err());
+        }
+
+        let mut damaged = bytes.clone();
+        damaged[6] ^= 1;
+        assert_eq!(parse(&damaged), Err(Error::ChecksumMismatch));
+
+        let mut long = bytes.clone();
+        long.push(0);
+        assert_eq!(parse(&long), Err(Error::ChecksumMismatch));
+        let mut long = bytes[..bytes.len() - 4].to_vec();
+        long.push(0);
+        assert_eq!(
+            parse(&with_crc(long)),
+            Err(Error::TrailingBytes { offset: 10 })
+        );
+
+        assert_eq!(
+            parse(&with_crc(vec![1, 7])),
+            Err(Error::UnknownField { offset: 1 })
+        );
+        assert_eq!(
+            Record::<GF2m<0x11B>>::from_bytes(&bytes),
+            Err(Error::FieldMismatch {
+                expected: FieldId::Binary {
+                    degree: 8,
+                    low: 0x1B
+                },
+                found: F::ID,
+            })
+        );
+        assert_eq!(
+            parse(&with_crc(vec![1, 0, 8, 0x1D, 0, 7, 0])),
+            Err(Error::InvalidThreshold)
+        );
+        assert_eq!(
+            parse(&with_crc(vec![1, 0, 8, 0x1D, 2, 0, 0])),
+            Err(Error::InvalidElement { offset: 5 })
+        );
+        assert_eq!(
+            parse(&with_crc(vec![1, 0, 0x80, 0x80, 0x80, 0x80, 0x10])),
+            Err(Error::Overflow { offset: 2 })
+        );
+        assert_eq!(
+            Record::<GFp<251>>::from_bytes(&with_crc(vec![1, 1, 0xFB, 1, 2, 0xFC])),
+            Err(Error::InvalidElement { offset: 5 })
+        );
+        assert_eq!(
+            parse(&with_crc(vec![1, 0, 8, 0x1D, 2, 7, 0xFF, 0xFF, 0x03])),
+            Err(Error::Truncated)
+        );
+
+        // any one-character typo past the version, padding aside
+        let text: Vec<char> = record.to_text().chars().collect();
+        for i in 2..text.len() - 1 {
+            if text[i] == '-' {
+                continue;
+            }
+            for &c in ALPHABET {
+                let mut typo = text.clone();
+                typo[i] = c as char;
+                if typo != text {
+                    let typo: String = typo.into_iter().collect();
+                    assert_eq!(Record::<F>::from_text(&typo), Err(Error::ChecksumMismatch));
+                }
+            }
+        }
+        assert_eq!(
+            Record::<F>::from_text("0G0!"),
+            Err(Error::InvalidCharacter {
+                position: 3,
+                character: '!'
+            })
+        );
+        assert_eq!(Record::<F>::from_text("0G1"), Err(Error::InvalidPadding));
+    }
+}
@@ -0,0 +1,676 @@
+//! # ssss
+//!
+//! Splitting and combining compatible with B. Poettering's `ssss-split` and
+//! `ssss-combine`. Arithmetic is in GF(2^n) for any multiple `n` of 8 from 8
+//! to 1024, the security level, reduced by the pentanomial ssss lists for
+//! that degree. As in ssss the secret is the constant term of a polynomial
+//! with leading term `x^t`, and share `i` is its value at `i`, printed as
+//! `[token-]index-hex` with the index zero-padded to the width of the share
+//! count.
+//!
+//! Unless turned off with `-D`, ssss first scrambles secrets of 64 bits or
+//! more with XTEA rounds under an all-zero key; [`split`] and [`combine`]
+//! take the same switch. Given the random bytes `ssss-split` reads from
+//! `/dev/urandom`, [`split`] prints the same shares.
+//!
+//! ```
+//! use gf::ssss;
+//! use rand::{rngs::OsRng, RngCore};
+//!
+//! let fill = |buf: &mut [u8]| OsRng.fill_bytes(buf);
+//!
+//! // ssss-split -t 3 -n 5 -w vault
+//! let shares = ssss::split(b"my password", 3, 5, None, Some("vault"), true, fill).unwrap();
+//! assert!(shares[0].starts_with("vault-1-"));
+//! assert_eq!(shares[0].len(), 8 + 22);
+//! assert_eq!(ssss::combine(&shares[1..4], 3, true).unwrap(), b"my password");
+//! assert_eq!(
+//!     ssss::combine(&[&shares[0], &shares[0]], 2, true),
+//!     Err(ssss::Error::DuplicateIndex)
+//! );
+//! ```
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt;
+
+/// The errors of `ssss-split` and `ssss-combine`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    InvalidThreshold,
+    InvalidShareCount,
+    InvalidSecurityLevel,
+    SecretTooLong,
+    InvalidShare,
+    LengthMismatch,
+    TooFewShares,
+    DuplicateIndex,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidThreshold => "invalid threshold value".fmt(f),
+            Error::InvalidShareCount => "invalid number of shares".fmt(f),
+            Error::InvalidSecurityLevel => "security level invalid".fmt(f),
+            Error::SecretTooLong => "input string too long".fmt(f),
+            Error::InvalidShare => "invalid share".fmt(f),
+            Error::LengthMismatch => "shares have different security levels".fmt(f),
+            Error::TooFewShares => "fewer shares than the threshold".fmt(f),
+            Error::DuplicateIndex => "shares inconsistent, perhaps one was used twice".fmt(f),
+        }
+    }
+}
+
+/// The exponents `a > b > c` of `x^n + x^a + x^b + x^c + 1` for
+/// `n = 8, 16, ..., 1024`, the `irred_coeff` table of ssss.
+#[rustfmt::skip]
+const IRREDUCIBLE: [[u8; 3]; 128] = [
+    [4, 3, 1], [5, 3, 1], [4, 3, 1], [7, 3, 2], [5, 4, 3], [5, 3, 2], [7, 4, 2], [4, 3, 1],
+    [10, 9, 3], [9, 4, 2], [7, 6, 2], [10, 9, 6], [4, 3, 1], [5, 4, 3], [4, 3, 1], [7, 2, 1],
+    [5, 3, 2], [7, 4, 2], [6, 3, 2], [5, 3, 2], [15, 3, 2], [11, 3, 2], [9, 8, 7], [7, 2, 1],
+    [5, 3, 2], [9, 3, 1], [7, 3, 1], [9, 8, 3], [9, 4, 2], [8, 5, 3], [15, 14, 10], [10, 5, 2],
+    [9, 6, 2], [9, 3, 2], [9, 5, 2], [11, 10, 1], [7, 3, 2], [11, 2, 1], [9, 7, 4], [4, 3, 1],
+    [8, 3, 1], [7, 4, 1], [7, 2, 1], [13, 11, 6], [5, 3, 2], [7, 3, 2], [8, 7, 5], [12, 3, 2],
+    [13, 10, 6], [5, 3, 2], [5, 3, 2], [9, 5, 2], [9, 7, 2], [13, 4, 3], [4, 3, 1], [11, 6, 4],
+    [18, 9, 6], [19, 18, 13], [11, 3, 2], [15, 9, 6], [4, 3, 1], [16, 5, 2], [15, 14, 6], [8, 5, 2],
+    [15, 11, 2], [11, 6, 2], [7, 5, 3], [8, 3, 1], [19, 16, 9], [11, 9, 6], [15, 7, 6], [13, 4, 3],
+    [14, 13, 3], [13, 6, 3], [9, 5, 2], [19, 13, 6], [19, 10, 3], [11, 6, 5], [9, 2, 1], [14, 3, 2],
+    [13, 3, 1], [7, 5, 4], [11, 9, 8], [11, 6, 5], [23, 16, 9], [19, 14, 6], [23, 10, 2], [8, 3, 2],
+    [5, 4, 3], [9, 6, 4], [4, 3, 2], [13, 8, 6], [13, 11, 1], [13, 10, 3], [11, 6, 5], [19, 17, 4],
+    [15, 14, 7], [13, 9, 6], [9, 7, 3], [9, 7, 1], [14, 3, 2], [11, 8, 2], [11, 6, 4], [13, 5, 2],
+    [11, 5, 1], [11, 4, 1], [19, 10, 3], [21, 10, 6], [13, 3, 1], [15, 7, 5], [19, 18, 10], [7, 5, 3],
+    [12, 7, 2], [7, 5, 1], [14, 9, 6], [10, 3, 2], [15, 13, 12], [12, 11, 9], [16, 9, 7], [12, 9, 3],
+    [9, 5, 2], [17, 10, 6], [24, 9, 3], [17, 15, 13], [5, 4, 3], [19, 17, 8], [15, 6, 3], [19, 6, 1],
+];
+
+const MAX_DEGREE: usize = 1024;
+const LIMBS: usize = MAX_DEGREE / 64 + 1;
+
+/// A polynomial over GF(2) of degree at most 1024, bit `i` being the
+/// coefficient of `x^i`: an element of the field or its modulus.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Element([u64; LIMBS]);
+
+impl Element {
+    const ZERO: Self = Element([0; LIMBS]);
+
+    fn small(v: u64) -> Self {
+        let mut e = Self::ZERO;
+        e.0[0] = v;
+        e
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        self.0[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    fn bit_len(&self) -> usize {
+        (0..LIMBS)
+            .rev()
+            .find(|&i| self.0[i] != 0)
+            .map_or(0, |i| 64 * i + 64 - self.0[i].leading_zeros() as usize)
+    }
+
+    fn add(mut self, other: &Self) -> Self {
+        for (a, b) in self.0.iter_mut().zip(&other.0) {
+            *a ^= b;
+        }
+        self
+    }
+
+    /// `self += other * x^shift`, which must fit.
+    fn add_shifted(&mut self, other: &Self, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+        for i in (words..LIMBS).rev() {
+            let mut w = other.0[i - words] << bits;
+            if bits != 0 && i > words {
+                w |= other.0[i - words - 1] >> (64 - bits);
+            }
+            self.0[i] ^= w;
+        }
+    }
+
+    /// Byte `i` counting from the least significant.
+    fn byte(&self, i: usize) -> u8 {
+        (self.0[i / 8] >> (8 * (i % 8))) as u8
+    }
+
+    fn set_byte(&mut self, i: usize, b: u8) {
+        let shift = 8 * (i % 8);
+        self.0[i / 8] = self.0[i / 8] & !(0xff << shift) | (b as u64) << shift;
+    }
+
+    fn from_be_bytes(bytes: &[u8]) -> Self {
+        let mut e = Self::ZERO;
+        for (i, &b) in bytes.iter().rev().enumerate() {
+            e.set_byte(i, b);
+        }
+        e
+    }
+
+    fn to_be_bytes(self, len: usize) -> Vec<u8> {
+        (0..len).rev().map(|i| self.byte(i)).collect()
+    }
+}
+
+/// GF(2^degree) reduced by the polynomial ssss uses for that degree.
+struct BinaryField {
+    degree: usize,
+    modulus: Element,
+}
+
+impl BinaryField {
+    fn new(degree: usize) -> Option<Self> {
+        if !(8..=MAX_DEGREE).contains(&degree) || !degree.is_multiple_of(8) {
+            return None;
+        }
+        let mut modulus = Element::small(1);
+        for e in IRREDUCIBLE[degree / 8 - 1] {
+            modulus.0[0] |= 1 << e;
+        }
+        modulus.0[degree / 64] |= 1 << (degree % 64);
+        Some(BinaryField { degree, modulus })
+    }
+
+    fn mul_x(&self, mut a: Element) -> Element {
+        for i in (1..LIMBS).rev() {
+            a.0[i] = a.0[i] << 1 | a.0[i - 1] >> 63;
+        }
+        a.0[0] <<= 1;
+        if a.bit(self.degree) {
+            a = a.add(&self.modulus);
+        }
+        a
+    }
+
+    fn mul(&self, a: &Element, b: &Element) -> Element {
+        let mut r = Element::ZERO;
+        for i in (0..b.bit_len()).rev() {
+            r = self.mul_x(r);
+            if b.bit(i) {
+                r = r.add(a);
+            }
+        }
+        r
+    }
+
+    /// The inverse of a nonzero `a`, by the extended Euclidean algorithm.
+    fn inv(&self, a: &Element) -> Element {
+        let (mut u, mut v) = (*a, self.modulus);
+        let (mut g, mut h) = (Element::small(1), Element::ZERO);
+        while u.bit_len() > 1 {
+            if u.bit_len() < v.bit_len() {
+                core::mem::swap(&mut u, &mut v);
+                core::mem::swap(&mut g, &mut h);
+            }
+            let shift = u.bit_len() - v.bit_len();
+            u.add_shifted(&v, shift);
+            g.add_shifted(&h, shift);
+        }
+        g
+    }
+}
+
+const DELTA: u32 = 0x9e37_79b9;
+
+/// 32 rounds of XTEA with an all-zero key.
+fn encipher(v: &mut [u32; 2]) {
+    let mut sum = 0u32;
+    for _ in 0..32 {
+        v[0] = v[0].wrapping_add(((v[1] << 4 ^ v[1] >> 5).wrapping_add(v[1])) ^ sum);
+        sum = sum.wrapping_add(DELTA);
+        v[1] = v[1].wrapping_add(((v[0] << 4 ^ v[0] >> 5).wrapping_add(v[0])) ^ sum);
+    }
+}
+
+fn decipher(v: &mut [u32; 2]) {
+    let mut sum = DELTA.wrapping_mul(32);
+    for _ in 0..32 {
+        v[1] = v[1].wrapping_sub(((v[0] << 4 ^ v[0] >> 5).wrapping_add(v[0])) ^ sum);
+        sum = sum.wrapping_sub(DELTA);
+        v[0] = v[0].wrapping_sub(((v[1] << 4 ^ v[1] >> 5).wrapping_add(v[1])) ^ sum);
+    }
+}
+
+/// The diffusion layer of ssss, or its inverse: XTEA over the 8 bytes at
+/// every even offset of the secret, wrapping around, 40 times over.
+///
+/// ssss lays the secret out as 16-bit big-endian words, least significant
+/// word first, and for an odd number of bytes moves the lone byte of the
+/// top word down so the bytes are contiguous.
+fn diffuse(x: &Element, degree: usize, forward: bool) -> Element {
+    let len = degree / 8;
+    let mut v = vec![0; (degree + 8) / 16 * 2];
+    for k in 0..v.len() / 2 {
+        v[2 * k] = x.byte(2 * k + 1);
+        v[2 * k + 1] = x.byte(2 * k);
+    }
+    if degree % 16 == 8 {
+        v[len - 1] = v[len];
+    }
+
+    let mut slice = |i: usize, block: fn(&mut [u32; 2])| {
+        let mut w = [0; 2];
+        for (j, w) in w.iter_mut().enumerate() {
+            *w = (0..4).fold(0, |w, k| w << 8 | v[(i + 4 * j + k) % len] as u32);
+        }
+        block(&mut w);
+        for (j, w) in w.iter().enumerate() {
+            for k in 0..4 {
+                v[(i + 4 * j + k) % len] = (w >> (24 - 8 * k)) as u8;
+            }
+        }
+    };
+    if forward {
+        (0..40 * len).step_by(2).for_each(|i| slice(i, encipher));
+    } else {
+        (0..40 * len)
+            .step_by(2)
+            .rev()
+            .for_each(|i| slice(i, decipher));
+    }
+
+    if degree % 16 == 8 {
+        v[len] = v[len - 1];
+        v[len - 1] = 0;
+    }
+    let mut y = Element::ZERO;
+    for k in 0..v.len() / 2 {
+        y.set_byte(2 * k + 1, v[2 * k]);
+        y.set_byte(2 * k, v[2 * k + 1]);
+    }
+    y
+}
+
+/// Splits `secret` into `shares` shares, any `threshold` of which recover
+/// it, as `ssss-split -t threshold -n shares`.
+///
+/// `security` is the field degree in bits (`-s`), by default eight per
+/// byte of the secret, `token` prefixes every share (`-w`), and `diffusion`
+/// is true unless `-D` is given. `fill` provides the coefficients and must
+/// be a cryptographically secure source. A secret typed in hex (`-x`) is
+/// passed here as the bytes it spells.
+pub fn split(
+    secret: &[u8],
+    threshold: usize,
+    shares: usize,
+    security: Option<usize>,
+    token: Option<&str>,
+    diffusion: bool,
+    mut fill: impl FnMut(&mut [u8]),
+) -> Result<Vec<String>, Error> {
+    if threshold < 2 {
+        return Err(Error::InvalidThreshold);
+    }
+    if shares < threshold {
+        return Err(Error::InvalidShareCount);
+    }
+    let field = BinaryField::new(security.unwrap_or(8 * secret.len()))
+        .ok_or(Error::InvalidSecurityLevel)?;
+    let len = field.degree / 8;
+    if secret.len() > len {
+        return Err(Error::SecretTooLong);
+    }
+    if field.degree < 64 && shares >> field.degree != 0 {
+        return Err(Error::InvalidShareCount);
+    }
+
+    let mut coeffs = vec![Element::from_be_bytes(secret)];
+    if diffusion && field.degree >= 64 {
+        coeffs[0] = diffuse(&coeffs[0], field.degree, true);
+    }
+    let mut buf = vec![0; len];
+    for _ in 1..threshold {
+        fill(&mut buf);
+        coeffs.push(Element::from_be_bytes(&buf));
+    }
+
+    let prefix = token.map_or(String::new(), |t| format!("{t}-"));
+    let width = format!("{shares}").len();
+    let out = (1..=shares)
+        .map(|i| {
+            // x^t + c_{t-1} x^{t-1} + ... + c_0, by Horner's rule
+            let x = Element::small(i as u64);
+            let mut y = x;
+            for c in coeffs[1..].iter().rev() {
+                y = field.mul(&y.add(c), &x);
+            }
+            y = y.add(&coeffs[0]);
+            let hex: String = y
+                .to_be_bytes(len)
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            format!("{prefix}{i:0width$}-{hex}")
+        })
+        .collect();
+    Ok(out)
+}
+
+/// Parses `[token-]index-hex` into the index and the hex digits.
+fn parse(share: &str) -> Result<(u64, &str), Error> {
+    let parts: Vec<&str> = share.trim().splitn(3, '-').collect();
+    let (index, value) = match parts[..] {
+        [_, index, value] | [index, value] => (index, value),
+        _ => return Err(Error::InvalidShare),
+    };
+    let index = index.parse::<u64>().map_err(|_| Error::InvalidShare)?;
+    if index == 0 || value.is_empty() || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(Error::InvalidShare);
+    }
+    Ok((index, value))
+}
+
+/// Recovers the secret from the first `threshold` of `shares`, made by
+/// `ssss-split` or [`split`], as `ssss-combine -t threshold`. The secret
+/// fills the whole field, so `ssss-combine` without `-x` prints it with
+/// leading zero bytes dropped. As in ssss, too few or corrupted shares are
+/// not detected and give a wrong secret.
+pub fn combine<S: AsRef<str>>(
+    shares: &[S],
+    threshold: usize,
+    diffusion: bool,
+) -> Result<Vec<u8>, Error> {
+    if threshold < 2 {
+        return Err(Error::InvalidThreshold);
+    }
+    if shares.len() < threshold {
+        return Err(Error::TooFewShares);
+    }
+    let parsed = shares[..threshold]
+        .iter()
+        .map(|s| parse(s.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let digits = parsed[0].1.len();
+    let field = BinaryField::new(4 * digits).ok_or(Error::InvalidShare)?;
+    if parsed.iter().any(|(_, v)| v.len() != digits) {
+        return Err(Error::LengthMismatch);
+    }
+
+    let mut xs = Vec::with_capacity(threshold);
+    let mut ys = Vec::with_capacity(threshold);
+    for &(index, value) in &parsed {
+        if field.degree < 64 && index >> field.degree != 0 {
+            return Err(Error::InvalidShare);
+        }
+        let x = Element::small(index);
+        if xs.contains(&x) {
+            return Err(Error::DuplicateIndex);
+        }
+        let bytes: Vec<u8> = (0..digits)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect();
+        // remove the leading term so the rest interpolates to the secret
+        let mut y = Element::from_be_bytes(&bytes);
+        let mut power = Element::small(1);
+        for _ in 0..threshold {
+            power = field.mul(&power, &x);
+        }
+        y = y.add(&power);
+        xs.push(x);
+        ys.push(y);
+    }
+
+    // Lagrange at zero: the sum of y_i prod_{j != i} x_j / (x_j - x_i)
+    let mut secret = Element::ZERO;
+    for (i, (xi, yi)) in xs.iter().zip(&ys).enumerate() {
+        let (mut num, mut den) = (Element::small(1), Element::small(1));
+        for (j, xj) in xs.iter().enumerate() {
+            if j != i {
+                num = field.mul(&num, xj);
+                den = field.mul(&den, &xj.add(xi));
+            }
+        }
+        let weight = field.mul(&num, &field.inv(&den));
+        secret = secret.add(&field.mul(yi, &weight));
+    }
+    if diffusion && field.degree >= 64 {
+        secret = diffuse(&secret, field.degree, false);
+    }
+    Ok(secret.to_be_bytes(field.degree / 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GF2m;
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    /// Fixed bytes standing in for `/dev/urandom`, replayed from the start by
+    /// every split of the vectors.
+    fn urandom() -> impl FnMut(&mut [u8]) {
+        let bytes =
+            "2101c54fd1d01ab22574cb378aaef5b10808911933b9eb4ff229a5e4db3e57140128e0f4fae27e07f11a";
+        let mut pos = 0;
+        move |buf| {
+            for b in buf {
+                *b = u8::from_str_radix(&bytes[pos..pos + 2], 16).unwrap();
+                pos += 2;
+            }
+        }
+    }
+
+    fn random(field: &BinaryField, rng: &mut StdRng) -> Element {
+        let mut buf = vec![0; field.degree / 8];
+        rng.fill_bytes(&mut buf);
+        Element::from_be_bytes(&buf)
+    }
+
+    #[test]
+    fn arithmetic() {
+        let f = BinaryField::new(8).unwrap();
+        for a in 0..=255u16 {
+            for b in 0..=255u16 {
+                let product = f.mul(&Element::small(a as u64), &Element::small(b as u64));
+                let expected = GF2m::<0x11B>::new(a) * GF2m::<0x11B>::new(b);
+                assert_eq!(product, Element::small(expected.0 as u64));
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(5);
+        for degree in [16, 72, 128, 520, 1024] {
+            let f = BinaryField::new(degree).unwrap();
+            for _ in 0..4 {
+                let (a, b, c) = (
+                    random(&f, &mut rng),
+                    random(&f, &mut rng),
+                    random(&f, &mut rng),
+                );
+                assert_eq!(f.mul(&a, &b), f.mul(&b, &a));
+                assert_eq!(f.mul(&f.mul(&a, &b), &c), f.mul(&a, &f.mul(&b, &c)));
+                assert_eq!(f.mul(&a, &b.add(&c)), f.mul(&a, &b).add(&f.mul(&a, &c)));
+                assert_eq!(f.mul(&a, &f.inv(&a)), Element::small(1));
+            }
+        }
+        assert!(BinaryField::new(0).is_none());
+        assert!(BinaryField::new(12).is_none());
+        assert!(BinaryField::new(1032).is_none());
+    }
+
+    #[test]
+    fn diffusion() {
+        let mut rng = StdRng::seed_from_u64(6);
+        for degree in [64, 72, 88, 256, 1024] {
+            let f = BinaryField::new(degree).unwrap();
+            let x = random(&f, &mut rng);
+            let y = diffuse(&x, degree, true);
+            assert_ne!(x, y);
+            assert!(y.bit_len() <= degree);
+            assert_eq!(diffuse(&y, degree, false), x);
+        }
+    }
+
+    #[test]
+    fn vectors() {
+        // from a transcription of ssss.c fed the same random bytes: plain,
+        // with a token, below 64 bits, an odd number of bytes, with -D, above
+        // 128 bits and hex secrets (-x); not output of the ssss binaries
+        assert_eq!(
+            split(b"hello", 2, 3, None, None, true, urandom()).unwrap(),
+            ["1-4964a923bf", "2-2a66e6f3c9", "3-0b6723bc19"]
+        );
+        let shares = split(b"Tyrannosaurus", 3, 12, None, Some("dino"), true, urandom()).unwrap();
+        assert_eq!(shares[0], "dino-01-966d99f4cb3e1e97887f4010a3");
+        assert_eq!(shares[9], "dino-10-552ecb70395dd5c3e8d1c845b1");
+        assert_eq!(shares[11], "dino-12-c7a7807076e87d9a4ef2b21f09");
+        assert_eq!(combine(&shares[4..], 3, true).unwrap(), b"Tyrannosaurus");
+        assert_ne!(combine(&shares[4..], 3, false).unwrap(), b"Tyrannosaurus");
+
+        assert_eq!(
+            split(b"abc", 2, 2, Some(24), None, false, urandom()).unwrap(),
+            ["1-4063a7", "2-2361ed"]
+        );
+        let shares = split(b"odd bytes", 2, 4, Some(72), None, true, urandom()).unwrap();
+        assert_eq!(
+            shares,
+            [
+                "1-f2e9a5f9e6db3b06fa",
+                "2-91ebea2994ab14d090",
+                "3-b0ea2f66457b0e62b4",
+                "4-57ef7589704b4b7c5a",
+            ]
+        );
+        assert_eq!(
+            combine(&[&shares[3], &shares[1]], 2, true).unwrap(),
+            b"odd bytes"
+        );
+
+        let shares = [
+            "1-0c3c8d81f6d4fdc9f908b6d82958a3ed4b7afbae22",
+            "2-3178b26b0b96a5a8519eaf853dc89a2f38e551b016",
+            "3-a99238d6f3e35bc14abd70b39f869b64d293c75838",
+            "4-8e65ed21d3bf24db928905222fa23bcca6ce53b359",
+            "5-168f679c2bcadab289aada148dec3a874cb8c55b65",
+        ];
+        assert_eq!(
+            split(b"correct horse battery", 3, 5, None, None, true, urandom()).unwrap(),
+            shares
+        );
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let chosen: Vec<&str> = subset.iter().map(|&i| shares[i]).collect();
+            assert_eq!(combine(&chosen, 3, true).unwrap(), b"correct horse battery");
+        }
+
+        let shares = [
+            "1-516da426bfa47fca5154b852e9dc90c4",
+            "2-326febf6cdd4501c3ec9e50a762f8e12",
+            "3-136e2eb91c044aae1bbd2e3dfc817ba2",
+        ];
+        assert_eq!(
+            split(b"plaintext secret", 2, 3, None, None, false, urandom()).unwrap(),
+            shares
+        );
+        assert_eq!(
+            combine(&shares[1..], 2, false).unwrap(),
+            b"plaintext secret"
+        );
+
+        // -x 00c0ffee0123456789abcdeffedcba98, the second time with -s 160
+        let secret = [
+            0x00, 0xc0, 0xff, 0xee, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc,
+            0xba, 0x98,
+        ];
+        let shares = split(&secret, 3, 4, None, Some("hex"), true, urandom()).unwrap();
+        assert_eq!(
+            shares,
+            [
+                "hex-1-352bd99bea904bd37ea18df85922a3b0",
+                "hex-2-7e03433665be22752bb3e2d77116b62e",
+                "hex-3-570a176087d7d388fcee8c042086148c",
+                "hex-4-18ac8961742764191eb591b891eca6d0",
+            ]
+        );
+        assert_eq!(combine(&shares[1..], 3, true).unwrap(), secret);
+        let shares = split(&secret, 2, 3, Some(160), None, true, urandom()).unwrap();
+        assert_eq!(
+            shares,
+            [
+                "1-2930276b4cf6e2453fb541de4603a9ccb704bb7d",
+                "2-4a3268bb3e86cd9350281c86d9f0b71faf1d0853",
+                "3-6b33adf4ef56d721755cd7b1535e42aea715994b",
+            ]
+        );
+        let recovered = combine(&shares[1..], 2, true).unwrap();
+        assert_eq!(recovered[..4], [0; 4]);
+        assert_eq!(recovered[4..], secret);
+    }
+
+    #[test]
+    fn large_field() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let fill = |b: &mut [u8]| rng.fill_bytes(b);
+        let shares = split(b"x", 4, 6, Some(1024), Some("big"), true, fill).unwrap();
+        assert_eq!(shares[0].len(), 6 + 256);
+        assert!(shares[5].starts_with("big-6-"));
+        let secret = combine(&shares[2..], 4, true).unwrap();
+        assert_eq!(secret.len(), 128);
+        assert!(secret[..127].iter().all(|&b| b == 0));
+        assert_eq!(secret[127], b'x');
+    }
+
+    #[test]
+    fn errors() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut fill = |b: &mut [u8]| rng.fill_bytes(b);
+        assert_eq!(
+            split(b"s", 1, 3, None, None, true, &mut fill),
+            Err(Error::InvalidThreshold)
+        );
+        assert_eq!(
+            split(b"s", 3, 2, None, None, true, &mut fill),
+            Err(Error::InvalidShareCount)
+        );
+        assert_eq!(
+            split(b"s", 2, 256, None, None, true, &mut fill),
+            Err(Error::InvalidShareCount)
+        );
+        assert_eq!(
+            split(b"", 2, 3, None, None, true, &mut fill),
+            Err(Error::InvalidSecurityLevel)
+        );
+        assert_eq!(
+            split(b"s", 2, 3, Some(12), None, true, &mut fill),
+            Err(Error::InvalidSecurityLevel)
+        );
+        assert_eq!(
+            split(b"secret", 2, 3, Some(40), None, true, &mut fill),
+            Err(Error::SecretTooLong)
+        );
+
+        assert_eq!(
+            combine(&["1-4964a923bf"], 2, true),
+            Err(Error::TooFewShares)
+        );
+        assert_eq!(
+            combine(&["1-4964a923bf", "2-2a66e6f3c9"], 1, true),
+            Err(Error::InvalidThreshold)
+        );
+        for bad in ["1", "0-2a66e6f3c9", "x-2a66e6f3c9", "2-2a66e6f3cg", "2-"] {
+            assert_eq!(
+                combine(&["1-4964a923bf", bad], 2, true),
+                Err(Error::InvalidShare)
+            );
+        }
+        assert_eq!(
+            combine(&["1-4964a923bf", "2-2a66e6f3"], 2, true),
+            Err(Error::LengthMismatch)
+        );
+        assert_eq!(
+            combine(&["1-4964a923bf", "2-2a66e6f3c"], 2, true),
+            Err(Error::LengthMismatch)
+        );
+        assert_eq!(
+            combine(&["1-4964a923b", "2-2a66e6f3c"], 2, true),
+            Err(Error::InvalidShare)
+        );
+        assert_eq!(
+            combine(&["1-4964a923bf", "t-1-2a66e6f3c9"], 2, true),
+            Err(Error::DuplicateIndex)
+        );
+        assert_eq!(
+            combine(&["300-49", "2-2a"], 2, true),
+            Err(Error::InvalidShare)
+        );
+    }
+}
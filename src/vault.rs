@@ -0,0 +1,325 @@
+//! # HashiCorp Vault Shamir
+//!
+//! Splitting and combining compatible with the `shamir` package of
+//! HashiCorp Vault, which makes its unseal keys. Arithmetic is in GF(2^8)
+//! with the AES polynomial `0x11B`, and every part is the share bytes
+//! followed by its x-coordinate, a distinct random value in `1..=255`.
+//!
+//! Only [`split`] involves randomness, so any parts from Vault combine here
+//! and any parts from here combine in Vault.
+//!
+//! ```
+//! use gf::vault;
+//! use rand::{rngs::OsRng, RngCore};
+//!
+//! let fill = |buf: &mut [u8]| OsRng.fill_bytes(buf);
+//!
+//! let parts = vault::split(b"root key", 5, 3, fill).unwrap();
+//! assert_eq!(parts[0].len(), 9);
+//! assert_eq!(vault::combine(&parts[1..4]).unwrap(), b"root key");
+//! assert_eq!(
+//!     vault::combine(&[&parts[0], &parts[0]]),
+//!     Err(vault::Error::DuplicatePart)
+//! );
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{interpolate::Lagrange, multipoint::SubproductTree, GF2m, Poly};
+
+type F = GF2m<0x11B>;
+
+/// The errors of Vault's `Split` and `Combine`, in the same order.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Error {
+    PartsLessThanThreshold,
+    TooManyParts,
+    ThresholdTooSmall,
+    ThresholdTooLarge,
+    EmptySecret,
+    TooFewParts,
+    PartsTooShort,
+    LengthMismatch,
+    DuplicatePart,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::PartsLessThanThreshold => "parts cannot be less than threshold".fmt(f),
+            Error::TooManyParts => "parts cannot exceed 255".fmt(f),
+            Error::ThresholdTooSmall => "threshold must be at least 2".fmt(f),
+            Error::ThresholdTooLarge => "threshold cannot exceed 255".fmt(f),
+            Error::EmptySecret => "cannot split an empty secret".fmt(f),
+            Error::TooFewParts => {
+                "less than two parts cannot be used to reconstruct the secret".fmt(f)
+            }
+            Error::PartsTooShort => "parts must be at least two bytes".fmt(f),
+            Error::LengthMismatch => "all parts must be the same length".fmt(f),
+            Error::DuplicatePart => "duplicate part detected".fmt(f),
+        }
+    }
+}
+
+/// A uniform random number below `n <= 256`, by rejection.
+fn below(n: usize, fill: &mut impl FnMut(&mut [u8])) -> usize {
+    let limit = 256 - 256 % n;
+    let mut b = [0];
+    loop {
+        fill(&mut b);
+        if (b[0] as usize) < limit {
+            return b[0] as usize % n;
+        }
+    }
+}
+
+/// Splits `secret` into `parts` parts, any `threshold` of which recover it.
+/// `fill` provides the x-coordinates and coefficients and must be a
+/// cryptographically secure source.
+pub fn split(
+    secret: &[u8],
+    parts: usize,
+    threshold: usize,
+    mut fill: impl FnMut(&mut [u8]),
+) -> Result<Vec<Vec<u8>>, Error> {
+    if parts < threshold {
+        return Err(Error::PartsLessThanThreshold);
+    }
+    if parts > 255 {
+        return Err(Error::TooManyParts);
+    }
+    if threshold < 2 {
+        return Err(Error::ThresholdTooSmall);
+    }
+    if threshold > 255 {
+        return Err(Error::ThresholdTooLarge);
+    }
+    if secret.is_empty() {
+        return Err(Error::EmptySecret);
+    }
+
+    // the first `parts` entries of a random permutation of 1..=255
+    let mut xs: Vec<u8> = (1..=255).collect();
+    for i in 0..parts {
+        let j = i + below(255 - i, &mut fill);
+        xs.swap(i, j);
+    }
+    xs.truncate(parts);
+
+    let points: Vec<F> = xs.iter().map(|&x| F::new(x as u16)).collect();
+    let tree = SubproductTree::new(&points);
+
+    let mut out: Vec<Vec<u8>> = vec![Vec::with_capacity(secret.len() + 1); parts];
+    let mut coeffs = vec![0; threshold];
+    for &byte in secret {
+        fill(&mut coeffs[1..]);
+        coeffs[0] = byte;
+        let poly = Poly::new(coeffs.iter().map(|&c| F::new(c as u16)).collect::<Vec<_>>());
+        for (part, y) in out.iter_mut().zip(tree.evaluate(&poly)) {
+            part.push(y.0 as u8);
+        }
+    }
+    for (part, x) in out.iter_mut().zip(xs) {
+        part.push(x);
+    }
+    Ok(out)
+}
+
+/// Recovers the secret from parts made by Vault or [`split`]. As in Vault,
+/// too few or corrupted parts are not detected and give a wrong secret.
+pub fn combine<P: AsRef<[u8]>>(parts: &[P]) -> Result<Vec<u8>, Error> {
+    if parts.len() < 2 {
+        return Err(Error::TooFewParts);
+    }
+    let len = parts[0].as_ref().len();
+    if len < 2 {
+        return Err(Error::PartsTooShort);
+    }
+    if parts.iter().any(|p| p.as_ref().len() != len) {
+        return Err(Error::LengthMismatch);
+    }
+
+    let mut seen = [false; 256];
+    let mut xs = Vec::with_capacity(parts.len());
+    for part in parts {
+        let x = part.as_ref()[len - 1];
+        if seen[x as usize] {
+            return Err(Error::DuplicatePart);
+        }
+        seen[x as usize] = true;
+        xs.push(F::new(x as u16));
+    }
+
+    let ys: Vec<Vec<F>> = parts
+        .iter()
+        .map(|p| {
+            p.as_ref()[..len - 1]
+                .iter()
+                .map(|&y| F::new(y as u16))
+                .collect()
+        })
+        .collect();
+    let mut secret = vec![F::ZERO; len - 1];
+    Lagrange::at(&xs, F::ZERO).combine(&ys, &mut secret);
+    Ok(secret.into_iter().map(|y| y.0 as u8).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    use super::*;
+
+    /// `mult` of Vault's `shamir.go`.
+    fn go_mult(a: u8, b: u8) -> u8 {
+        let mut r: u8 = 0;
+        for i in (0..8).rev() {
+            r = (((b >> i) & 1).wrapping_neg() & a)
+                ^ ((r >> 7).wrapping_neg() & 0x1B)
+                ^ r.wrapping_add(r);
+        }
+        r
+    }
+
+    /// `Combine` of Vault's `shamir.go` with its `interpolatePolynomial`,
+    /// `div` and `inverse`, for parts already checked.
+    fn go_combine(parts: &[&[u8]]) -> Vec<u8> {
+        let inverse = |a: u8| {
+            let mut b = go_mult(a, a);
+            let mut c = go_mult(a, b);
+            b = go_mult(c, c);
+            b = go_mult(b, b);
+            c = go_mult(b, c);
+            b = go_mult(b, b);
+            b = go_mult(b, b);
+            b = go_mult(b, c);
+            b = go_mult(b, b);
+            b = go_mult(a, b);
+            go_mult(b, b)
+        };
+        let div = |a: u8, b: u8| if a == 0 { 0 } else { go_mult(a, inverse(b)) };
+
+        let len = parts[0].len();
+        let xs: Vec<u8> = parts.iter().map(|p| p[len - 1]).collect();
+        (0..len - 1)
+            .map(|idx| {
+                let mut result = 0;
+                for (i, part) in parts.iter().enumerate() {
+                    let mut basis = 1;
+                    for j in 0..parts.len() {
+                        if i != j {
+                            basis = go_mult(basis, div(xs[j], xs[i] ^ xs[j]));
+                        }
+                    }
+                    result ^= go_mult(part[idx], basis);
+                }
+                result
+            })
+            .collect()
+    }
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn same_field() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!(go_mult(a, b), (F::new(a as u16) * F::new(b as u16)).0 as u8);
+            }
+        }
+    }
+
+    #[test]
+    fn parts_in_vault_layout() {
+        // "vault unseal key", threshold 3, from a transcription of Vault's
+        // makePolynomial and evaluate with fixed coefficients
+        let parts = [
+            "3c581df6d19af20b974778992bc825a09c",
+            "b4ce1ad4e20fcfeba13ad54fcfa0a77012",
+            "79afe0aa5096b335fb118e35477b96f0e1",
+            "e3c3ac54c22df2a88b07f9ad4ce803d101",
+            "abca14a3a93553b0fe0027e5d3eda193ff",
+        ]
+        .map(hex);
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let chosen: Vec<&Vec<u8>> = subset.iter().map(|&i| &parts[i]).collect();
+            assert_eq!(combine(&chosen).unwrap(), b"vault unseal key");
+        }
+        assert_eq!(combine(&parts).unwrap(), b"vault unseal key");
+        assert_ne!(combine(&parts[..2]).unwrap(), b"vault unseal key");
+    }
+
+    #[test]
+    fn split_fixture() {
+        // our split with StdRng seed 9; shamir.Combine of Vault should return
+        // "vault unseal key" for any two of these parts
+        let expected = [
+            "60f193a1d104e64d62153a616141d73c88",
+            "45e1c34d160027bfb1ec7f4768b27a124a",
+            "2b6d4921ed2363a6e56114fb60e55ee8dc",
+        ]
+        .map(hex);
+        let mut rng = StdRng::seed_from_u64(9);
+        let parts = split(b"vault unseal key", 3, 2, |b| rng.fill_bytes(b)).unwrap();
+        assert_eq!(parts, expected);
+        for subset in [[0, 1], [2, 0], [1, 2]] {
+            let chosen: Vec<&[u8]> = subset.iter().map(|&i| &parts[i][..]).collect();
+            assert_eq!(combine(&chosen).unwrap(), b"vault unseal key");
+        }
+    }
+
+    #[test]
+    fn same_as_vault_combine() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let secret: Vec<u8> = (0..64).map(|i| i * 3).collect();
+        let mut parts = split(&secret, 12, 5, |b| rng.fill_bytes(b)).unwrap();
+        parts[11][9] ^= 0x40;
+        // enough, too few, and with a corrupted part, all give the same bytes
+        for range in [0..5, 3..11, 7..12, 0..4, 10..12, 2..12] {
+            let chosen: Vec<&[u8]> = parts[range].iter().map(Vec::as_slice).collect();
+            assert_eq!(combine(&chosen).unwrap(), go_combine(&chosen));
+        }
+    }
+
+    #[test]
+    fn split_combine() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let secret: Vec<u8> = (0..=255).collect();
+        let parts = split(&secret, 255, 200, |b| rng.fill_bytes(b)).unwrap();
+        let mut xs: Vec<u8> = parts.iter().map(|p| p[256]).collect();
+        xs.sort_unstable();
+        assert_eq!(xs, (1..=255).collect::<Vec<u8>>());
+        assert_eq!(combine(&parts[55..]).unwrap(), secret);
+
+        let parts = split(b"x", 2, 2, |b| rng.fill_bytes(b)).unwrap();
+        assert_ne!(parts[0][1], parts[1][1]);
+        assert_eq!(combine(&parts).unwrap(), b"x");
+    }
+
+    #[test]
+    fn errors() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut fill = |b: &mut [u8]| rng.fill_bytes(b);
+        assert_eq!(
+            split(b"s", 2, 3, &mut fill),
+            Err(Error::PartsLessThanThreshold)
+        );
+        assert_eq!(split(b"s", 256, 3, &mut fill), Err(Error::TooManyParts));
+        assert_eq!(split(b"s", 3, 1, &mut fill), Err(Error::ThresholdTooSmall));
+        assert_eq!(split(b"", 3, 2, &mut fill), Err(Error::EmptySecret));
+
+        assert_eq!(combine(&[[1, 2]]), Err(Error::TooFewParts));
+        assert_eq!(combine(&[[1], [2]]), Err(Error::PartsTooShort));
+        assert_eq!(
+            combine(&[&[1, 2][..], &[1, 2, 3]]),
+            Err(Error::LengthMismatch)
+        );
+        assert_eq!(combine(&[[1, 2], [3, 2]]), Err(Error::DuplicatePart));
+    }
+}